@@ -0,0 +1,271 @@
+/*
+Anti-nuke protection
+
+Watches the audit log for destructive actions (deleting channels and roles, banning and kicking members, creating
+webhooks) and counts them per user. If a single user does too many of the same kind of action within the guild's
+interval, every role that gives them a dangerous permission is taken away, the owner is DMed, and a report of
+everything they did is posted to the logs channel.
+
+The owner, the bot itself and any trusted users are never checked.
+*/
+
+pub mod error;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{
+    self, Action, AuditLogEntry, ChannelAction, CreateEmbed, CreateMessage, GuildId, MemberAction,
+    Permissions, RoleAction, RoleId, UserId, WebhookAction,
+};
+use tokio::sync::Mutex;
+
+use crate::colors;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+// Actions are only remembered for this long, which keeps the history small no matter how busy the guild is.
+pub const MAX_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// Any role with one of these gets taken away from whoever trips anti-nuke.
+const DANGEROUS_PERMISSIONS: Permissions = Permissions::ADMINISTRATOR
+    .union(Permissions::MANAGE_GUILD)
+    .union(Permissions::MANAGE_ROLES)
+    .union(Permissions::MANAGE_CHANNELS)
+    .union(Permissions::MANAGE_WEBHOOKS)
+    .union(Permissions::BAN_MEMBERS)
+    .union(Permissions::KICK_MEMBERS);
+
+/// The anti-nuke settings of a guild.
+/// A user trips anti-nuke once they do `threshold` actions of the same kind within `interval`.
+#[derive(Copy, Clone, Debug)]
+pub struct AntinukeSettings {
+    pub threshold: u32,
+    pub interval: Duration,
+}
+
+/// The kinds of destructive actions that are counted.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum NukeAction {
+    ChannelDelete,
+    RoleDelete,
+    Ban,
+    Kick,
+    WebhookCreate,
+}
+
+impl NukeAction {
+    fn from_audit_log(action: &Action) -> Option<Self> {
+        match action {
+            Action::Channel(ChannelAction::Delete) => Some(NukeAction::ChannelDelete),
+            Action::Role(RoleAction::Delete) => Some(NukeAction::RoleDelete),
+            Action::Member(MemberAction::BanAdd) => Some(NukeAction::Ban),
+            Action::Member(MemberAction::Kick) => Some(NukeAction::Kick),
+            Action::Webhook(WebhookAction::Create) => Some(NukeAction::WebhookCreate),
+            _ => None,
+        }
+    }
+
+    fn describe(&self, target_id: Option<u64>) -> String {
+        let target = target_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| String::from("unknown"));
+
+        match self {
+            NukeAction::ChannelDelete => format!("Deleted channel `{}`", target),
+            NukeAction::RoleDelete => format!("Deleted role `{}`", target),
+            NukeAction::Ban => format!("Banned <@{}>", target),
+            NukeAction::Kick => format!("Kicked <@{}>", target),
+            NukeAction::WebhookCreate => format!("Created webhook `{}`", target),
+        }
+    }
+}
+
+impl std::fmt::Display for NukeAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_string = match self {
+            NukeAction::ChannelDelete => "channel deletions",
+            NukeAction::RoleDelete => "role deletions",
+            NukeAction::Ban => "bans",
+            NukeAction::Kick => "kicks",
+            NukeAction::WebhookCreate => "webhook creations",
+        };
+        write!(f, "{}", action_string)
+    }
+}
+
+struct HistoryEntry {
+    time: Instant,
+    action: NukeAction,
+    description: String,
+}
+
+#[derive(Default)]
+pub struct AntinukeState {
+    history: Mutex<HashMap<(GuildId, UserId), VecDeque<HistoryEntry>>>,
+}
+
+/// Gets the owner of a guild, preferably from the cache.
+pub async fn guild_owner(
+    ctx: &serenity_prelude::Context,
+    guild_id: GuildId,
+) -> Result<UserId, Error> {
+    if let Some(owner_id) = ctx.cache.guild(guild_id).map(|guild| guild.owner_id) {
+        return Ok(owner_id);
+    }
+
+    Ok(guild_id.to_partial_guild(ctx).await?.owner_id)
+}
+
+pub async fn audit_log_entry(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    entry: &AuditLogEntry,
+) -> Result<(), Error> {
+    let action = match NukeAction::from_audit_log(&entry.action) {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+
+    let settings = match database::get_antinuke_settings(&data.database, guild_id).await? {
+        Some(settings) => settings,
+        None => return Ok(()),
+    };
+
+    let user_id = entry.user_id;
+    if user_id == ctx.cache.current_user().id
+        || user_id == guild_owner(ctx, guild_id).await?
+        || database::get_antinuke_trusted_users(&data.database, guild_id)
+            .await?
+            .contains(&user_id)
+    {
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let incident: Vec<HistoryEntry> = {
+        let mut history = data.antinuke.history.lock().await;
+        let user_history = history.entry((guild_id, user_id)).or_default();
+        user_history.push_back(HistoryEntry {
+            time: now,
+            action,
+            description: action.describe(entry.target_id.map(|id| id.get())),
+        });
+        while let Some(oldest) = user_history.front() {
+            if now.duration_since(oldest.time) <= settings.interval {
+                break;
+            }
+            user_history.pop_front();
+        }
+
+        let count = user_history
+            .iter()
+            .filter(|past| past.action == action)
+            .count();
+        if count < settings.threshold as usize {
+            return Ok(());
+        }
+
+        // Starting over means the same user won't trip anti-nuke again for every action after this one
+        history
+            .remove(&(guild_id, user_id))
+            .map(Vec::from)
+            .unwrap_or_default()
+    };
+
+    respond(ctx, data, guild_id, user_id, action, &settings, incident).await
+}
+
+async fn respond(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    action: NukeAction,
+    settings: &AntinukeSettings,
+    incident: Vec<HistoryEntry>,
+) -> Result<(), Error> {
+    let mut removed: Vec<RoleId> = Vec::new();
+    let mut failed: Vec<RoleId> = Vec::new();
+
+    // The user might have left (or been kicked by someone else) already
+    if let Ok(member) = guild_id.member(ctx, user_id).await {
+        let roles = guild_id.roles(ctx).await?;
+        for role_id in &member.roles {
+            let role = match roles.get(role_id) {
+                Some(role) => role,
+                None => continue,
+            };
+            if !role.permissions.intersects(DANGEROUS_PERMISSIONS) {
+                continue;
+            }
+
+            // Roles managed by an integration (like a bot's own role) can't be taken away
+            if role.managed || member.remove_role(ctx, *role_id).await.is_err() {
+                failed.push(*role_id);
+            } else {
+                removed.push(*role_id);
+            }
+        }
+    }
+
+    let count = incident.iter().filter(|past| past.action == action).count();
+    let actions = incident
+        .iter()
+        .map(|past| format!("- {}", past.description))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let report = CreateEmbed::default()
+        .color(colors::RED)
+        .title("ANTI-NUKE")
+        .description(format!(
+            "<@{}> made {} {} within {}.",
+            user_id,
+            count,
+            action,
+            humantime::format_duration(settings.interval)
+        ))
+        .field("Recent Actions:", messages::truncate_field(&actions), false)
+        .field("Roles Removed:", role_list(&removed), true)
+        .field("Could Not Remove:", role_list(&failed), true);
+
+    messages::send_log(ctx, &data.database, guild_id, report.clone()).await?;
+
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| String::from("your server"));
+    let owner_id = guild_owner(ctx, guild_id).await?;
+    let dm = match owner_id.create_dm_channel(ctx).await {
+        Ok(channel) => channel
+            .send_message(
+                ctx,
+                CreateMessage::default()
+                    .content(format!("Anti-nuke was triggered in **{}**!", guild_name))
+                    .embed(report),
+            )
+            .await
+            .map(|_| ()),
+        Err(error) => Err(error),
+    };
+
+    if dm.is_err() {
+        println!("Failed to DM the owner of {} about anti-nuke", guild_id);
+    }
+
+    Ok(())
+}
+
+fn role_list(roles: &[RoleId]) -> String {
+    if roles.is_empty() {
+        return String::from("None");
+    }
+
+    roles
+        .iter()
+        .map(|role_id| format!("<@&{}>", role_id))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
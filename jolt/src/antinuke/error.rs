@@ -0,0 +1,22 @@
+/// Errors relating to anti-nuke protection.
+#[derive(thiserror::Error, Debug)]
+pub enum AntinukeError {
+    /// An error representing someone other than the owner trying to change anti-nuke settings.
+    /// Admins are who anti-nuke protects against, so only the owner is allowed to touch it.
+    #[error("Only the server owner can configure anti-nuke protection!")]
+    NotOwner,
+    /// An error representing a command that needs anti-nuke protection, but it isn't enabled in the guild.
+    #[error("Anti-nuke protection is not enabled!")]
+    NotEnabled,
+    /// An error representing a threshold of zero, which would trip on every action.
+    #[error("Thresholds must be above 0!")]
+    ZeroThreshold,
+    /// An error representing an interval that is too long to keep track of.
+    /// Contains one value, which is the longest interval that is allowed.
+    #[error("Intervals can be at most {}", humantime::format_duration(*.0))]
+    IntervalTooLong(std::time::Duration),
+    /// An error representing a user that isn't trusted.
+    /// Contains one value, which is the ID of the user.
+    #[error("User <@{0}> is not trusted!")]
+    UserNotTrusted(u64),
+}
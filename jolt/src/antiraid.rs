@@ -0,0 +1,503 @@
+/*
+Anti-raid protection
+
+Keeps track of how fast members are joining each guild. Once too many join too quickly, raid mode starts: the
+verification level is raised, the configured channels are locked for @everyone, and everyone who joined during the
+spike (along with anyone who joins after it) is kicked or quarantined. Moderators get an alert in the logs channel
+with buttons to end raid mode, which can also ban every account that got flagged along the way.
+
+Raid mode only lives in memory. If the bot restarts during a raid, the verification level and channel locks have to
+be undone by hand.
+*/
+
+pub mod age_gate;
+pub mod error;
+pub mod types;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{
+    self, ButtonStyle, ChannelId, CreateActionRow, CreateButton, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditGuild, GuildId,
+    Interaction, Member, PermissionOverwrite, PermissionOverwriteType, Permissions, RoleId,
+    Timestamp, UserId, VerificationLevel,
+};
+use tokio::sync::Mutex;
+
+use crate::colors;
+use crate::commands::moderation::types::ModerationType;
+use crate::database;
+use crate::error::{AntiraidError, Error};
+use crate::messages;
+
+use types::{RaidAction, RaidSettings};
+
+// Joins are only remembered for this long, which keeps the join history small no matter how big the guild is.
+pub const MAX_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// What @everyone loses in locked channels.
+const LOCKED_PERMISSIONS: Permissions = Permissions::SEND_MESSAGES
+    .union(Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(Permissions::CREATE_PUBLIC_THREADS)
+    .union(Permissions::ADD_REACTIONS);
+
+const END_BUTTON: &str = "antiraid_end";
+const END_AND_BAN_BUTTON: &str = "antiraid_end_ban";
+
+const RAID_REASON: &str = "Joined during a raid";
+
+#[derive(Default)]
+pub struct AntiraidState {
+    joins: Mutex<HashMap<GuildId, VecDeque<(Instant, UserId)>>>,
+    raids: Mutex<HashMap<GuildId, Raid>>,
+}
+
+/// A raid in progress, along with everything needed to undo raid mode once it's over.
+struct Raid {
+    started_at: Timestamp,
+    // Only set if the verification level actually had to be raised
+    previous_verification_level: Option<VerificationLevel>,
+    // Each locked channel along with the @everyone overwrite it had before it was locked
+    locked_channels: Vec<(ChannelId, Option<PermissionOverwrite>)>,
+    flagged: Vec<UserId>,
+}
+
+// The @everyone role shares its ID with the guild
+fn everyone(guild_id: GuildId) -> PermissionOverwriteType {
+    PermissionOverwriteType::Role(RoleId::new(guild_id.get()))
+}
+
+/// Gets when the current raid in a guild started and how many accounts have been flagged so far.
+pub async fn raid_status(data: &crate::Data, guild_id: GuildId) -> Option<(Timestamp, usize)> {
+    data.antiraid
+        .raids
+        .lock()
+        .await
+        .get(&guild_id)
+        .map(|raid| (raid.started_at, raid.flagged.len()))
+}
+
+pub async fn member_join(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<(), crate::DynError> {
+    let guild_id = member.guild_id;
+    if member.user.bot {
+        return Ok(());
+    }
+
+    // Members caught by the age gate still count towards raid detection, but don't get acted on twice
+    let gated = age_gate::check(ctx, data, member).await?;
+
+    let settings = match database::get_antiraid_settings(&data.database, guild_id).await? {
+        Some(settings) => settings,
+        None => return Ok(()),
+    };
+
+    let now = Instant::now();
+    let recent_joins: Vec<UserId> = {
+        let mut joins = data.antiraid.joins.lock().await;
+        let window = joins.entry(guild_id).or_default();
+        window.push_back((now, member.user.id));
+        while let Some((joined_at, _)) = window.front() {
+            if now.duration_since(*joined_at) <= settings.interval {
+                break;
+            }
+            window.pop_front();
+        }
+
+        window.iter().map(|(_, user_id)| *user_id).collect()
+    };
+
+    let mut raids = data.antiraid.raids.lock().await;
+    let mut flagged = if let Some(raid) = raids.get_mut(&guild_id) {
+        raid.flagged.push(member.user.id);
+        vec![member.user.id]
+    } else if recent_joins.len() >= settings.threshold as usize {
+        let trigger = format!(
+            "{} members joined within {}.",
+            recent_joins.len(),
+            humantime::format_duration(settings.interval)
+        );
+        let raid = start_raid(
+            ctx,
+            data,
+            guild_id,
+            &settings,
+            recent_joins.clone(),
+            &trigger,
+        )
+        .await?;
+        raids.insert(guild_id, raid);
+        data.antiraid.joins.lock().await.remove(&guild_id);
+
+        // Everyone that set off raid mode gets flagged too, not just whoever joined last
+        recent_joins
+    } else {
+        return Ok(());
+    };
+    drop(raids);
+
+    if gated {
+        flagged.retain(|user_id| *user_id != member.user.id);
+    }
+
+    for user_id in flagged {
+        if let Err(error) = act_on_joiner(ctx, data, guild_id, user_id, settings.action).await {
+            println!(
+                "Failed to {} user {} during a raid: {}",
+                settings.action.to_string().to_lowercase(),
+                user_id,
+                error
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Starts raid mode by hand.
+pub async fn begin_raid(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    moderator_id: UserId,
+) -> Result<(), Error> {
+    let settings = database::get_antiraid_settings(&data.database, guild_id)
+        .await?
+        .ok_or(AntiraidError::NotEnabled)?;
+
+    let mut raids = data.antiraid.raids.lock().await;
+    if raids.contains_key(&guild_id) {
+        return Err(AntiraidError::RaidAlreadyActive.into());
+    }
+
+    let trigger = format!("Raid mode was started by <@{}>.", moderator_id);
+    let raid = start_raid(ctx, data, guild_id, &settings, Vec::new(), &trigger).await?;
+    raids.insert(guild_id, raid);
+
+    Ok(())
+}
+
+async fn start_raid(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    settings: &RaidSettings,
+    flagged: Vec<UserId>,
+    trigger: &str,
+) -> Result<Raid, Error> {
+    let current_level = guild_id.to_partial_guild(ctx).await?.verification_level;
+    let target_level: VerificationLevel = settings.verification_level.into();
+    let previous_verification_level = if u8::from(current_level) < u8::from(target_level) {
+        guild_id
+            .edit(
+                ctx,
+                EditGuild::default()
+                    .verification_level(target_level)
+                    .audit_log_reason("Raid mode started"),
+            )
+            .await?;
+        Some(current_level)
+    } else {
+        None
+    };
+
+    let channels = guild_id.channels(ctx).await?;
+    let mut locked_channels = Vec::new();
+    for channel_id in database::get_raid_lock_channels(&data.database, guild_id).await? {
+        // The channel might have been deleted since it was added
+        let channel = match channels.get(&channel_id) {
+            Some(channel) => channel,
+            None => continue,
+        };
+
+        let previous = channel
+            .permission_overwrites
+            .iter()
+            .find(|overwrite| overwrite.kind == everyone(guild_id))
+            .cloned();
+        let mut overwrite = previous.clone().unwrap_or(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::empty(),
+            kind: everyone(guild_id),
+        });
+        overwrite.allow.remove(LOCKED_PERMISSIONS);
+        overwrite.deny.insert(LOCKED_PERMISSIONS);
+
+        match channel_id.create_permission(ctx, overwrite).await {
+            Ok(()) => locked_channels.push((channel_id, previous)),
+            Err(error) => println!(
+                "Failed to lock channel {} during a raid: {}",
+                channel_id, error
+            ),
+        }
+    }
+
+    messages::send_log_message(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateMessage::default()
+            .content("@here")
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::RED)
+                    .title("RAID MODE STARTED")
+                    .description(trigger)
+                    .field(
+                        "Verification Level:",
+                        match previous_verification_level {
+                            Some(_) => format!("Raised to {}", settings.verification_level),
+                            None => String::from("Unchanged"),
+                        },
+                        true,
+                    )
+                    .field(
+                        "Locked Channels:",
+                        if locked_channels.is_empty() {
+                            String::from("None")
+                        } else {
+                            locked_channels
+                                .iter()
+                                .map(|(channel_id, _)| format!("<#{}>", channel_id))
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        },
+                        true,
+                    )
+                    .field(
+                        "New Members:",
+                        format!("Will be {}", settings.action.to_string().to_lowercase()),
+                        true,
+                    )
+                    .field("Flagged Accounts:", flagged.len().to_string(), true),
+            )
+            .components(vec![CreateActionRow::Buttons(vec![
+                CreateButton::new(END_BUTTON)
+                    .label("End Raid Mode")
+                    .style(ButtonStyle::Secondary),
+                CreateButton::new(END_AND_BAN_BUTTON)
+                    .label("End Raid Mode & Ban Flagged")
+                    .style(ButtonStyle::Danger),
+            ])]),
+    )
+    .await?;
+
+    Ok(Raid {
+        started_at: Timestamp::now(),
+        previous_verification_level,
+        locked_channels,
+        flagged,
+    })
+}
+
+/// Ends raid mode, putting the verification level and locked channels back the way they were.
+/// If `ban` is set, every account flagged during the raid is banned. Returns how many accounts were banned.
+pub async fn end_raid(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    ban: bool,
+    moderator_id: UserId,
+) -> Result<usize, Error> {
+    let raid = data
+        .antiraid
+        .raids
+        .lock()
+        .await
+        .remove(&guild_id)
+        .ok_or(AntiraidError::NoActiveRaid)?;
+
+    if let Some(level) = raid.previous_verification_level {
+        guild_id
+            .edit(
+                ctx,
+                EditGuild::default()
+                    .verification_level(level)
+                    .audit_log_reason("Raid mode ended"),
+            )
+            .await?;
+    }
+
+    for (channel_id, previous) in raid.locked_channels {
+        let result = match previous {
+            Some(overwrite) => channel_id.create_permission(ctx, overwrite).await,
+            None => channel_id.delete_permission(ctx, everyone(guild_id)).await,
+        };
+
+        if let Err(error) = result {
+            println!(
+                "Failed to unlock channel {} after a raid: {}",
+                channel_id, error
+            );
+        }
+    }
+
+    let mut banned = 0;
+    if ban {
+        let administered_at = Timestamp::now();
+        for user_id in &raid.flagged {
+            if let Err(error) = guild_id.ban_with_reason(ctx, user_id, 0, RAID_REASON).await {
+                println!("Failed to ban user {} after a raid: {}", user_id, error);
+                continue;
+            }
+
+            database::add_moderation(
+                &data.database,
+                guild_id,
+                *user_id,
+                moderator_id,
+                ModerationType::Ban,
+                administered_at,
+                None,
+                Some(RAID_REASON),
+            )
+            .await?;
+            banned += 1;
+        }
+    }
+
+    let mut embed = CreateEmbed::default()
+        .color(colors::GREEN)
+        .title("RAID MODE ENDED")
+        .description(format!("Raid mode was ended by <@{}>.", moderator_id))
+        .field("Flagged Accounts:", raid.flagged.len().to_string(), true);
+    if ban {
+        embed = embed.field("Banned:", banned.to_string(), true);
+    }
+    messages::send_log(ctx, &data.database, guild_id, embed).await?;
+
+    Ok(banned)
+}
+
+async fn act_on_joiner(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    action: RaidAction,
+) -> Result<(), Error> {
+    let bot_id = ctx.cache.current_user().id;
+
+    // The DM has to go out before a kick, otherwise we no longer share a server with the user.
+    // Raid accounts very often have their DMs closed, so failing here is expected.
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| String::from("the server"));
+    if let Ok(channel) = user_id.create_dm_channel(ctx).await {
+        let _ = channel
+            .send_message(
+                ctx,
+                CreateMessage::default().embed(CreateEmbed::default().color(colors::RED).field(
+                    "Zap!",
+                    format!(
+                        "You have {} in **{}** because the server is currently in raid mode. \
+                                Please try again later.",
+                        action.past_tense(),
+                        guild_name
+                    ),
+                    true,
+                )),
+            )
+            .await;
+    }
+
+    match action {
+        RaidAction::Kick => guild_id.kick_with_reason(ctx, user_id, RAID_REASON).await?,
+        RaidAction::Quarantine => {
+            let quarantine_role = database::get_quarantine_role(&data.database, guild_id)
+                .await?
+                .ok_or_else(|| Error::ConfigNotSetError(String::from("%quarantinerole")))?;
+            guild_id
+                .member(ctx, user_id)
+                .await?
+                .add_role(ctx, quarantine_role)
+                .await?
+        }
+    }
+
+    database::add_moderation(
+        &data.database,
+        guild_id,
+        user_id,
+        bot_id,
+        action.moderation_type(),
+        Timestamp::now(),
+        None,
+        Some(RAID_REASON),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn handle_interaction(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    let component = match interaction {
+        Interaction::Component(component) => component,
+        _ => return Ok(()),
+    };
+    let ban = match component.data.custom_id.as_str() {
+        END_BUTTON => false,
+        END_AND_BAN_BUTTON => true,
+        _ => return Ok(()),
+    };
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+
+    let permissions = component
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let error = if !permissions.manage_guild() || (ban && !permissions.ban_members()) {
+        Some("You do not have permission to end raid mode!")
+    } else if raid_status(data, guild_id).await.is_none() {
+        Some("Raid mode is not on!")
+    } else {
+        None
+    };
+
+    if let Some(error) = error {
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::default()
+                        .embed(
+                            CreateEmbed::default()
+                                .color(colors::RED)
+                                .title("Error!")
+                                .description(error),
+                        )
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    // The buttons go away first, so that nobody else clicks them while the bans are going through
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::default().components(Vec::new()),
+            ),
+        )
+        .await?;
+
+    match end_raid(ctx, data, guild_id, ban, component.user.id).await {
+        // Someone else already ended it in the meantime
+        Err(Error::AntiraidError(AntiraidError::NoActiveRaid)) => Ok(()),
+        result => result.map(|_| ()),
+    }
+}
@@ -0,0 +1,166 @@
+/*
+Account age gate
+
+Brand new accounts are what most raids and ban evasion are made of, so guilds can set a minimum account age.
+Anyone younger than that when they join gets kicked, quarantined, or timed out until their account is old enough.
+*/
+
+use std::time::Duration;
+
+use poise::serenity_prelude::{self, CreateEmbed, CreateMessage, Member, Timestamp};
+
+use crate::colors;
+use crate::commands::moderation::utilities::append_expiry_date;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+use super::types::AgeGateAction;
+
+// Discord doesn't allow timeouts longer than 28 days
+const MAX_TIMEOUT: Duration = Duration::from_secs(28 * 24 * 60 * 60);
+
+/// Checks a new member against the guild's age gate, and takes action against them if their account is too new.
+/// Returns whether or not any action was taken.
+pub async fn check(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<bool, crate::DynError> {
+    let guild_id = member.guild_id;
+    let settings = match database::get_age_gate(&data.database, guild_id).await? {
+        Some(settings) => settings,
+        None => return Ok(false),
+    };
+
+    let administered_at = Timestamp::now();
+    let created_at = member.user.id.created_at();
+    let account_age =
+        (administered_at.unix_timestamp() - created_at.unix_timestamp()).max(0) as u64;
+    if account_age >= settings.min_age.as_secs() {
+        return Ok(false);
+    }
+
+    let bot_id = ctx.cache.current_user().id;
+    let reason = format!(
+        "Account is younger than {}",
+        humantime::format_duration(settings.min_age)
+    );
+    // Timeouts last until the account is old enough (or as long as Discord allows)
+    let expiry_date = match settings.action {
+        AgeGateAction::Timeout => {
+            let remaining =
+                Duration::from_secs(settings.min_age.as_secs() - account_age).min(MAX_TIMEOUT);
+            Some(Timestamp::from_unix_timestamp(
+                administered_at.unix_timestamp() + remaining.as_secs() as i64,
+            )?)
+        }
+        _ => None,
+    };
+
+    // The DM has to go out before a kick, otherwise we no longer share a server with the user
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| String::from("the server"));
+    let dm = match member.user.id.create_dm_channel(ctx).await {
+        Ok(channel) => channel
+            .send_message(
+                ctx,
+                CreateMessage::default().embed(
+                    CreateEmbed::default()
+                        .color(colors::RED)
+                        .field(
+                            "Zap!",
+                            append_expiry_date(
+                                &format!(
+                                    "You have {} in **{}**",
+                                    settings.action.past_tense(),
+                                    guild_name
+                                ),
+                                expiry_date,
+                            ),
+                            true,
+                        )
+                        .field(
+                            "Reason:",
+                            format!(
+                                "Your account is too new. Accounts need to be at least {} old to join.",
+                                humantime::format_duration(settings.min_age)
+                            ),
+                            false,
+                        )
+                        .field(
+                            "Account Created:",
+                            format!("<t:{}:R>", created_at.unix_timestamp()),
+                            false,
+                        ),
+                ),
+            )
+            .await
+            .map(|_| ()),
+        Err(error) => Err(error),
+    };
+
+    if dm.is_err() {
+        println!("Failed to DM user {} about the age gate", member.user.id);
+    }
+
+    match settings.action {
+        AgeGateAction::Kick => {
+            guild_id
+                .kick_with_reason(ctx, member.user.id, &reason)
+                .await?
+        }
+        AgeGateAction::Timeout => {
+            member
+                .clone()
+                // Unwrap is safe as timeouts always have an expiry date
+                .disable_communication_until_datetime(ctx, expiry_date.unwrap())
+                .await?
+        }
+        AgeGateAction::Quarantine => {
+            let quarantine_role = database::get_quarantine_role(&data.database, guild_id)
+                .await?
+                .ok_or_else(|| Error::ConfigNotSetError(String::from("%quarantinerole")))?;
+            member.add_role(ctx, quarantine_role).await?
+        }
+    }
+
+    database::add_moderation(
+        &data.database,
+        guild_id,
+        member.user.id,
+        bot_id,
+        settings.action.moderation_type(),
+        administered_at,
+        expiry_date,
+        Some(&reason),
+    )
+    .await?;
+
+    messages::send_log(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("AGE GATE")
+            .description(append_expiry_date(
+                &format!(
+                    "User <@{}> has {}",
+                    member.user.id,
+                    settings.action.past_tense()
+                ),
+                expiry_date,
+            ))
+            .field(
+                "Account Created:",
+                format!("<t:{}:R>", created_at.unix_timestamp()),
+                true,
+            )
+            .field("Reason:", reason, false),
+    )
+    .await?;
+
+    Ok(true)
+}
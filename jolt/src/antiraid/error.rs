@@ -0,0 +1,30 @@
+/// Errors relating to anti-raid protection.
+#[derive(thiserror::Error, Debug)]
+pub enum AntiraidError {
+    /// An error representing a command that needs anti-raid protection, but it isn't enabled in the guild.
+    #[error("Anti-raid protection is not enabled! Use `antiraid enable` first.")]
+    NotEnabled,
+    /// An error representing an attempt to turn off the age gate when it isn't on.
+    #[error("The account age gate is not enabled!")]
+    AgeGateNotEnabled,
+    /// An error representing an attempt to start raid mode while it's already on.
+    #[error("Raid mode is already on!")]
+    RaidAlreadyActive,
+    /// An error representing an attempt to end raid mode while it isn't on.
+    #[error("Raid mode is not on!")]
+    NoActiveRaid,
+    /// An error representing an attempt to ban the flagged accounts without the permission to ban.
+    #[error("You need the `Ban Members` permission to ban the flagged accounts!")]
+    MissingBanPermission,
+    /// An error representing a join threshold that is too low to mean anything.
+    #[error("The join threshold must be at least 2!")]
+    ThresholdTooLow,
+    /// An error representing an interval that is too long to keep track of.
+    /// Contains one value, which is the longest interval that is allowed.
+    #[error("Intervals can be at most {}", humantime::format_duration(*.0))]
+    IntervalTooLong(std::time::Duration),
+    /// An error representing a channel that isn't in the list of channels to lock.
+    /// Contains one value, which is the ID of the channel.
+    #[error("Channel <#{0}> is not locked during raids!")]
+    ChannelNotLocked(u64),
+}
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::VerificationLevel;
+
+use crate::commands::moderation::types::ModerationType;
+use crate::error::Error;
+
+/// What happens to accounts that join while raid mode is on.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum RaidAction {
+    Kick = 0,
+    Quarantine = 1,
+}
+
+impl RaidAction {
+    /// The moderation that gets recorded in the modlogs for this action.
+    pub fn moderation_type(&self) -> ModerationType {
+        match self {
+            RaidAction::Kick => ModerationType::Kick,
+            RaidAction::Quarantine => ModerationType::Quarantine,
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            RaidAction::Kick => "been kicked",
+            RaidAction::Quarantine => "been quarantined",
+        }
+    }
+}
+
+impl TryFrom<u8> for RaidAction {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RaidAction::Kick),
+            1 => Ok(RaidAction::Quarantine),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for RaidAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_string = match self {
+            RaidAction::Kick => "Kick",
+            RaidAction::Quarantine => "Quarantine",
+        };
+        write!(f, "{}", action_string)
+    }
+}
+
+/// The verification level a guild gets raised to while raid mode is on.
+/// These line up with Discord's own verification levels, minus "None", which wouldn't raise anything.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum RaidVerificationLevel {
+    #[name = "Low (verified email)"]
+    Low = 1,
+    #[name = "Medium (registered for 5 minutes)"]
+    Medium = 2,
+    #[name = "High (member for 10 minutes)"]
+    High = 3,
+    #[name = "Highest (verified phone)"]
+    Highest = 4,
+}
+
+impl From<RaidVerificationLevel> for VerificationLevel {
+    fn from(level: RaidVerificationLevel) -> Self {
+        match level {
+            RaidVerificationLevel::Low => VerificationLevel::Low,
+            RaidVerificationLevel::Medium => VerificationLevel::Medium,
+            RaidVerificationLevel::High => VerificationLevel::High,
+            RaidVerificationLevel::Highest => VerificationLevel::Higher,
+        }
+    }
+}
+
+impl TryFrom<u8> for RaidVerificationLevel {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(RaidVerificationLevel::Low),
+            2 => Ok(RaidVerificationLevel::Medium),
+            3 => Ok(RaidVerificationLevel::High),
+            4 => Ok(RaidVerificationLevel::Highest),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for RaidVerificationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level_string = match self {
+            RaidVerificationLevel::Low => "Low",
+            RaidVerificationLevel::Medium => "Medium",
+            RaidVerificationLevel::High => "High",
+            RaidVerificationLevel::Highest => "Highest",
+        };
+        write!(f, "{}", level_string)
+    }
+}
+
+/// The anti-raid settings of a guild.
+/// Raid mode starts once `threshold` members join within `interval` of each other.
+#[derive(Copy, Clone, Debug)]
+pub struct RaidSettings {
+    pub threshold: u32,
+    pub interval: Duration,
+    pub action: RaidAction,
+    pub verification_level: RaidVerificationLevel,
+}
+
+/// What happens to members whose account is too new to join.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum AgeGateAction {
+    Kick = 0,
+    Timeout = 1,
+    Quarantine = 2,
+}
+
+impl AgeGateAction {
+    /// The moderation that gets recorded in the modlogs for this action.
+    pub fn moderation_type(&self) -> ModerationType {
+        match self {
+            AgeGateAction::Kick => ModerationType::Kick,
+            AgeGateAction::Timeout => ModerationType::Timeout,
+            AgeGateAction::Quarantine => ModerationType::Quarantine,
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            AgeGateAction::Kick => "been kicked",
+            AgeGateAction::Timeout => "been timed out",
+            AgeGateAction::Quarantine => "been quarantined",
+        }
+    }
+}
+
+impl TryFrom<u8> for AgeGateAction {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AgeGateAction::Kick),
+            1 => Ok(AgeGateAction::Timeout),
+            2 => Ok(AgeGateAction::Quarantine),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for AgeGateAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_string = match self {
+            AgeGateAction::Kick => "Kick",
+            AgeGateAction::Timeout => "Timeout",
+            AgeGateAction::Quarantine => "Quarantine",
+        };
+        write!(f, "{}", action_string)
+    }
+}
+
+/// The account age gate settings of a guild.
+/// Members whose account is younger than `min_age` when they join get `action` taken against them.
+#[derive(Copy, Clone, Debug)]
+pub struct AgeGateSettings {
+    pub min_age: Duration,
+    pub action: AgeGateAction,
+}
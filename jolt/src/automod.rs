@@ -0,0 +1,178 @@
+/*
+Automod
+
+Watches messages as they come in and punishes users that break any of the rules a guild has enabled.
+Punishments go through the exact same path as manual moderations (modlogs, DMs, logs channel), with the bot
+recorded as the moderator, so that automod actions show up in `modlogs` like anything else.
+Moderators are never checked against automod.
+*/
+
+pub mod actions;
+pub mod error;
+pub mod filter;
+pub mod ghostping;
+pub mod links;
+pub mod names;
+pub mod normalize;
+pub mod phishing;
+pub mod spam;
+pub mod types;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use poise::serenity_prelude::{self, GuildId, Message, MessageId, UserId};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::commands::moderation::utilities::is_member_moderator;
+use crate::database;
+
+use types::{AutomodAction, Punishment};
+
+/// In-memory state that automod needs to keep track of between messages.
+#[derive(Default)]
+pub struct AutomodState {
+    history: Mutex<HashMap<(GuildId, UserId), VecDeque<spam::HistoryEntry>>>,
+    filters: Mutex<HashMap<GuildId, Arc<filter::CompiledFilter>>>,
+    ghost_pings: Mutex<HashMap<MessageId, ghostping::TrackedMessage>>,
+    invites: Mutex<HashMap<String, links::CachedInvite>>,
+    pub blocklist: RwLock<phishing::Blocklist>,
+}
+
+impl AutomodState {
+    pub fn new(blocklist: phishing::Blocklist) -> Self {
+        Self {
+            blocklist: RwLock::new(blocklist),
+            ..Default::default()
+        }
+    }
+}
+
+/// Runs every automod check against a message.
+/// Returns whether or not the message was acted on (log only rules don't count).
+pub async fn handle_message(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    message: &Message,
+) -> Result<bool, crate::DynError> {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(false),
+    };
+
+    if message.author.bot {
+        return Ok(false);
+    }
+
+    // Moderators can ghost ping too, so this happens before anything that skips them
+    ghostping::track(data, guild_id, message).await?;
+
+    let rules = database::get_automod_rules(&data.database, guild_id).await?;
+    let filter = filter::get_filter(data, guild_id).await?;
+    let link_settings = database::get_link_filter_settings(&data.database, guild_id).await?;
+    let phishing_timeout = database::get_phishing_protection(&data.database, guild_id).await?;
+    if rules.is_empty()
+        && filter.is_empty()
+        && link_settings.is_none()
+        && phishing_timeout.is_none()
+    {
+        return Ok(false);
+    }
+
+    let member = guild_id.member(ctx, message.author.id).await?;
+    if is_member_moderator(&ctx.cache, &member)? {
+        return Ok(false);
+    }
+
+    // Spam is always checked first so that every message ends up in the history
+    let spam_violations = spam::check(&data.automod, guild_id, message, &rules).await;
+
+    // Phishing links are always dealt with, there's no log-only mode for them
+    if let Some(timeout) = phishing_timeout {
+        if let Some(reason) = check_phishing(data, message).await {
+            let punishment = Punishment {
+                action: AutomodAction::Timeout,
+                duration: Some(timeout),
+                log_only: false,
+            };
+            let reason = format!("[Phishing] {}", reason);
+            return enforce(ctx, data, guild_id, message, &punishment, &reason).await;
+        }
+    }
+
+    // Log-only violations are only reported, so everything else still gets checked until something is enforced
+    for (settings, reason) in spam_violations {
+        if enforce(ctx, data, guild_id, message, &settings.punishment, &reason).await? {
+            return Ok(true);
+        }
+    }
+
+    // Phishing and spam don't care about exemptions, but everything past this point does
+    if filter.is_exempt(message.channel_id, &member.roles) {
+        return Ok(false);
+    }
+
+    for rule in filter.find_matches(&message.content) {
+        let reason = format!(
+            "[Filter #{}] Message matched {} `{}`",
+            rule.id,
+            rule.kind.to_string().to_lowercase(),
+            rule.pattern
+        );
+        if enforce(ctx, data, guild_id, message, &rule.punishment, &reason).await? {
+            return Ok(true);
+        }
+    }
+
+    if let Some(settings) = &link_settings {
+        if let Some((punishment, reason)) =
+            links::check(ctx, data, guild_id, message, settings).await?
+        {
+            return enforce(ctx, data, guild_id, message, &punishment, &reason).await;
+        }
+    }
+
+    Ok(false)
+}
+
+/// Punishes the author of a message for breaking a rule.
+/// Returns whether the message was dealt with, which log-only punishments never do.
+async fn enforce(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    message: &Message,
+    punishment: &Punishment,
+    reason: &str,
+) -> Result<bool, crate::DynError> {
+    actions::punish(
+        ctx,
+        data,
+        guild_id,
+        message.author.id,
+        Some(message),
+        punishment,
+        reason,
+    )
+    .await?;
+
+    Ok(!punishment.log_only)
+}
+
+/// Checks every link in the message against the phishing blocklist, returning why the first caught link was caught.
+async fn check_phishing(data: &crate::Data, message: &Message) -> Option<String> {
+    let blocklist = data.automod.blocklist.read().await;
+
+    links::extract_domains(&message.content)
+        .iter()
+        .find_map(|domain| match blocklist.find_match(domain)? {
+            phishing::Match::Listed(listed) => Some(format!(
+                "Posted a link to known phishing domain `{}`",
+                listed
+            )),
+            phishing::Match::Lookalike(protected) => Some(format!(
+                "Posted a link to `{}`, which imitates `{}`",
+                domain, protected
+            )),
+        })
+}
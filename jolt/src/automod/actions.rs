@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{
+    self, CreateEmbed, CreateMessage, GuildId, Message, Timestamp, UserId,
+};
+
+use crate::colors;
+use crate::commands::moderation::utilities::append_expiry_date;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+use super::error::AutomodError;
+use super::types::{AutomodAction, Punishment};
+
+/// The longest timeout Discord allows.
+pub const MAX_TIMEOUT: Duration = Duration::from_secs(28 * 24 * 60 * 60);
+
+// Timeouts can't be indefinite, so this is used when a rule doesn't specify how long one should last.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Makes sure a punishment can actually be carried out, so a broken rule is caught when it's made and not the first
+/// time someone breaks it.
+pub async fn validate_punishment(
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    punishment: &Punishment,
+) -> Result<(), Error> {
+    match punishment.action {
+        AutomodAction::Timeout => {
+            if punishment
+                .duration
+                .map_or(false, |duration| duration > MAX_TIMEOUT)
+            {
+                return Err(AutomodError::TimeoutTooLong(MAX_TIMEOUT).into());
+            }
+        }
+        AutomodAction::Mute => {
+            if database::get_mute_role(database, guild_id).await?.is_none() {
+                return Err(Error::ConfigNotSetError(String::from("%muterole")));
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Carries out an automod punishment on a user.
+///
+/// The offending message (if any) is deleted, the user is DMed, the moderation is recorded in the modlogs with the
+/// bot as the moderator, and the whole thing is announced in the logs channel.
+/// Log-only punishments skip all of that and just report what would have happened.
+pub async fn punish(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    message: Option<&Message>,
+    punishment: &Punishment,
+    reason: &str,
+) -> Result<(), crate::DynError> {
+    if punishment.log_only {
+        return log_only(ctx, data, guild_id, user_id, message, punishment, reason).await;
+    }
+
+    let bot_id = ctx.cache.current_user().id;
+    let administered_at = Timestamp::now();
+
+    if let Some(message) = message {
+        super::ghostping::forget(data, message.id).await;
+        message.delete(ctx).await?;
+    }
+
+    let duration = match punishment.action {
+        AutomodAction::Timeout => Some(punishment.duration.unwrap_or(DEFAULT_TIMEOUT)),
+        AutomodAction::Mute | AutomodAction::Ban => punishment.duration,
+        _ => None,
+    };
+    let expiry_date = duration
+        .map(|duration| {
+            Timestamp::from_unix_timestamp(
+                administered_at.unix_timestamp() + duration.as_secs() as i64,
+            )
+        })
+        .transpose()?;
+
+    // The DM has to go out before a kick or ban, otherwise we no longer share a server with the user
+    if punishment.action != AutomodAction::Delete {
+        let guild_name = guild_id
+            .name(&ctx.cache)
+            .unwrap_or_else(|| String::from("the server"));
+        let dm = user_id
+            .create_dm_channel(ctx)
+            .await?
+            .send_message(
+                ctx,
+                CreateMessage::default().embed(
+                    CreateEmbed::default()
+                        .color(colors::RED)
+                        .field(
+                            "Zap!",
+                            append_expiry_date(
+                                &format!(
+                                    "You have {} in **{}** by automod",
+                                    punishment.action.past_tense(),
+                                    guild_name
+                                ),
+                                expiry_date,
+                            ),
+                            true,
+                        )
+                        .field("Reason:", reason, false),
+                ),
+            )
+            .await;
+
+        if dm.is_err() {
+            println!("Failed to DM user {} about an automod action", user_id);
+        }
+    }
+
+    match punishment.action {
+        AutomodAction::Delete | AutomodAction::Warn => (),
+        AutomodAction::Timeout => {
+            guild_id
+                .member(ctx, user_id)
+                .await?
+                // Unwrap is safe as timeouts always have an expiry date
+                .disable_communication_until_datetime(ctx, expiry_date.unwrap())
+                .await?
+        }
+        AutomodAction::Mute => {
+            let mute_role = database::get_mute_role(&data.database, guild_id)
+                .await?
+                .ok_or_else(|| Error::ConfigNotSetError(String::from("%muterole")))?;
+            guild_id
+                .member(ctx, user_id)
+                .await?
+                .add_role(ctx, mute_role)
+                .await?
+        }
+        AutomodAction::Kick => guild_id.kick_with_reason(ctx, user_id, reason).await?,
+        AutomodAction::Ban => guild_id.ban_with_reason(ctx, user_id, 0, reason).await?,
+    }
+
+    if let Some(moderation_type) = punishment.action.moderation_type() {
+        database::add_moderation(
+            &data.database,
+            guild_id,
+            user_id,
+            bot_id,
+            moderation_type,
+            administered_at,
+            expiry_date,
+            Some(reason),
+        )
+        .await?;
+    }
+
+    messages::send_log(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("AUTOMOD")
+            .description(append_expiry_date(
+                &format!("User <@{}> has {}", user_id, punishment.action.past_tense()),
+                expiry_date,
+            ))
+            .field("Reason:", reason, false),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Reports what a punishment would have done, without actually doing any of it.
+async fn log_only(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    message: Option<&Message>,
+    punishment: &Punishment,
+    reason: &str,
+) -> Result<(), crate::DynError> {
+    let mut embed = CreateEmbed::default()
+        .color(colors::BLUE)
+        .title("AUTOMOD (LOG ONLY)")
+        .description(format!(
+            "User <@{}> would have {}",
+            user_id,
+            punishment.action.past_tense()
+        ))
+        .field("Action:", punishment.to_string(), true)
+        .field("Reason:", reason, false);
+    if let Some(message) = message {
+        embed = embed.field("Message:", message.link(), false);
+    }
+
+    messages::send_log(ctx, &data.database, guild_id, embed).await?;
+
+    Ok(())
+}
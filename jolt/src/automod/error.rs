@@ -0,0 +1,51 @@
+/// Errors relating to automod configuration.
+#[derive(thiserror::Error, Debug)]
+pub enum AutomodError {
+    /// An error representing an interval that is too long to keep track of.
+    /// Contains one value, which is the longest interval that is allowed.
+    #[error("Intervals can be at most {}", humantime::format_duration(*.0))]
+    IntervalTooLong(std::time::Duration),
+    /// An error representing a threshold of zero, which would trip on every message.
+    #[error("Thresholds must be above 0!")]
+    ZeroThreshold,
+    /// An error representing a rule that isn't enabled in the guild.
+    /// Contains one value, which is the name of the rule.
+    #[error("Rule `{0}` is not enabled!")]
+    RuleNotEnabled(String),
+    /// An error representing a filter pattern that couldn't be compiled.
+    /// Contains one value, which is the reason the pattern is invalid.
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+    /// An error representing a filter rule that doesn't exist in the guild.
+    /// Contains one value, which is the ID of the rule.
+    #[error("Filter rule {0} does not exist!")]
+    FilterRuleNotFound(u64),
+    /// An error representing a command that needs either a role or a channel, but got neither.
+    #[error("Please specify a role or a channel!")]
+    NoExemptionTarget,
+    /// An error representing a domain that isn't in either of the link filter's lists.
+    /// Contains one value, which is the domain.
+    #[error("Domain `{0}` is not in the allow or deny list!")]
+    DomainNotFound(String),
+    /// An error representing a server that couldn't be found from an ID or invite.
+    /// Contains one value, which is what the user gave.
+    #[error("Could not find a server from `{0}`")]
+    ServerNotFound(String),
+    /// An error representing a server that isn't in the invite whitelist.
+    /// Contains one value, which is the ID of the server.
+    #[error("Server {0} is not whitelisted!")]
+    ServerNotWhitelisted(u64),
+    /// An error representing a phishing blocklist file that couldn't be read.
+    /// Contains two values, which is the path of the file, and the reason it couldn't be read.
+    #[error("Failed to load the phishing blocklist from `{0}`: {1}")]
+    BlocklistLoadFailed(String, String),
+    /// An error representing a reload with no blocklist file to load.
+    #[error(
+        "No phishing blocklist file was given, and `phishing_blocklist` is not set in the config!"
+    )]
+    NoBlocklistPath,
+    /// An error representing a timeout that is longer than Discord allows.
+    /// Contains one value, which is the longest timeout that is allowed.
+    #[error("Timeouts can be at most {}", humantime::format_duration(*.0))]
+    TimeoutTooLong(std::time::Duration),
+}
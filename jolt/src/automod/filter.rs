@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use poise::serenity_prelude::{ChannelId, GuildId, RoleId};
+use regex::{Regex, RegexBuilder};
+
+use crate::database;
+use crate::error::{AutomodError, Error};
+
+use super::normalize::{collapse_repeats, normalize};
+use super::types::{ExemptionKind, FilterKind, FilterRule};
+
+// Keeps a single pattern from eating all of the memory (or taking forever to compile).
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+pub const MAX_PATTERN_LENGTH: usize = 500;
+
+/// A guild's filter with every pattern already compiled.
+/// These are cached per guild, so that patterns only get compiled again when the filter changes.
+pub struct CompiledFilter {
+    rules: Vec<(FilterRule, Regex)>,
+    exempt_roles: HashSet<RoleId>,
+    exempt_channels: HashSet<ChannelId>,
+}
+
+impl CompiledFilter {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn is_exempt(&self, channel_id: ChannelId, roles: &[RoleId]) -> bool {
+        self.exempt_channels.contains(&channel_id)
+            || roles.iter().any(|role| self.exempt_roles.contains(role))
+    }
+
+    /// Whether any of the roles are exempt, for checks that don't happen in a channel.
+    pub fn is_role_exempt(&self, roles: &[RoleId]) -> bool {
+        roles.iter().any(|role| self.exempt_roles.contains(role))
+    }
+
+    /// Returns the first rule that matches the text.
+    pub fn find_match(&self, text: &str) -> Option<&FilterRule> {
+        self.find_matches(text).into_iter().next()
+    }
+
+    /// Returns every rule that matches the text.
+    pub fn find_matches(&self, text: &str) -> Vec<&FilterRule> {
+        let normalized = normalize(text);
+        let collapsed = collapse_repeats(&normalized);
+
+        self.rules
+            .iter()
+            .filter(|(rule, regex)| match rule.kind {
+                FilterKind::Word => regex.is_match(&normalized) || regex.is_match(&collapsed),
+                FilterKind::Regex => regex.is_match(text) || regex.is_match(&normalized),
+            })
+            .map(|(rule, _)| rule)
+            .collect()
+    }
+}
+
+/// Compiles a filter pattern, making sure that it is valid along the way.
+/// Words are normalized the same way messages are, then matched on word boundaries.
+pub fn compile_pattern(kind: FilterKind, pattern: &str) -> Result<Regex, AutomodError> {
+    if pattern.is_empty() || pattern.chars().count() > MAX_PATTERN_LENGTH {
+        return Err(AutomodError::InvalidPattern(format!(
+            "Patterns must be between 1 and {} characters long",
+            MAX_PATTERN_LENGTH
+        )));
+    }
+
+    let regex = match kind {
+        FilterKind::Word => format!(r"\b{}\b", regex::escape(&normalize(pattern))),
+        FilterKind::Regex => pattern.to_string(),
+    };
+
+    RegexBuilder::new(&regex)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|error| AutomodError::InvalidPattern(error.to_string()))
+}
+
+/// Gets the compiled filter for a guild, compiling it first if it isn't cached yet.
+pub async fn get_filter(
+    data: &crate::Data,
+    guild_id: GuildId,
+) -> Result<Arc<CompiledFilter>, Error> {
+    if let Some(filter) = data.automod.filters.lock().await.get(&guild_id) {
+        return Ok(filter.clone());
+    }
+
+    let rules = database::get_filter_rules(&data.database, guild_id)
+        .await?
+        .into_iter()
+        // Patterns are validated before they're saved, so this should only skip anything that was put in by hand
+        .filter_map(|rule| {
+            compile_pattern(rule.kind, &rule.pattern)
+                .ok()
+                .map(|regex| (rule, regex))
+        })
+        .collect();
+
+    let mut exempt_roles = HashSet::new();
+    let mut exempt_channels = HashSet::new();
+    for (target_id, kind) in database::get_filter_exemptions(&data.database, guild_id).await? {
+        match kind {
+            ExemptionKind::Role => exempt_roles.insert(RoleId::new(target_id)),
+            ExemptionKind::Channel => exempt_channels.insert(ChannelId::new(target_id)),
+        };
+    }
+
+    let filter = Arc::new(CompiledFilter {
+        rules,
+        exempt_roles,
+        exempt_channels,
+    });
+    data.automod
+        .filters
+        .lock()
+        .await
+        .insert(guild_id, filter.clone());
+
+    Ok(filter)
+}
+
+/// Throws away the cached filter for a guild.
+/// This needs to be called whenever the rules or exemptions of a guild change.
+pub async fn invalidate_filter(data: &crate::Data, guild_id: GuildId) {
+    data.automod.filters.lock().await.remove(&guild_id);
+}
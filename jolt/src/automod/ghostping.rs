@@ -0,0 +1,180 @@
+/*
+Ghost ping detection
+
+A ghost ping is a message that mentions someone and is then deleted (or edited to drop the mention) before they get
+a chance to see who pinged them. Messages with mentions are remembered for a short while, and if one of them loses
+its mentions in that time, a notice naming the author and everyone they pinged is posted in the same channel.
+Messages deleted by automod itself are forgotten first, so that punishments don't count as ghost pings.
+*/
+
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{
+    self, ChannelId, CreateEmbed, CreateMessage, GuildId, Message, MessageId, MessageUpdateEvent,
+    RoleId, UserId,
+};
+
+use crate::colors;
+use crate::database;
+
+/// How long after being sent a message can lose its mentions and still count as a ghost ping.
+pub const GHOST_PING_WINDOW: Duration = Duration::from_secs(30);
+
+/// A recently sent message that mentioned someone.
+pub struct TrackedMessage {
+    sent_at: Instant,
+    author: UserId,
+    users: Vec<UserId>,
+    roles: Vec<RoleId>,
+}
+
+/// Remembers a message if it mentions anyone and ghost ping detection is enabled.
+pub async fn track(
+    data: &crate::Data,
+    guild_id: GuildId,
+    message: &Message,
+) -> Result<(), crate::DynError> {
+    // Bots and the author pinging themselves aren't worth a notice
+    let users: Vec<UserId> = message
+        .mentions
+        .iter()
+        .filter(|user| !user.bot && user.id != message.author.id)
+        .map(|user| user.id)
+        .collect();
+    let roles: Vec<RoleId> = message.mention_roles.iter().copied().collect();
+    if users.is_empty() && roles.is_empty() {
+        return Ok(());
+    }
+
+    if !database::get_ghost_ping_detection(&data.database, guild_id).await? {
+        return Ok(());
+    }
+
+    let mut tracked = data.automod.ghost_pings.lock().await;
+    tracked.retain(|_, entry| entry.sent_at.elapsed() <= GHOST_PING_WINDOW);
+    tracked.insert(
+        message.id,
+        TrackedMessage {
+            sent_at: Instant::now(),
+            author: message.author.id,
+            users,
+            roles,
+        },
+    );
+
+    Ok(())
+}
+
+/// Stops tracking a message, so that deleting it doesn't count as a ghost ping.
+pub async fn forget(data: &crate::Data, message_id: MessageId) {
+    data.automod.ghost_pings.lock().await.remove(&message_id);
+}
+
+pub async fn message_delete(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> Result<(), crate::DynError> {
+    let entry = match data.automod.ghost_pings.lock().await.remove(&message_id) {
+        Some(entry) if entry.sent_at.elapsed() <= GHOST_PING_WINDOW => entry,
+        _ => return Ok(()),
+    };
+
+    notify(
+        ctx,
+        channel_id,
+        entry.author,
+        "deleted a message that pinged",
+        &entry.users,
+        &entry.roles,
+    )
+    .await
+}
+
+pub async fn message_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    event: &MessageUpdateEvent,
+) -> Result<(), crate::DynError> {
+    let (author, removed_users, removed_roles) = {
+        let mut tracked = data.automod.ghost_pings.lock().await;
+        let entry = match tracked.get_mut(&event.id) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+        if entry.sent_at.elapsed() > GHOST_PING_WINDOW {
+            tracked.remove(&event.id);
+            return Ok(());
+        }
+
+        // Missing fields mean they didn't change in this edit
+        let mut removed_users = Vec::new();
+        if let Some(mentions) = &event.mentions {
+            let (kept, removed): (Vec<UserId>, Vec<UserId>) = entry
+                .users
+                .iter()
+                .copied()
+                .partition(|user_id| mentions.iter().any(|user| user.id == *user_id));
+            entry.users = kept;
+            removed_users = removed;
+        }
+        let mut removed_roles = Vec::new();
+        if let Some(mention_roles) = &event.mention_roles {
+            let (kept, removed): (Vec<RoleId>, Vec<RoleId>) = entry
+                .roles
+                .iter()
+                .copied()
+                .partition(|role_id| mention_roles.contains(role_id));
+            entry.roles = kept;
+            removed_roles = removed;
+        }
+
+        (entry.author, removed_users, removed_roles)
+    };
+
+    if removed_users.is_empty() && removed_roles.is_empty() {
+        return Ok(());
+    }
+
+    notify(
+        ctx,
+        event.channel_id,
+        author,
+        "edited a message to take out pings to",
+        &removed_users,
+        &removed_roles,
+    )
+    .await
+}
+
+async fn notify(
+    ctx: &serenity_prelude::Context,
+    channel_id: ChannelId,
+    author: UserId,
+    what_happened: &str,
+    users: &[UserId],
+    roles: &[RoleId],
+) -> Result<(), crate::DynError> {
+    let pinged = users
+        .iter()
+        .map(|user_id| format!("<@{}>", user_id))
+        .chain(roles.iter().map(|role_id| format!("<@&{}>", role_id)))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    // Mentions in embeds don't ping, so this doesn't just ping everyone a second time
+    channel_id
+        .send_message(
+            ctx,
+            CreateMessage::default().embed(
+                CreateEmbed::default()
+                    .color(colors::RED)
+                    .title("Ghost Ping")
+                    .description(format!("<@{}> {}:\n{}", author, what_happened, pinged)),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
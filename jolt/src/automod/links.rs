@@ -0,0 +1,199 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{self, GuildId, Invite, Message};
+use regex::Regex;
+
+use crate::database;
+use crate::error::Error;
+
+use super::types::{DomainList, LinkFilterSettings, Punishment};
+
+/// How long the guild an invite belongs to is remembered for.
+pub const INVITE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+// Keeps someone spamming made up invite codes from growing the cache forever
+const MAX_CACHED_INVITES: usize = 1000;
+
+/// The result of looking up an invite, which is `None` for invites that couldn't be resolved.
+pub struct CachedInvite {
+    resolved_at: Instant,
+    guild_id: Option<GuildId>,
+}
+
+fn invite_regex() -> &'static Regex {
+    static INVITE_REGEX: OnceLock<Regex> = OnceLock::new();
+    INVITE_REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:https?://)?(?:www\.)?(?:discord(?:app)?\.com/invite|discord\.gg|discord\.me)/([a-z0-9-]+)",
+        )
+        .expect("Invite regex is invalid!")
+    })
+}
+
+fn url_regex() -> &'static Regex {
+    static URL_REGEX: OnceLock<Regex> = OnceLock::new();
+    URL_REGEX.get_or_init(|| {
+        Regex::new(r"(?i)\bhttps?://(?:[^\s/@<>]*@)?([^\s/:?#<>]+)").expect("URL regex is invalid!")
+    })
+}
+
+/// Finds every invite code in a message.
+pub fn extract_invites(content: &str) -> Vec<String> {
+    invite_regex()
+        .captures_iter(content)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// Finds the domain of every URL in a message, lowercased and without any trailing dots.
+pub fn extract_domains(content: &str) -> Vec<String> {
+    url_regex()
+        .captures_iter(content)
+        .map(|captures| captures[1].trim_end_matches('.').to_lowercase())
+        .filter(|domain| !domain.is_empty())
+        .collect()
+}
+
+/// Checks if a domain is the same as `entry`, or is a subdomain of it.
+pub fn domain_matches(domain: &str, entry: &str) -> bool {
+    domain == entry || domain.ends_with(&format!(".{}", entry))
+}
+
+fn is_invite_domain(domain: &str) -> bool {
+    ["discord.gg", "discord.com", "discordapp.com", "discord.me"]
+        .iter()
+        .any(|entry| domain_matches(domain, entry))
+}
+
+/// Turns whatever a user typed in as a domain ("https://www.Example.com/path") into just the domain ("example.com").
+pub fn clean_domain(input: &str) -> String {
+    let input = input.trim().to_lowercase();
+    let input = input
+        .split_once("://")
+        .map_or(input.as_str(), |(_, rest)| rest);
+    let input = input.split(['/', '?', '#', ':']).next().unwrap_or_default();
+
+    input
+        .trim_start_matches("www.")
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Looks up the guild an invite belongs to.
+/// Invites that can't be resolved (expired, invalid, etc.) return `None`.
+pub async fn resolve_invite(http: &serenity_prelude::Http, code: &str) -> Option<GuildId> {
+    Invite::get(http, code, false, false, None)
+        .await
+        .ok()
+        .and_then(|invite| invite.guild.map(|guild| guild.id))
+}
+
+/// Same as `resolve_invite`, but looks in the cache first so the same invite isn't requested over and over.
+async fn resolve_invite_cached(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    code: &str,
+) -> Option<GuildId> {
+    {
+        let mut cache = data.automod.invites.lock().await;
+        cache.retain(|_, entry| entry.resolved_at.elapsed() <= INVITE_CACHE_TTL);
+        if let Some(entry) = cache.get(code) {
+            return entry.guild_id;
+        }
+    }
+
+    // The lock isn't held during the request, so other messages don't have to wait on it
+    let guild_id = resolve_invite(&ctx.http, code).await;
+
+    let mut cache = data.automod.invites.lock().await;
+    if cache.len() >= MAX_CACHED_INVITES {
+        let oldest = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.resolved_at)
+            .map(|(code, _)| code.clone());
+        if let Some(oldest) = oldest {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(
+        code.to_string(),
+        CachedInvite {
+            resolved_at: Instant::now(),
+            guild_id,
+        },
+    );
+
+    guild_id
+}
+
+/// Checks a message against the link filter of the guild.
+/// Returns the punishment to use and the reason for it if the message breaks the filter.
+pub async fn check(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    message: &Message,
+    settings: &LinkFilterSettings,
+) -> Result<Option<(Punishment, String)>, Error> {
+    if settings.filter_invites {
+        let mut invites = extract_invites(&message.content);
+        invites.sort_unstable();
+        invites.dedup();
+        if !invites.is_empty() {
+            let whitelist = database::get_invite_whitelist(&data.database, guild_id).await?;
+
+            for code in invites {
+                let allowed = match resolve_invite_cached(ctx, data, &code).await {
+                    Some(invite_guild_id) => {
+                        invite_guild_id == guild_id || whitelist.contains(&invite_guild_id)
+                    }
+                    None => false,
+                };
+
+                if !allowed {
+                    return Ok(Some((
+                        settings.punishment,
+                        format!(
+                            "[Link Filter] Posted an invite to another server (`{}`)",
+                            code
+                        ),
+                    )));
+                }
+            }
+        }
+    }
+
+    if settings.filter_urls {
+        let domains: Vec<String> = extract_domains(&message.content)
+            .into_iter()
+            .filter(|domain| !is_invite_domain(domain))
+            .collect();
+
+        if !domains.is_empty() {
+            let lists = database::get_link_filter_domains(&data.database, guild_id).await?;
+            let allowlist: Vec<&String> = lists
+                .iter()
+                .filter(|(_, list)| *list == DomainList::Allow)
+                .map(|(domain, _)| domain)
+                .collect();
+
+            for domain in domains {
+                let denied = lists.iter().any(|(entry, list)| {
+                    *list == DomainList::Deny && domain_matches(&domain, entry)
+                });
+                // Having anything in the allow list means that only those domains are allowed
+                let not_allowed = !allowlist.is_empty()
+                    && !allowlist.iter().any(|entry| domain_matches(&domain, entry));
+
+                if denied || not_allowed {
+                    return Ok(Some((
+                        settings.punishment,
+                        format!("[Link Filter] Posted a link to `{}`", domain),
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
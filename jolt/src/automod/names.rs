@@ -0,0 +1,313 @@
+/*
+Name filter
+
+Runs the guild's filter rules against usernames, global display names and nicknames whenever members join or change
+their name. Resetting the nickname only cares about the name that's actually shown in the member list, since that's
+all it can change; quarantining and kicking look at every name the member has.
+*/
+
+use poise::serenity_prelude::{
+    self, CreateEmbed, CreateMessage, EditMember, GuildId, GuildMemberUpdateEvent, Member,
+    Timestamp, User,
+};
+
+use crate::colors;
+use crate::commands::moderation::utilities::is_member_moderator;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+use super::filter;
+use super::types::{FilterRule, NameFilterAction};
+
+/// The nickname given to members whose account name can't be shown.
+pub const PLACEHOLDER_NAME: &str = "Moderated Name";
+
+/// Checks a new member's names against the filter.
+/// Returns whether or not any action was taken.
+pub async fn member_join(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<bool, crate::DynError> {
+    check(
+        ctx,
+        data,
+        member.guild_id,
+        &member.user,
+        member.nick.as_deref(),
+        true,
+    )
+    .await
+}
+
+/// Checks a member's names against the filter after they change.
+/// Returns whether or not any action was taken.
+pub async fn member_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    old: Option<&Member>,
+    event: &GuildMemberUpdateEvent,
+) -> Result<bool, crate::DynError> {
+    // Without the old member there's no telling what changed, so assume the names did
+    let names_changed = old.map_or(true, |old| {
+        old.user.name != event.user.name
+            || old.user.global_name != event.user.global_name
+            || old.nick != event.nick
+    });
+
+    check(
+        ctx,
+        data,
+        event.guild_id,
+        &event.user,
+        event.nick.as_deref(),
+        names_changed,
+    )
+    .await
+}
+
+async fn check(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user: &User,
+    nickname: Option<&str>,
+    names_changed: bool,
+) -> Result<bool, crate::DynError> {
+    if user.bot {
+        return Ok(false);
+    }
+
+    let action = match database::get_name_filter(&data.database, guild_id).await? {
+        Some(action) => action,
+        None => return Ok(false),
+    };
+
+    let filter = filter::get_filter(data, guild_id).await?;
+    if filter.is_empty() {
+        return Ok(false);
+    }
+
+    let account_name = user.global_name.as_deref().unwrap_or(&user.name);
+    let names = match action {
+        // A clean nickname already hides the account name
+        NameFilterAction::ResetNickname => vec![("Display name", nickname.unwrap_or(account_name))],
+        NameFilterAction::Quarantine | NameFilterAction::Kick => {
+            let mut names = vec![("Username", user.name.as_str())];
+            names.extend(
+                user.global_name
+                    .as_deref()
+                    .map(|name| ("Global name", name)),
+            );
+            names.extend(nickname.map(|name| ("Nickname", name)));
+            names
+        }
+    };
+
+    let matches: Vec<(&str, &str, &FilterRule)> = names
+        .into_iter()
+        .flat_map(|(field, name)| {
+            filter
+                .find_matches(name)
+                .into_iter()
+                .map(move |rule| (field, name, rule))
+        })
+        .collect();
+    if matches.is_empty() {
+        return Ok(false);
+    }
+
+    // The member may have already left, in which case there's nothing left to do
+    let member = match guild_id.member(ctx, user.id).await {
+        Ok(member) => member,
+        Err(_) => return Ok(false),
+    };
+    if is_member_moderator(&ctx.cache, &member)? || filter.is_role_exempt(&member.roles) {
+        return Ok(false);
+    }
+
+    // Log-only rules are only reported, the member is left alone unless an enforced rule matched as well
+    let (field, name, rule) = match matches
+        .iter()
+        .find(|(_, _, rule)| !rule.punishment.log_only)
+    {
+        Some(found) => *found,
+        None => {
+            // Anything else about the member changing would report the same names all over again
+            if names_changed {
+                let (field, name, rule) = matches[0];
+                log_only(
+                    ctx,
+                    data,
+                    guild_id,
+                    user,
+                    action,
+                    name,
+                    &reason(field, rule),
+                )
+                .await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    let reason = reason(field, rule);
+
+    match action {
+        NameFilterAction::ResetNickname => {
+            // Removing the nickname only helps if the account name underneath is clean
+            let account_name_clean = !filter
+                .find_matches(account_name)
+                .iter()
+                .any(|rule| !rule.punishment.log_only);
+            let new_nickname = if nickname.is_some() && account_name_clean {
+                ""
+            } else {
+                PLACEHOLDER_NAME
+            };
+
+            if let Err(error) = guild_id
+                .edit_member(
+                    ctx,
+                    user.id,
+                    EditMember::default()
+                        .nickname(new_nickname)
+                        .audit_log_reason(&reason),
+                )
+                .await
+            {
+                // Members above the bot (like the owner) can't be renamed, which isn't worth erroring over
+                println!(
+                    "Failed to reset the nickname of user {} in {}: {}",
+                    user.id, guild_id, error
+                );
+                return Ok(false);
+            }
+        }
+        NameFilterAction::Quarantine => {
+            let quarantine_role = database::get_quarantine_role(&data.database, guild_id)
+                .await?
+                .ok_or_else(|| Error::ConfigNotSetError(String::from("%quarantinerole")))?;
+            // Every name change would otherwise add another quarantine to the modlogs
+            if member.roles.contains(&quarantine_role) {
+                return Ok(false);
+            }
+
+            notify(ctx, guild_id, user, action, &reason).await;
+            member.add_role(ctx, quarantine_role).await?
+        }
+        NameFilterAction::Kick => {
+            // The DM has to go out before the kick, otherwise we no longer share a server with the user
+            notify(ctx, guild_id, user, action, &reason).await;
+            guild_id.kick_with_reason(ctx, user.id, &reason).await?
+        }
+    }
+
+    if let Some(moderation_type) = action.moderation_type() {
+        database::add_moderation(
+            &data.database,
+            guild_id,
+            user.id,
+            ctx.cache.current_user().id,
+            moderation_type,
+            Timestamp::now(),
+            None,
+            Some(&reason),
+        )
+        .await?;
+    }
+
+    messages::send_log(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("NAME FILTER")
+            .description(format!("User <@{}> has {}", user.id, action.past_tense()))
+            .field("Name:", name, true)
+            .field("Reason:", &reason, false),
+    )
+    .await?;
+
+    Ok(true)
+}
+
+async fn log_only(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user: &User,
+    action: NameFilterAction,
+    name: &str,
+    reason: &str,
+) -> Result<(), crate::DynError> {
+    messages::send_log(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("NAME FILTER (LOG ONLY)")
+            .description(format!(
+                "User <@{}> would have {}",
+                user.id,
+                action.past_tense()
+            ))
+            .field("Name:", name, true)
+            .field("Reason:", reason, false),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn reason(field: &str, rule: &FilterRule) -> String {
+    format!(
+        "[Filter #{}] {} matched {} `{}`",
+        rule.id,
+        field,
+        rule.kind.to_string().to_lowercase(),
+        rule.pattern
+    )
+}
+
+async fn notify(
+    ctx: &serenity_prelude::Context,
+    guild_id: GuildId,
+    user: &User,
+    action: NameFilterAction,
+    reason: &str,
+) {
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| String::from("the server"));
+    let dm = match user.id.create_dm_channel(ctx).await {
+        Ok(channel) => channel
+            .send_message(
+                ctx,
+                CreateMessage::default().embed(
+                    CreateEmbed::default()
+                        .color(colors::RED)
+                        .field(
+                            "Zap!",
+                            format!(
+                                "You have {} in **{}** because of your name",
+                                action.past_tense(),
+                                guild_name
+                            ),
+                            true,
+                        )
+                        .field("Reason:", reason, false),
+                ),
+            )
+            .await
+            .map(|_| ()),
+        Err(error) => Err(error),
+    };
+
+    if dm.is_err() {
+        println!("Failed to DM user {} about the name filter", user.id);
+    }
+}
@@ -0,0 +1,275 @@
+/*
+Text normalization
+
+People trying to get around filters tend to use the same handful of tricks: invisible characters between letters,
+letters from other alphabets that look identical to latin ones, fancy unicode "fonts", and leetspeak.
+Everything here boils text down to plain lowercase ascii (as much as possible) so filters only have to match that.
+*/
+
+/// Whether a character is invisible when displayed (or changes how the characters around it are displayed).
+pub fn is_invisible(c: char) -> bool {
+    matches!(c as u32,
+        0x00AD // soft hyphen
+        | 0x034F // combining grapheme joiner
+        | 0x0300..=0x036F // combining diacritical marks
+        | 0x180E // mongolian vowel separator
+        | 0x200B..=0x200F // zero-width spaces, joiners and direction marks
+        | 0x202A..=0x202E // directional formatting
+        | 0x2060..=0x2064 // word joiner and invisible operators
+        | 0xFE00..=0xFE0F // variation selectors
+        | 0xFEFF // zero-width no-break space
+    )
+}
+
+/// Maps a character that looks like a latin letter to that letter.
+/// Characters that are already ascii (or that we don't know about) are returned as-is.
+pub fn homoglyph(c: char) -> char {
+    let code = c as u32;
+
+    // Fullwidth forms (Ａ-Ｚ, ａ-ｚ, ０-９)
+    if (0xFF21..=0xFF3A).contains(&code) {
+        return char::from_u32(code - 0xFF21 + 'a' as u32).unwrap_or(c);
+    }
+    if (0xFF41..=0xFF5A).contains(&code) {
+        return char::from_u32(code - 0xFF41 + 'a' as u32).unwrap_or(c);
+    }
+    if (0xFF10..=0xFF19).contains(&code) {
+        return char::from_u32(code - 0xFF10 + '0' as u32).unwrap_or(c);
+    }
+
+    // Mathematical alphanumeric symbols (𝐚, 𝒂, 𝓪, 𝔞, 𝕒, ...) come in runs of 52 letters, uppercase then lowercase
+    if (0x1D400..=0x1D6A3).contains(&code) {
+        let offset = (code - 0x1D400) % 52 % 26;
+        return char::from_u32('a' as u32 + offset).unwrap_or(c);
+    }
+    // ...and the digits in runs of 10
+    if (0x1D7CE..=0x1D7FF).contains(&code) {
+        return char::from_u32('0' as u32 + (code - 0x1D7CE) % 10).unwrap_or(c);
+    }
+
+    // Circled and parenthesized letters (ⓐ, ⒜, Ⓐ)
+    if (0x24D0..=0x24E9).contains(&code) {
+        return char::from_u32(code - 0x24D0 + 'a' as u32).unwrap_or(c);
+    }
+    if (0x24B6..=0x24CF).contains(&code) {
+        return char::from_u32(code - 0x24B6 + 'a' as u32).unwrap_or(c);
+    }
+    if (0x249C..=0x24B5).contains(&code) {
+        return char::from_u32(code - 0x249C + 'a' as u32).unwrap_or(c);
+    }
+
+    match c {
+        // Cyrillic
+        'а' | 'А' => 'a',
+        'в' | 'В' => 'b',
+        'с' | 'С' => 'c',
+        'ԁ' => 'd',
+        'е' | 'Е' | 'ё' | 'Ё' => 'e',
+        'һ' | 'Н' => 'h',
+        'і' | 'І' | 'ї' | 'Ї' => 'i',
+        'ј' | 'Ј' => 'j',
+        'к' | 'К' => 'k',
+        'м' | 'М' => 'm',
+        'о' | 'О' => 'o',
+        'р' | 'Р' => 'p',
+        'ѕ' | 'Ѕ' => 's',
+        'т' | 'Т' => 't',
+        'у' | 'У' => 'y',
+        'х' | 'Х' => 'x',
+        'ԝ' => 'w',
+        // Greek
+        'α' | 'Α' => 'a',
+        'β' | 'Β' => 'b',
+        'ε' | 'Ε' => 'e',
+        'η' | 'Η' => 'h',
+        'ι' | 'Ι' => 'i',
+        'κ' | 'Κ' => 'k',
+        'μ' | 'Μ' => 'm',
+        'ν' | 'Ν' => 'n',
+        'ο' | 'Ο' => 'o',
+        'ρ' | 'Ρ' => 'p',
+        'τ' | 'Τ' => 't',
+        'υ' | 'Υ' => 'u',
+        'χ' | 'Χ' => 'x',
+        'ζ' | 'Ζ' => 'z',
+        // Latin letters with diacritics and other lookalikes
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' | 'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => {
+            'a'
+        }
+        'ç' | 'ć' | 'č' | 'Ç' | 'Ć' | 'Č' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' | 'È' | 'É' | 'Ê' | 'Ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ı' | 'Ì' | 'Í' | 'Î' | 'Ï' => 'i',
+        'ñ' | 'ń' | 'Ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => 'o',
+        'ś' | 'š' | 'ß' | 'Ś' | 'Š' => 's',
+        'ù' | 'ú' | 'û' | 'ü' | 'Ù' | 'Ú' | 'Û' | 'Ü' => 'u',
+        'ý' | 'ÿ' | 'Ý' => 'y',
+        'ź' | 'ż' | 'ž' | 'Ź' | 'Ż' | 'Ž' => 'z',
+        'ɡ' => 'g',
+        'ℓ' => 'l',
+        _ => c,
+    }
+}
+
+fn leetspeak(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' => 'i',
+        '3' => 'e',
+        '4' => 'a',
+        '5' => 's',
+        '7' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => c,
+    }
+}
+
+/// Symbols that stand in for letters, but only inside a word. Anywhere else they're just punctuation ("bad!").
+fn leet_symbol(c: char) -> Option<char> {
+    match c {
+        '!' | '|' => Some('i'),
+        '@' => Some('a'),
+        '$' => Some('s'),
+        _ => None,
+    }
+}
+
+/// Removes invisible characters and replaces lookalike characters, without touching leetspeak.
+/// This is useful for things like domain names, where numbers are expected to be numbers.
+pub fn skeleton(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_invisible(*c))
+        .map(homoglyph)
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Fully normalizes text for filtering: removes invisible characters, replaces lookalikes and undoes leetspeak.
+pub fn normalize(text: &str) -> String {
+    let chars: Vec<char> = skeleton(text).chars().collect();
+    let mut normalized = String::with_capacity(chars.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        if leet_symbol(chars[i]).is_none() {
+            normalized.push(leetspeak(chars[i]));
+            i += 1;
+            continue;
+        }
+
+        // Runs of symbols ("p@$$word") are only letters if there's a letter or digit on both sides of the run
+        let end = i + chars[i..]
+            .iter()
+            .take_while(|c| leet_symbol(**c).is_some())
+            .count();
+        let inside_word = i > 0
+            && chars[i - 1].is_alphanumeric()
+            && chars.get(end).map_or(false, |c| c.is_alphanumeric());
+
+        for c in &chars[i..end] {
+            normalized.push(match leet_symbol(*c) {
+                Some(letter) if inside_word => letter,
+                _ => *c,
+            });
+        }
+        i = end;
+    }
+
+    normalized
+}
+
+/// Collapses runs of the same character ("baaaad" -> "bad").
+pub fn collapse_repeats(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut last = None;
+
+    for c in text.chars() {
+        if Some(c) != last {
+            collapsed.push(c);
+        }
+        last = Some(c);
+    }
+
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_undoes_filter_evasion() {
+        let cases = [
+            ("plain", "plain"),
+            ("LOUD", "loud"),
+            // Invisible characters between letters
+            ("b\u{200B}a\u{200D}d", "bad"),
+            ("ba\u{0301}d", "bad"),
+            // Cyrillic and greek lookalikes
+            ("\u{0431}\u{0430}d", "\u{0431}ad"),
+            ("\u{0441}\u{043E}\u{043E}l", "cool"),
+            ("\u{03B1}\u{03B2}\u{03B5}", "abe"),
+            // Fancy unicode "fonts"
+            ("\u{FF42}\u{FF41}\u{FF44}", "bad"),
+            ("\u{1D41B}\u{1D41A}\u{1D41D}", "bad"),
+            ("\u{1D5EF}\u{1D5EE}\u{1D5F1}", "bad"),
+            ("\u{24D1}\u{24D0}\u{24D3}", "bad"),
+            ("\u{24B7}\u{24B6}\u{24B9}", "bad"),
+            // Diacritics
+            ("b\u{E0}d", "bad"),
+            ("n\u{E4}\u{EF}ve", "naive"),
+            // Leetspeak
+            ("b4d", "bad"),
+            ("h3ll0", "hello"),
+            ("5p@m", "spam"),
+            ("n1c3", "nice"),
+            ("b!tch", "bitch"),
+            ("p@$$w0rd", "password"),
+            // Symbols outside of a word are punctuation
+            ("word!", "word!"),
+            ("word$", "word$"),
+            ("bad! really", "bad! really"),
+            ("$p@m", "$pam"),
+            ("hi @everyone", "hi @everyone"),
+            ("a | b", "a | b"),
+            // Fullwidth digits are digits first, then leetspeak
+            ("b\u{FF14}d", "bad"),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(normalize(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn skeleton_keeps_numbers() {
+        let cases = [
+            ("discord.gg/abc123", "discord.gg/abc123"),
+            ("d\u{0456}scord.com", "discord.com"),
+            ("g\u{200B}oogle.com", "google.com"),
+            ("\u{FF21}\u{FF22}\u{FF23}", "abc"),
+            ("steam\u{0441}ommunity", "steamcommunity"),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(skeleton(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn collapse_repeats_squashes_runs() {
+        let cases = [
+            ("", ""),
+            ("bad", "bad"),
+            ("baaaad", "bad"),
+            ("bbaadd", "bad"),
+            ("hello", "helo"),
+            ("aabbaa", "aba"),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(collapse_repeats(text), expected, "{:?}", text);
+        }
+    }
+}
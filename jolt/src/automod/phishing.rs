@@ -0,0 +1,188 @@
+/*
+Phishing link protection
+
+Checks links against a list of known phishing domains that is read from a file on disk, so nothing has to be
+fetched over the network. The list is shared between every guild, and can be reloaded by the bot owner.
+
+Domains are compared in their punycode form, and subdomains of a listed domain count as a match. Unicode domains
+are also reduced to what they look like in plain ascii, which catches lookalikes of listed domains, as well as
+lookalikes of a handful of protected domains that are often imitated. That way `dіscord.com` (with a cyrillic `і`)
+is caught even though it isn't on the list, while `discord.com` itself is left alone.
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::error::AutomodError;
+
+use super::normalize::skeleton;
+
+// Real domains that phishing sites like to imitate
+const PROTECTED_DOMAINS: [&str; 10] = [
+    "discord.com",
+    "discord.gg",
+    "discordapp.com",
+    "discord.gift",
+    "steamcommunity.com",
+    "steampowered.com",
+    "epicgames.com",
+    "roblox.com",
+    "twitch.tv",
+    "youtube.com",
+];
+
+/// What a domain was caught for.
+pub enum Match<'a> {
+    /// A domain on the blocklist, or a lookalike of one.
+    Listed(&'a str),
+    /// A lookalike of a protected domain.
+    Lookalike(&'static str),
+}
+
+/// The loaded blocklist.
+#[derive(Default)]
+pub struct Blocklist {
+    domains: HashSet<String>,
+    // Lookalike skeleton -> the domain on the list it came from
+    skeletons: HashMap<String, String>,
+}
+
+impl Blocklist {
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    /// Reads a blocklist file.
+    ///
+    /// The file should have one domain per line. Anything after a `#` is ignored, and only the last word of
+    /// each line is used, so hosts-style files (`0.0.0.0 example.com`) work as well.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AutomodError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|error| {
+            AutomodError::BlocklistLoadFailed(path.display().to_string(), error.to_string())
+        })?;
+
+        let mut blocklist = Blocklist::default();
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let domain = match line.split_whitespace().last() {
+                Some(domain) => domain.trim_end_matches('.').to_lowercase(),
+                None => continue,
+            };
+
+            let domain = match idna::domain_to_ascii(&domain) {
+                Ok(domain) if !domain.is_empty() => domain,
+                _ => continue,
+            };
+
+            blocklist
+                .skeletons
+                .insert(skeleton(&unicode_form(&domain)), domain.clone());
+            blocklist.domains.insert(domain);
+        }
+
+        Ok(blocklist)
+    }
+
+    /// Checks a domain against the blocklist and the protected domains.
+    pub fn find_match(&self, domain: &str) -> Option<Match<'_>> {
+        let ascii = idna::domain_to_ascii(domain).ok()?;
+        let lookalike = skeleton(&unicode_form(&ascii));
+
+        for candidate in parent_domains(&ascii) {
+            if let Some(domain) = self.domains.get(candidate) {
+                return Some(Match::Listed(domain.as_str()));
+            }
+        }
+
+        for candidate in parent_domains(&lookalike) {
+            if let Some(domain) = self.skeletons.get(candidate) {
+                return Some(Match::Listed(domain.as_str()));
+            }
+        }
+
+        // Only a domain that changed when reduced to ascii can be imitating something
+        if lookalike != ascii {
+            for candidate in parent_domains(&lookalike) {
+                if let Some(protected) = PROTECTED_DOMAINS
+                    .iter()
+                    .find(|domain| **domain == candidate)
+                {
+                    return Some(Match::Lookalike(protected));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn unicode_form(ascii: &str) -> String {
+    idna::domain_to_unicode(ascii).0
+}
+
+/// Gets the domain and every domain it is a subdomain of ("a.b.c" -> "a.b.c", "b.c", "c").
+fn parent_domains(domain: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(domain).chain(
+        domain
+            .match_indices('.')
+            .map(move |(index, _)| &domain[index + 1..]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist(domains: &[&str]) -> Blocklist {
+        let mut blocklist = Blocklist::default();
+        for domain in domains {
+            blocklist
+                .skeletons
+                .insert(skeleton(&unicode_form(domain)), domain.to_string());
+            blocklist.domains.insert(domain.to_string());
+        }
+
+        blocklist
+    }
+
+    fn describe(found: Option<Match<'_>>) -> Option<String> {
+        found.map(|found| match found {
+            Match::Listed(domain) => format!("listed {}", domain),
+            Match::Lookalike(domain) => format!("lookalike {}", domain),
+        })
+    }
+
+    #[test]
+    fn finds_listed_domains_and_lookalikes() {
+        let blocklist = blocklist(&["free-nitro.ru"]);
+        let cases = [
+            ("free-nitro.ru", Some("listed free-nitro.ru")),
+            ("gift.free-nitro.ru", Some("listed free-nitro.ru")),
+            ("fr\u{0435}e-nitro.ru", Some("listed free-nitro.ru")),
+            ("d\u{0456}scord.com", Some("lookalike discord.com")),
+            (
+                "cdn.d\u{0456}scordapp.com",
+                Some("lookalike discordapp.com"),
+            ),
+            (
+                "steamc\u{043E}mmunity.com",
+                Some("lookalike steamcommunity.com"),
+            ),
+            // The real thing, and domains that just happen to look similar in ascii
+            ("discord.com", None),
+            ("cdn.discordapp.com", None),
+            ("disc0rd.com", None),
+            ("example.com", None),
+        ];
+
+        for (domain, expected) in cases {
+            assert_eq!(
+                describe(blocklist.find_match(domain)).as_deref(),
+                expected,
+                "{:?}",
+                domain
+            );
+        }
+    }
+}
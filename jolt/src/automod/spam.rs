@@ -0,0 +1,234 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{GuildId, Message, UserId};
+
+use super::types::{AutomodRule, AutomodRuleSettings};
+use super::AutomodState;
+
+// How far back message history is kept for the rate and duplicate rules.
+// Intervals longer than this can't be configured.
+pub const MAX_INTERVAL: Duration = Duration::from_secs(5 * 60);
+// Stops a single user from growing their history forever if they're somehow under every threshold.
+const MAX_HISTORY_LENGTH: usize = 50;
+// Short messages like "LOL" shouldn't count as excessive caps
+const MIN_CAPS_LENGTH: usize = 10;
+
+/// A message that has been seen recently, reduced to what the spam rules care about.
+pub struct HistoryEntry {
+    sent_at: Instant,
+    content_hash: u64,
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.trim().to_lowercase().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn caps_percentage(content: &str) -> Option<u32> {
+    let letters: Vec<char> = content.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() < MIN_CAPS_LENGTH {
+        return None;
+    }
+
+    let uppercase = letters.iter().filter(|c| c.is_uppercase()).count();
+    Some((uppercase * 100 / letters.len()) as u32)
+}
+
+fn is_unicode_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F1E6..=0x1F1FF // regional indicators (flags)
+        | 0x1F300..=0x1FAFF // most pictographs
+        | 0x2600..=0x27BF // miscellaneous symbols and dingbats
+    )
+}
+
+fn emoji_count(content: &str) -> u32 {
+    // Custom emojis look like <:name:id> or <a:name:id>
+    let custom = content.matches("<:").count() + content.matches("<a:").count();
+    let unicode = content.chars().filter(|c| is_unicode_emoji(*c)).count();
+
+    (custom + unicode) as u32
+}
+
+fn mention_count(message: &Message) -> u32 {
+    // Pinging yourself doesn't bother anyone else
+    let users = message
+        .mentions
+        .iter()
+        .filter(|user| user.id != message.author.id)
+        .map(|user| user.id)
+        .collect::<HashSet<UserId>>()
+        .len();
+    let roles = message.mention_roles.iter().collect::<HashSet<_>>().len();
+
+    (users + roles + usize::from(message.mention_everyone)) as u32
+}
+
+/// Records the message in the author's history and checks it against every enabled spam rule.
+/// Returns every rule that was broken, in order, along with a reason that can be used in the modlogs.
+pub async fn check<'a>(
+    state: &AutomodState,
+    guild_id: GuildId,
+    message: &Message,
+    rules: &'a [AutomodRuleSettings],
+) -> Vec<(&'a AutomodRuleSettings, String)> {
+    let now = Instant::now();
+    let hash = content_hash(&message.content);
+
+    let mut history = state.history.lock().await;
+    let entries = history
+        .entry((guild_id, message.author.id))
+        .or_insert_with(VecDeque::new);
+
+    entries.push_back(HistoryEntry {
+        sent_at: now,
+        content_hash: hash,
+    });
+    while entries.len() > MAX_HISTORY_LENGTH
+        || entries
+            .front()
+            .map_or(false, |entry| now - entry.sent_at > MAX_INTERVAL)
+    {
+        entries.pop_front();
+    }
+
+    let mut broken = Vec::new();
+    for settings in rules {
+        let threshold = settings.threshold;
+        let reason = match settings.rule {
+            AutomodRule::MessageRate => {
+                let count = entries
+                    .iter()
+                    .filter(|entry| now - entry.sent_at <= settings.interval)
+                    .count() as u32;
+                (count >= threshold).then(|| {
+                    format!(
+                        "Sent {} messages in {}",
+                        count,
+                        humantime::format_duration(settings.interval)
+                    )
+                })
+            }
+            AutomodRule::DuplicateMessages => {
+                let count = entries
+                    .iter()
+                    .filter(|entry| {
+                        now - entry.sent_at <= settings.interval && entry.content_hash == hash
+                    })
+                    .count() as u32;
+                (count >= threshold).then(|| {
+                    format!(
+                        "Sent the same message {} times in {}",
+                        count,
+                        humantime::format_duration(settings.interval)
+                    )
+                })
+            }
+            AutomodRule::MassMentions => {
+                let count = mention_count(message);
+                (count >= threshold)
+                    .then(|| format!("Mentioned {} users and roles in one message", count))
+            }
+            AutomodRule::ExcessiveCaps => caps_percentage(&message.content)
+                .filter(|percentage| *percentage >= threshold)
+                .map(|percentage| format!("Message was {}% capital letters", percentage)),
+            AutomodRule::EmojiSpam => {
+                let count = emoji_count(&message.content);
+                (count >= threshold).then(|| format!("Sent {} emojis in one message", count))
+            }
+            AutomodRule::NewlineSpam => {
+                let count = message.content.matches('\n').count() as u32;
+                (count >= threshold).then(|| format!("Sent {} newlines in one message", count))
+            }
+        };
+
+        if let Some(reason) = reason {
+            broken.push((settings, format!("[Automod: {}] {}", settings.rule, reason)));
+        }
+    }
+
+    // Start the user off fresh, otherwise every message after this would trip the same rules again
+    if broken
+        .iter()
+        .any(|(settings, _)| settings.rule.uses_interval())
+    {
+        entries.clear();
+    }
+
+    broken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_percentage_ignores_short_messages() {
+        let cases = [
+            ("LOL", None),
+            ("OK FINE", None),
+            ("", None),
+            ("1234567890!!!!", None),
+        ];
+
+        for (content, expected) in cases {
+            assert_eq!(caps_percentage(content), expected, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn caps_percentage_only_counts_letters() {
+        let cases = [
+            ("THIS IS VERY LOUD", Some(100)),
+            ("this is very quiet", Some(0)),
+            ("HALF half HALF half", Some(50)),
+            ("WHY!!! 1234 ARE YOU SHOUTING", Some(100)),
+            ("Normal sentence with a Capital", Some(7)),
+        ];
+
+        for (content, expected) in cases {
+            assert_eq!(caps_percentage(content), expected, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn emoji_count_counts_custom_and_unicode_emojis() {
+        let cases = [
+            ("no emojis here", 0),
+            ("<:pog:123456789>", 1),
+            ("<a:dance:123456789> <:pog:123456789>", 2),
+            ("\u{1F600}\u{1F600}\u{1F600}", 3),
+            ("\u{2764} and <:pog:1>", 2),
+            ("\u{1F1FA}\u{1F1F8}", 2),
+            ("<@123456789> isn't an emoji", 0),
+        ];
+
+        for (content, expected) in cases {
+            assert_eq!(emoji_count(content), expected, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn content_hash_ignores_case_and_surrounding_whitespace() {
+        let cases = [
+            ("hello", "HELLO", true),
+            ("hello", "  hello\n", true),
+            ("hello", "hello world", false),
+            ("hello world", "hello  world", false),
+        ];
+
+        for (first, second, same) in cases {
+            assert_eq!(
+                content_hash(first) == content_hash(second),
+                same,
+                "{:?} and {:?}",
+                first,
+                second
+            );
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use crate::commands::moderation::types::ModerationType;
+use crate::error::Error;
+
+/// The spam rules that can be enabled in a guild.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum AutomodRule {
+    #[name = "Message Rate"]
+    MessageRate = 0,
+    #[name = "Duplicate Messages"]
+    DuplicateMessages = 1,
+    #[name = "Mass Mentions"]
+    MassMentions = 2,
+    #[name = "Excessive Caps"]
+    ExcessiveCaps = 3,
+    #[name = "Emoji Spam"]
+    EmojiSpam = 4,
+    #[name = "Newline Spam"]
+    NewlineSpam = 5,
+}
+
+impl AutomodRule {
+    /// Whether or not the rule looks at more than one message, which is when the interval matters.
+    pub fn uses_interval(&self) -> bool {
+        matches!(
+            self,
+            AutomodRule::MessageRate | AutomodRule::DuplicateMessages
+        )
+    }
+}
+
+impl TryFrom<u8> for AutomodRule {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AutomodRule::MessageRate),
+            1 => Ok(AutomodRule::DuplicateMessages),
+            2 => Ok(AutomodRule::MassMentions),
+            3 => Ok(AutomodRule::ExcessiveCaps),
+            4 => Ok(AutomodRule::EmojiSpam),
+            5 => Ok(AutomodRule::NewlineSpam),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for AutomodRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rule_string = match self {
+            AutomodRule::MessageRate => "Message Rate",
+            AutomodRule::DuplicateMessages => "Duplicate Messages",
+            AutomodRule::MassMentions => "Mass Mentions",
+            AutomodRule::ExcessiveCaps => "Excessive Caps",
+            AutomodRule::EmojiSpam => "Emoji Spam",
+            AutomodRule::NewlineSpam => "Newline Spam",
+        };
+        write!(f, "{}", rule_string)
+    }
+}
+
+/// What automod does to a user once they trip a rule.
+/// Every action other than `Delete` also deletes the offending message.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum AutomodAction {
+    Delete = 0,
+    Warn = 1,
+    Timeout = 2,
+    Mute = 3,
+    Kick = 4,
+    Ban = 5,
+}
+
+impl AutomodAction {
+    /// The moderation that gets recorded in the modlogs for this action, if any.
+    pub fn moderation_type(&self) -> Option<ModerationType> {
+        match self {
+            AutomodAction::Delete => None,
+            AutomodAction::Warn => Some(ModerationType::Warning),
+            AutomodAction::Timeout => Some(ModerationType::Timeout),
+            AutomodAction::Mute => Some(ModerationType::Mute),
+            AutomodAction::Kick => Some(ModerationType::Kick),
+            AutomodAction::Ban => Some(ModerationType::Ban),
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            AutomodAction::Delete => "had their message deleted",
+            AutomodAction::Warn => "been warned",
+            AutomodAction::Timeout => "been timed out",
+            AutomodAction::Mute => "been muted",
+            AutomodAction::Kick => "been kicked",
+            AutomodAction::Ban => "been banned",
+        }
+    }
+}
+
+impl TryFrom<u8> for AutomodAction {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AutomodAction::Delete),
+            1 => Ok(AutomodAction::Warn),
+            2 => Ok(AutomodAction::Timeout),
+            3 => Ok(AutomodAction::Mute),
+            4 => Ok(AutomodAction::Kick),
+            5 => Ok(AutomodAction::Ban),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for AutomodAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_string = match self {
+            AutomodAction::Delete => "Delete",
+            AutomodAction::Warn => "Warn",
+            AutomodAction::Timeout => "Timeout",
+            AutomodAction::Mute => "Mute",
+            AutomodAction::Kick => "Kick",
+            AutomodAction::Ban => "Ban",
+        };
+        write!(f, "{}", action_string)
+    }
+}
+
+/// An action along with how long it should last.
+/// The duration is ignored for actions that can't expire.
+#[derive(Copy, Clone, Debug)]
+pub struct Punishment {
+    pub action: AutomodAction,
+    pub duration: Option<Duration>,
+    /// Only report what would have happened to the logs channel, without deleting or punishing anything.
+    pub log_only: bool,
+}
+
+impl std::fmt::Display for Punishment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.duration {
+            Some(duration) => write!(
+                f,
+                "{} ({})",
+                self.action,
+                humantime::format_duration(duration)
+            )?,
+            None => write!(f, "{}", self.action)?,
+        }
+
+        if self.log_only {
+            write!(f, " [log only]")?;
+        }
+        Ok(())
+    }
+}
+
+/// The settings for a single enabled rule in a guild.
+#[derive(Copy, Clone, Debug)]
+pub struct AutomodRuleSettings {
+    pub rule: AutomodRule,
+    pub threshold: u32,
+    pub interval: Duration,
+    pub punishment: Punishment,
+}
+
+/// How a filter rule's pattern is matched against messages.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum FilterKind {
+    /// Matches a whole word, after the message has been normalized.
+    Word = 0,
+    /// Matches a regular expression against both the original and normalized message.
+    Regex = 1,
+}
+
+impl TryFrom<u8> for FilterKind {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FilterKind::Word),
+            1 => Ok(FilterKind::Regex),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for FilterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind_string = match self {
+            FilterKind::Word => "Word",
+            FilterKind::Regex => "Regex",
+        };
+        write!(f, "{}", kind_string)
+    }
+}
+
+/// A single word or pattern in a guild's filter.
+#[derive(Clone, Debug)]
+pub struct FilterRule {
+    pub id: u64,
+    pub kind: FilterKind,
+    pub pattern: String,
+    pub punishment: Punishment,
+}
+
+/// Whether a filter exemption applies to a role or a channel.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum ExemptionKind {
+    Role = 0,
+    Channel = 1,
+}
+
+impl TryFrom<u8> for ExemptionKind {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ExemptionKind::Role),
+            1 => Ok(ExemptionKind::Channel),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+/// What happens to members whose name trips the filter.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum NameFilterAction {
+    #[name = "Reset Nickname"]
+    ResetNickname = 0,
+    Quarantine = 1,
+    Kick = 2,
+}
+
+impl NameFilterAction {
+    /// The moderation that gets recorded in the modlogs for this action, if any.
+    pub fn moderation_type(&self) -> Option<ModerationType> {
+        match self {
+            NameFilterAction::ResetNickname => None,
+            NameFilterAction::Quarantine => Some(ModerationType::Quarantine),
+            NameFilterAction::Kick => Some(ModerationType::Kick),
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            NameFilterAction::ResetNickname => "had their nickname reset",
+            NameFilterAction::Quarantine => "been quarantined",
+            NameFilterAction::Kick => "been kicked",
+        }
+    }
+}
+
+impl TryFrom<u8> for NameFilterAction {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(NameFilterAction::ResetNickname),
+            1 => Ok(NameFilterAction::Quarantine),
+            2 => Ok(NameFilterAction::Kick),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for NameFilterAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_string = match self {
+            NameFilterAction::ResetNickname => "Reset Nickname",
+            NameFilterAction::Quarantine => "Quarantine",
+            NameFilterAction::Kick => "Kick",
+        };
+        write!(f, "{}", action_string)
+    }
+}
+
+/// The link filter settings of a guild.
+#[derive(Copy, Clone, Debug)]
+pub struct LinkFilterSettings {
+    pub filter_invites: bool,
+    pub filter_urls: bool,
+    pub punishment: Punishment,
+}
+
+/// Which list a domain in the link filter belongs to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+pub enum DomainList {
+    Allow,
+    Deny,
+}
+
+impl std::fmt::Display for DomainList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list_string = match self {
+            DomainList::Allow => "Allow",
+            DomainList::Deny => "Deny",
+        };
+        write!(f, "{}", list_string)
+    }
+}
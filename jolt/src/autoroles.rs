@@ -0,0 +1,163 @@
+/*
+Autoroles
+
+Roles given to members as soon as they join, with separate lists for humans and bots. Humans can instead be made to
+wait until they pass membership screening and/or until a delay has passed, in which case they're kept in the database
+until then and the scheduler in `main.rs` hands their roles out, so restarts don't lose anyone. Humans also always
+wait until they've verified, if the guild has verification turned on, so the roles can't be used to skip it.
+*/
+
+pub mod error;
+pub mod types;
+
+use poise::serenity_prelude::{self, GuildId, GuildMemberUpdateEvent, Member, RoleId, UserId};
+
+use crate::database;
+use crate::error::Error;
+
+/// The most autoroles for humans or bots, which is as many roles as a select menu in `configure` can hold.
+pub const MAX_AUTOROLES: usize = 25;
+
+const AUTOROLE_REASON: &str = "Autorole";
+
+pub async fn member_join(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<(), Error> {
+    let guild_id = member.guild_id;
+    let user_id = member.user.id;
+
+    if member.user.bot {
+        return assign(ctx, &data.database, guild_id, user_id, true).await;
+    }
+
+    let settings = database::get_autorole_settings(&data.database, guild_id).await?;
+    if settings.after_screening && member.pending {
+        // Their roles are handed out once they pass screening, see `member_update`
+        database::add_pending_autoroles(&data.database, guild_id, user_id, None).await?;
+        return Ok(());
+    }
+
+    schedule(ctx, data, guild_id, user_id, settings.delay).await
+}
+
+pub async fn member_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    event: &GuildMemberUpdateEvent,
+) -> Result<(), Error> {
+    if event.pending {
+        return Ok(());
+    }
+
+    // Only members that joined while screening was required are waiting on it
+    if !database::take_waiting_autoroles(&data.database, event.guild_id, event.user.id).await? {
+        return Ok(());
+    }
+
+    let settings = database::get_autorole_settings(&data.database, event.guild_id).await?;
+    schedule(ctx, data, event.guild_id, event.user.id, settings.delay).await
+}
+
+/// Hands out the autoroles of a member that just verified, unless they're still waiting on something else.
+pub async fn member_verified(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<(), Error> {
+    let settings = database::get_autorole_settings(&data.database, member.guild_id).await?;
+    // `member_update` takes over once they pass screening
+    if settings.after_screening && member.pending {
+        return Ok(());
+    }
+
+    if !database::take_waiting_autoroles(&data.database, member.guild_id, member.user.id).await? {
+        return Ok(());
+    }
+
+    schedule(ctx, data, member.guild_id, member.user.id, settings.delay).await
+}
+
+async fn schedule(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    delay: Option<std::time::Duration>,
+) -> Result<(), Error> {
+    // Members that haven't verified yet get their roles from `member_verified`
+    if database::is_verification_pending(&data.database, guild_id, user_id).await? {
+        database::add_pending_autoroles(&data.database, guild_id, user_id, None).await?;
+        return Ok(());
+    }
+
+    match delay {
+        Some(delay) => {
+            let assign_date =
+                serenity_prelude::Timestamp::now().unix_timestamp() + delay.as_secs() as i64;
+            database::add_pending_autoroles(&data.database, guild_id, user_id, Some(assign_date))
+                .await?;
+            Ok(())
+        }
+        None => assign(ctx, &data.database, guild_id, user_id, false).await,
+    }
+}
+
+/// Gives a member the autoroles for humans or bots.
+pub async fn assign(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    user_id: UserId,
+    bots: bool,
+) -> Result<(), Error> {
+    let role_ids = database::get_autoroles(database, guild_id, bots).await?;
+    if role_ids.is_empty() {
+        return Ok(());
+    }
+
+    // Someone kicked or banned by anti-raid or the age gate in the meantime doesn't need roles
+    let member = match guild_id.member(&cache_http, user_id).await {
+        Ok(member) => member,
+        Err(_) => return Ok(()),
+    };
+    // Neither does someone that got quarantined, since the roles could let them back into the server
+    if let Some(quarantine_role) = database::get_quarantine_role(database, guild_id).await? {
+        if member.roles.contains(&quarantine_role) {
+            return Ok(());
+        }
+    }
+
+    for role_id in role_ids {
+        if member.roles.contains(&role_id) {
+            continue;
+        }
+        cache_http
+            .http()
+            .add_member_role(guild_id, user_id, role_id, Some(AUTOROLE_REASON))
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn member_remove(
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    database::remove_pending_autoroles(&data.database, guild_id, user_id).await?;
+
+    Ok(())
+}
+
+pub async fn role_delete(
+    data: &crate::Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    database::remove_autorole(&data.database, guild_id, role_id, None).await?;
+
+    Ok(())
+}
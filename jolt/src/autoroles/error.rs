@@ -0,0 +1,11 @@
+/// Errors relating to autoroles.
+#[derive(thiserror::Error, Debug)]
+pub enum AutoroleError {
+    /// An error representing a role that isn't an autorole.
+    #[error("That role is not an autorole!")]
+    NotFound,
+    /// An error representing too many autoroles for one kind of member.
+    /// Contains one value, which is the most autoroles allowed.
+    #[error("There can be at most {0} autoroles for humans and {0} for bots!")]
+    TooManyRoles(usize),
+}
@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Which kind of member an autorole is given to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+pub enum AutoroleTarget {
+    Humans,
+    Bots,
+}
+
+impl AutoroleTarget {
+    pub fn is_bots(&self) -> bool {
+        *self == AutoroleTarget::Bots
+    }
+}
+
+impl std::fmt::Display for AutoroleTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let target_string = match self {
+            AutoroleTarget::Humans => "Humans",
+            AutoroleTarget::Bots => "Bots",
+        };
+        write!(f, "{}", target_string)
+    }
+}
+
+/// When autoroles are given to humans. Bots always get theirs right away.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct AutoroleSettings {
+    /// How long after joining (or passing membership screening) members wait for their roles.
+    pub delay: Option<Duration>,
+    /// Whether members have to pass membership screening before getting their roles.
+    pub after_screening: bool,
+}
@@ -0,0 +1,533 @@
+/*
+Server backups
+
+A snapshot is a JSON document of everything needed to put a server's structure back together: roles, categories,
+channels, permission overwrites, and the Jolt settings that point at any of those. Snapshots are versioned, so that
+older ones can still be read if the format ever changes.
+
+Restoring is meant for recovering from a nuke or a bad edit, so it only ever adds and repairs. Roles and channels
+that still exist are edited back to how they were, ones that were deleted are created again (with every reference
+to them pointed at the new copy), and anything made after the snapshot is left alone.
+*/
+
+pub mod error;
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude::{
+    self, ChannelId, ChannelType, CreateChannel, EditChannel, EditRole, GuildChannel, GuildId,
+    PermissionOverwrite, PermissionOverwriteType, Permissions, Role, RoleId, Timestamp, UserId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::database;
+use crate::error::{BackupError, Error};
+
+/// The current version of the snapshot format.
+/// Bump this whenever the format changes in a way older versions of Jolt can't read.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+const RESTORE_REASON: &str = "Restoring a backup";
+
+/// Information about a stored snapshot, without the snapshot itself.
+pub struct SnapshotInfo {
+    pub id: u64,
+    pub created_by: UserId,
+    pub created_at: Timestamp,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snapshot {
+    pub version: u32,
+    pub roles: Vec<RoleSnapshot>,
+    pub channels: Vec<ChannelSnapshot>,
+    #[serde(default)]
+    pub settings: SettingsSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RoleSnapshot {
+    pub id: u64,
+    pub name: String,
+    pub color: u32,
+    pub hoist: bool,
+    pub mentionable: bool,
+    pub permissions: u64,
+    pub position: u16,
+    pub managed: bool,
+}
+
+impl From<&Role> for RoleSnapshot {
+    fn from(role: &Role) -> Self {
+        Self {
+            id: role.id.get(),
+            name: role.name.clone(),
+            color: role.colour.0,
+            hoist: role.hoist,
+            mentionable: role.mentionable,
+            permissions: role.permissions.bits(),
+            position: role.position,
+            managed: role.managed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwriteTarget {
+    Role,
+    Member,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct OverwriteSnapshot {
+    pub target: OverwriteTarget,
+    pub id: u64,
+    pub allow: u64,
+    pub deny: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ChannelSnapshot {
+    pub id: u64,
+    pub name: String,
+    pub kind: u8,
+    pub parent_id: Option<u64>,
+    pub position: u16,
+    pub topic: Option<String>,
+    pub nsfw: bool,
+    pub rate_limit_per_user: Option<u16>,
+    pub bitrate: Option<u32>,
+    pub user_limit: Option<u32>,
+    pub overwrites: Vec<OverwriteSnapshot>,
+}
+
+impl From<&GuildChannel> for ChannelSnapshot {
+    fn from(channel: &GuildChannel) -> Self {
+        Self {
+            id: channel.id.get(),
+            name: channel.name.clone(),
+            kind: channel.kind.into(),
+            parent_id: channel.parent_id.map(|parent_id| parent_id.get()),
+            position: channel.position,
+            topic: channel.topic.clone(),
+            nsfw: channel.nsfw,
+            rate_limit_per_user: channel.rate_limit_per_user,
+            bitrate: channel.bitrate,
+            user_limit: channel.user_limit,
+            overwrites: snapshot_overwrites(&channel.permission_overwrites),
+        }
+    }
+}
+
+fn snapshot_overwrites(overwrites: &[PermissionOverwrite]) -> Vec<OverwriteSnapshot> {
+    let mut snapshots: Vec<OverwriteSnapshot> = overwrites
+        .iter()
+        .filter_map(|overwrite| {
+            let (target, id) = match overwrite.kind {
+                PermissionOverwriteType::Role(role_id) => (OverwriteTarget::Role, role_id.get()),
+                PermissionOverwriteType::Member(user_id) => {
+                    (OverwriteTarget::Member, user_id.get())
+                }
+                _ => return None,
+            };
+
+            Some(OverwriteSnapshot {
+                target,
+                id,
+                allow: overwrite.allow.bits(),
+                deny: overwrite.deny.bits(),
+            })
+        })
+        .collect();
+    // Discord doesn't keep overwrites in any particular order, so they're sorted to make comparing easier
+    snapshots.sort();
+    snapshots
+}
+
+/// The Jolt settings that refer to roles or channels.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default, Debug)]
+#[serde(default)]
+pub struct SettingsSnapshot {
+    pub prefix: Option<String>,
+    pub mute_role_id: Option<u64>,
+    pub quarantine_role_id: Option<u64>,
+    pub logs_channel_id: Option<u64>,
+    pub raid_lock_channel_ids: Vec<u64>,
+}
+
+/// What happened during a restore.
+#[derive(Default)]
+pub struct RestoreReport {
+    pub roles_created: usize,
+    pub roles_updated: usize,
+    pub channels_created: usize,
+    pub channels_updated: usize,
+    pub failures: Vec<String>,
+}
+
+/// Takes a snapshot of the guild as it is right now.
+pub async fn capture(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+) -> Result<Snapshot, Error> {
+    let mut roles: Vec<RoleSnapshot> = guild_id
+        .roles(ctx)
+        .await?
+        .values()
+        .map(RoleSnapshot::from)
+        .collect();
+    roles.sort_by(|a, b| b.position.cmp(&a.position).then(a.id.cmp(&b.id)));
+
+    let mut channels: Vec<ChannelSnapshot> = guild_id
+        .channels(ctx)
+        .await?
+        .values()
+        .map(ChannelSnapshot::from)
+        .collect();
+    channels.sort_by(|a, b| a.position.cmp(&b.position).then(a.id.cmp(&b.id)));
+
+    let settings = SettingsSnapshot {
+        prefix: database::get_prefix(&data.database, guild_id).await?,
+        mute_role_id: database::get_mute_role(&data.database, guild_id)
+            .await?
+            .map(|role_id| role_id.get()),
+        quarantine_role_id: database::get_quarantine_role(&data.database, guild_id)
+            .await?
+            .map(|role_id| role_id.get()),
+        logs_channel_id: database::get_logs_channel(&data.database, guild_id)
+            .await?
+            .map(|channel_id| channel_id.get()),
+        raid_lock_channel_ids: database::get_raid_lock_channels(&data.database, guild_id)
+            .await?
+            .into_iter()
+            .map(|channel_id| channel_id.get())
+            .collect(),
+    };
+
+    Ok(Snapshot {
+        version: SNAPSHOT_VERSION,
+        roles,
+        channels,
+        settings,
+    })
+}
+
+/// Reads a stored snapshot, making sure this version of Jolt understands it.
+pub fn parse(info: &SnapshotInfo, document: &str) -> Result<Snapshot, BackupError> {
+    if info.version > SNAPSHOT_VERSION {
+        return Err(BackupError::UnsupportedVersion(info.version));
+    }
+
+    serde_json::from_str(document).map_err(|error| BackupError::InvalidSnapshot(error.to_string()))
+}
+
+/// Lists every difference between two snapshots, as one line per role, channel or setting.
+pub fn diff(old: &Snapshot, new: &Snapshot) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let old_roles: HashMap<u64, &RoleSnapshot> =
+        old.roles.iter().map(|role| (role.id, role)).collect();
+    let new_roles: HashMap<u64, &RoleSnapshot> =
+        new.roles.iter().map(|role| (role.id, role)).collect();
+
+    for role in &old.roles {
+        match new_roles.get(&role.id) {
+            None => lines.push(format!("- Role **{}**", role.name)),
+            Some(new_role) => {
+                let changes = role_changes(role, new_role);
+                if !changes.is_empty() {
+                    lines.push(format!("~ Role **{}**: {}", role.name, changes.join(", ")));
+                }
+            }
+        }
+    }
+    for role in &new.roles {
+        if !old_roles.contains_key(&role.id) {
+            lines.push(format!("+ Role **{}**", role.name));
+        }
+    }
+
+    let old_channels: HashMap<u64, &ChannelSnapshot> = old
+        .channels
+        .iter()
+        .map(|channel| (channel.id, channel))
+        .collect();
+    let new_channels: HashMap<u64, &ChannelSnapshot> = new
+        .channels
+        .iter()
+        .map(|channel| (channel.id, channel))
+        .collect();
+
+    for channel in &old.channels {
+        match new_channels.get(&channel.id) {
+            None => lines.push(format!("- Channel **#{}**", channel.name)),
+            Some(new_channel) => {
+                let changes = channel_changes(channel, new_channel);
+                if !changes.is_empty() {
+                    lines.push(format!(
+                        "~ Channel **#{}**: {}",
+                        channel.name,
+                        changes.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    for channel in &new.channels {
+        if !old_channels.contains_key(&channel.id) {
+            lines.push(format!("+ Channel **#{}**", channel.name));
+        }
+    }
+
+    if old.settings != new.settings {
+        lines.push(String::from("~ Jolt settings"));
+    }
+
+    lines
+}
+
+fn role_changes(old: &RoleSnapshot, new: &RoleSnapshot) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if old.name != new.name {
+        changes.push("name");
+    }
+    if old.color != new.color {
+        changes.push("color");
+    }
+    if old.hoist != new.hoist {
+        changes.push("hoist");
+    }
+    if old.mentionable != new.mentionable {
+        changes.push("mentionable");
+    }
+    if old.permissions != new.permissions {
+        changes.push("permissions");
+    }
+    changes
+}
+
+// Positions are left out on purpose, since creating or deleting anything shifts the position of everything below it
+fn channel_changes(old: &ChannelSnapshot, new: &ChannelSnapshot) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if old.name != new.name {
+        changes.push("name");
+    }
+    if old.parent_id != new.parent_id {
+        changes.push("category");
+    }
+    if old.topic != new.topic {
+        changes.push("topic");
+    }
+    if old.nsfw != new.nsfw {
+        changes.push("nsfw");
+    }
+    if old.rate_limit_per_user != new.rate_limit_per_user {
+        changes.push("slowmode");
+    }
+    if old.overwrites != new.overwrites {
+        changes.push("permission overwrites");
+    }
+    changes
+}
+
+/// Points the overwrites of a snapshot at the roles as they exist now.
+/// Overwrites for roles that couldn't be restored are dropped.
+fn map_overwrites(
+    overwrites: &[OverwriteSnapshot],
+    role_ids: &HashMap<u64, RoleId>,
+) -> Vec<PermissionOverwrite> {
+    overwrites
+        .iter()
+        .filter_map(|overwrite| {
+            let kind = match overwrite.target {
+                OverwriteTarget::Role => {
+                    PermissionOverwriteType::Role(*role_ids.get(&overwrite.id)?)
+                }
+                OverwriteTarget::Member => {
+                    PermissionOverwriteType::Member(UserId::new(overwrite.id))
+                }
+            };
+
+            Some(PermissionOverwrite {
+                allow: Permissions::from_bits_truncate(overwrite.allow),
+                deny: Permissions::from_bits_truncate(overwrite.deny),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Puts the guild back the way it was in the snapshot. See the top of this file for what that does and doesn't do.
+pub async fn restore(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    snapshot: &Snapshot,
+) -> Result<RestoreReport, Error> {
+    let mut report = RestoreReport::default();
+
+    // Old role ID -> the ID the role has now, which is different if it had to be created again
+    let mut role_ids: HashMap<u64, RoleId> = HashMap::new();
+    let current_roles = guild_id.roles(ctx).await?;
+
+    // New roles always show up at the bottom, so creating them from the top down keeps them in the same order
+    let mut roles: Vec<&RoleSnapshot> = snapshot.roles.iter().collect();
+    roles.sort_by(|a, b| b.position.cmp(&a.position));
+
+    for role in roles {
+        // The @everyone role shares its ID with the guild, and can only have its permissions changed
+        let is_everyone = role.id == guild_id.get();
+        let mut builder = EditRole::default()
+            .permissions(Permissions::from_bits_truncate(role.permissions))
+            .audit_log_reason(RESTORE_REASON);
+        if !is_everyone {
+            builder = builder
+                .name(&role.name)
+                .colour(role.color)
+                .hoist(role.hoist)
+                .mentionable(role.mentionable);
+        }
+
+        match current_roles.get(&RoleId::new(role.id)) {
+            Some(current) => {
+                role_ids.insert(role.id, current.id);
+                // Roles belonging to bots and integrations are managed by Discord
+                if role.managed || role_changes(role, &RoleSnapshot::from(current)).is_empty() {
+                    continue;
+                }
+
+                match guild_id.edit_role(ctx, current.id, builder).await {
+                    Ok(_) => report.roles_updated += 1,
+                    Err(error) => report
+                        .failures
+                        .push(format!("Role **{}**: {}", role.name, error)),
+                }
+            }
+            // These come back by themselves when the bot or integration is added again
+            None if role.managed => (),
+            None => match guild_id.create_role(ctx, builder).await {
+                Ok(new_role) => {
+                    role_ids.insert(role.id, new_role.id);
+                    report.roles_created += 1;
+                }
+                Err(error) => report
+                    .failures
+                    .push(format!("Role **{}**: {}", role.name, error)),
+            },
+        }
+    }
+
+    // Same as roles, but for channels. Categories go first so that every other channel has somewhere to go.
+    let mut channel_ids: HashMap<u64, ChannelId> = HashMap::new();
+    let current_channels = guild_id.channels(ctx).await?;
+
+    let mut channels: Vec<&ChannelSnapshot> = snapshot.channels.iter().collect();
+    channels.sort_by_key(|channel| {
+        (
+            ChannelType::from(channel.kind) != ChannelType::Category,
+            channel.position,
+        )
+    });
+
+    for channel in channels {
+        let kind = ChannelType::from(channel.kind);
+        let parent_id = channel
+            .parent_id
+            .and_then(|parent_id| channel_ids.get(&parent_id).copied());
+        let overwrites = map_overwrites(&channel.overwrites, &role_ids);
+
+        match current_channels.get(&ChannelId::new(channel.id)) {
+            Some(current) => {
+                channel_ids.insert(channel.id, current.id);
+
+                // Compare against the snapshot with its IDs pointed at whatever they are now
+                let mut expected = channel.clone();
+                expected.parent_id = parent_id.map(|parent_id| parent_id.get());
+                expected.overwrites = snapshot_overwrites(&overwrites);
+                if channel_changes(&expected, &ChannelSnapshot::from(current)).is_empty() {
+                    continue;
+                }
+
+                let mut builder = EditChannel::default()
+                    .name(&channel.name)
+                    .permissions(overwrites)
+                    .audit_log_reason(RESTORE_REASON);
+                if kind != ChannelType::Category {
+                    builder = builder.category(parent_id).nsfw(channel.nsfw);
+                }
+                if let Some(topic) = &channel.topic {
+                    builder = builder.topic(topic);
+                }
+                if let Some(rate_limit) = channel.rate_limit_per_user {
+                    builder = builder.rate_limit_per_user(rate_limit);
+                }
+
+                match current.id.edit(ctx, builder).await {
+                    Ok(_) => report.channels_updated += 1,
+                    Err(error) => report
+                        .failures
+                        .push(format!("Channel **#{}**: {}", channel.name, error)),
+                }
+            }
+            None => {
+                let mut builder = CreateChannel::new(&channel.name)
+                    .kind(kind)
+                    .position(channel.position)
+                    .nsfw(channel.nsfw)
+                    .permissions(overwrites)
+                    .audit_log_reason(RESTORE_REASON);
+                if let Some(parent_id) = parent_id {
+                    builder = builder.category(parent_id);
+                }
+                if let Some(topic) = &channel.topic {
+                    builder = builder.topic(topic);
+                }
+                if let Some(rate_limit) = channel.rate_limit_per_user {
+                    builder = builder.rate_limit_per_user(rate_limit);
+                }
+                if let Some(bitrate) = channel.bitrate {
+                    builder = builder.bitrate(bitrate);
+                }
+                if let Some(user_limit) = channel.user_limit {
+                    builder = builder.user_limit(user_limit);
+                }
+
+                match guild_id.create_channel(ctx, builder).await {
+                    Ok(new_channel) => {
+                        channel_ids.insert(channel.id, new_channel.id);
+                        report.channels_created += 1;
+                    }
+                    Err(error) => report
+                        .failures
+                        .push(format!("Channel **#{}**: {}", channel.name, error)),
+                }
+            }
+        }
+    }
+
+    let settings = &snapshot.settings;
+    if let Some(prefix) = &settings.prefix {
+        database::set_prefix(&data.database, guild_id, prefix).await?;
+    }
+    if let Some(role_id) = settings.mute_role_id.and_then(|id| role_ids.get(&id)) {
+        database::set_mute_role(&data.database, guild_id, *role_id).await?;
+    }
+    if let Some(role_id) = settings.quarantine_role_id.and_then(|id| role_ids.get(&id)) {
+        database::set_quarantine_role(&data.database, guild_id, *role_id).await?;
+    }
+    if let Some(channel_id) = settings.logs_channel_id.and_then(|id| channel_ids.get(&id)) {
+        database::set_logs_channel(&data.database, guild_id, *channel_id).await?;
+    }
+    for channel_id in settings
+        .raid_lock_channel_ids
+        .iter()
+        .filter_map(|id| channel_ids.get(id))
+    {
+        database::add_raid_lock_channel(&data.database, guild_id, *channel_id).await?;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,16 @@
+/// Errors relating to server backups.
+#[derive(thiserror::Error, Debug)]
+pub enum BackupError {
+    /// An error representing a snapshot that doesn't exist in the guild.
+    /// Contains one value, which is the ID of the snapshot.
+    #[error("Snapshot #{0} does not exist!")]
+    SnapshotNotFound(u64),
+    /// An error representing a snapshot made by a newer version of Jolt.
+    /// Contains one value, which is the version of the snapshot.
+    #[error("Snapshot version {0} is not supported by this version of Jolt!")]
+    UnsupportedVersion(u32),
+    /// An error representing a snapshot that could not be read.
+    /// Contains one value, which is the reason it could not be read.
+    #[error("Snapshot could not be read: {0}")]
+    InvalidSnapshot(String),
+}
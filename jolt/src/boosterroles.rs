@@ -0,0 +1,114 @@
+/*
+Booster roles
+
+Server boosters can make one role of their own, with their pick of name, color and (if the server has enough boosts)
+icon. The roles are put right below an anchor role picked by the admins, and are deleted once their owner stops
+boosting or leaves. Names go through the word filter the same way member names do.
+*/
+
+pub mod error;
+
+use poise::serenity_prelude::{self, Colour, GuildId, GuildMemberUpdateEvent, RoleId, UserId};
+
+use crate::automod::filter;
+use crate::colors;
+use crate::database;
+use crate::error::{BoosterRoleError, Error};
+
+/// The guild feature needed for roles to have icons.
+pub const ROLE_ICONS_FEATURE: &str = "ROLE_ICONS";
+
+/// Reads a hex color, with or without the leading `#`.
+pub fn parse_color(color: &str) -> Result<Colour, BoosterRoleError> {
+    colors::parse_hex(color)
+        .map(Colour::new)
+        .ok_or_else(|| BoosterRoleError::InvalidColor(color.to_string()))
+}
+
+/// Makes sure a role name gets past the guild's word filter, unless the member is exempt from it.
+pub async fn check_name(
+    data: &crate::Data,
+    guild_id: GuildId,
+    roles: &[RoleId],
+    name: &str,
+) -> Result<(), Error> {
+    let filter = filter::get_filter(data, guild_id).await?;
+    if !filter.is_role_exempt(roles) && filter.find_match(name).is_some() {
+        return Err(BoosterRoleError::FilteredName.into());
+    }
+
+    Ok(())
+}
+
+/// Deletes a member's booster role, if they have one.
+/// Returns whether or not they had one.
+pub async fn delete(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    user_id: UserId,
+    reason: &str,
+) -> Result<bool, Error> {
+    let role_id = match database::get_booster_role(database, guild_id, user_id).await? {
+        Some(role_id) => role_id,
+        None => return Ok(false),
+    };
+
+    // The role is only forgotten once it's actually gone, otherwise it would stay behind with nobody owning it
+    match cache_http
+        .http()
+        .delete_role(guild_id, role_id, Some(reason))
+        .await
+    {
+        Ok(()) => (),
+        // The role may already have been deleted by hand
+        Err(serenity_prelude::Error::Http(error))
+            if error.status_code() == Some(serenity_prelude::StatusCode::NOT_FOUND) => {}
+        Err(error) => return Err(error.into()),
+    }
+    database::remove_booster_role_by_role(database, guild_id, role_id).await?;
+
+    Ok(true)
+}
+
+pub async fn member_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    event: &GuildMemberUpdateEvent,
+) -> Result<(), Error> {
+    if event.premium_since.is_some() {
+        return Ok(());
+    }
+
+    delete(
+        ctx,
+        &data.database,
+        event.guild_id,
+        event.user.id,
+        "Member stopped boosting",
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn member_remove(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    delete(ctx, &data.database, guild_id, user_id, "Member left").await?;
+
+    Ok(())
+}
+
+pub async fn role_delete(
+    data: &crate::Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    database::remove_booster_role_by_role(&data.database, guild_id, role_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+/// Errors relating to booster roles.
+#[derive(thiserror::Error, Debug)]
+pub enum BoosterRoleError {
+    /// An error representing a member that isn't boosting the server.
+    #[error("Only server boosters can have a booster role!")]
+    NotBoosting,
+    /// An error representing a member that doesn't have a booster role yet.
+    #[error("You don't have a booster role! Use `boosterrole create` first.")]
+    NoRole,
+    /// An error representing a member that already has a booster role.
+    #[error("You already have a booster role! Use `boosterrole edit` to change it.")]
+    AlreadyHasRole,
+    /// An error representing a color that couldn't be read.
+    /// Contains one value, which is the color that was given.
+    #[error("`{0}` is not a hex color like `#5865F2`!")]
+    InvalidColor(String),
+    /// An error representing a role name caught by the word filter.
+    #[error("That name is not allowed by the server's word filter!")]
+    FilteredName,
+    /// An error representing a server that can't use role icons yet.
+    #[error("This server needs more boosts before roles can have icons!")]
+    IconsUnavailable,
+}
@@ -1,3 +1,13 @@
 pub const RED: u32 = 0xf38ba8;
 pub const GREEN: u32 = 0xa6e3a1;
 pub const BLUE: u32 = 0x89b4fa;
+
+/// Reads a hex color, with or without the leading `#`.
+pub fn parse_hex(color: &str) -> Option<u32> {
+    let hex = color.trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+
+    u32::from_str_radix(hex, 16).ok()
+}
@@ -1,3 +1,16 @@
+pub mod antinuke;
+pub mod antiraid;
+pub mod automod;
+pub mod autoroles;
+pub mod backup;
+pub mod boosterroles;
 pub mod configuration;
+pub mod dehoist;
+pub mod leveling;
 pub mod meta;
 pub mod moderation;
+pub mod prune;
+pub mod reactionroles;
+pub mod rolemenus;
+pub mod temproles;
+pub mod verification;
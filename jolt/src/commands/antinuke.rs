@@ -0,0 +1,284 @@
+// anti-nuke configuration commands
+
+use poise::serenity_prelude::{self, CreateEmbed};
+use poise::CreateReply;
+
+use crate::antinuke::{self, AntinukeSettings, MAX_INTERVAL};
+use crate::colors;
+use crate::database;
+use crate::error::AntinukeError;
+
+// Admins are exactly who anti-nuke is meant to stop, so MANAGE_GUILD isn't enough here
+async fn ensure_owner(ctx: &crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if antinuke::guild_owner(ctx.serenity_context(), guild_id).await? != ctx.author().id {
+        return Err(AntinukeError::NotOwner.into());
+    }
+
+    Ok(())
+}
+
+/// Configure anti-nuke protection (server owner only)
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands(
+        "antinuke_enable",
+        "antinuke_disable",
+        "antinuke_trust",
+        "antinuke_untrust",
+        "antinuke_status"
+    ),
+    help_text_fn = "antinuke_help",
+    category = "antinuke"
+)]
+pub async fn antinuke(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn antinuke_help() -> String {
+    String::from(
+        "Configure anti-nuke protection. Only the server owner can use these commands.
+Use one of the subcommands `enable`, `disable`, `trust`, `untrust` or `status`.
+        ",
+    )
+}
+
+/// Enable or update anti-nuke protection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "antinuke_enable_help",
+    category = "antinuke",
+    rename = "enable"
+)]
+pub async fn antinuke_enable(
+    ctx: crate::Context<'_>,
+    #[description = "Amount of the same kind of action that trips anti-nuke"] threshold: u32,
+    #[description = "Time period to count actions over"] interval: humantime::Duration,
+) -> Result<(), crate::DynError> {
+    ensure_owner(&ctx).await?;
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if threshold == 0 {
+        return Err(AntinukeError::ZeroThreshold.into());
+    }
+    if *interval > MAX_INTERVAL {
+        return Err(AntinukeError::IntervalTooLong(MAX_INTERVAL).into());
+    }
+
+    let settings = AntinukeSettings {
+        threshold,
+        interval: *interval,
+    };
+    database::set_antinuke_settings(&ctx.data().database, guild_id, &settings).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Anti-nuke protection has been enabled. Anyone that deletes {} channels or roles, bans or kicks {} \
+                    members, or creates {} webhooks within {} will lose their dangerous roles.",
+                    threshold,
+                    threshold,
+                    threshold,
+                    humantime::format_duration(settings.interval)
+                ))
+                .field(
+                    "NOTE",
+                    "Jolt needs the `View Audit Log` and `Manage Roles` permissions, and its role has to be above \
+                    any role it should be able to take away.",
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antinuke_enable_help() -> String {
+    String::from(
+        "Enable anti-nuke protection, or update it if it is already enabled.
+If a user deletes channels, deletes roles, bans members, kicks members or creates webhooks too many times within \
+the interval, every role giving them a dangerous permission is taken away, the owner is DMed, and a report is sent \
+to the logs channel.
+Example: %antinuke enable 3 1m
+        ",
+    )
+}
+
+/// Disable anti-nuke protection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "antinuke_disable_help",
+    category = "antinuke",
+    rename = "disable"
+)]
+pub async fn antinuke_disable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    ensure_owner(&ctx).await?;
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_antinuke_settings(&ctx.data().database, guild_id).await? {
+        return Err(AntinukeError::NotEnabled.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Anti-nuke protection has been disabled."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antinuke_disable_help() -> String {
+    String::from(
+        "Disable anti-nuke protection.
+Example: %antinuke disable
+        ",
+    )
+}
+
+/// Let a user skip anti-nuke checks
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "antinuke_trust_help",
+    category = "antinuke",
+    rename = "trust"
+)]
+pub async fn antinuke_trust(
+    ctx: crate::Context<'_>,
+    #[description = "User to trust"] user: serenity_prelude::User,
+) -> Result<(), crate::DynError> {
+    ensure_owner(&ctx).await?;
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::add_antinuke_trusted_user(&ctx.data().database, guild_id, user.id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("<@{}> is now trusted by anti-nuke.", user.id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antinuke_trust_help() -> String {
+    String::from(
+        "Trust a user, so that anti-nuke never acts against them. Useful for other bots that clean up channels or roles.
+Example: %antinuke trust @User
+        ",
+    )
+}
+
+/// Stop trusting a user
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "antinuke_untrust_help",
+    category = "antinuke",
+    rename = "untrust"
+)]
+pub async fn antinuke_untrust(
+    ctx: crate::Context<'_>,
+    #[description = "User to stop trusting"] user: serenity_prelude::User,
+) -> Result<(), crate::DynError> {
+    ensure_owner(&ctx).await?;
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_antinuke_trusted_user(&ctx.data().database, guild_id, user.id).await? {
+        return Err(AntinukeError::UserNotTrusted(user.id.get()).into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("<@{}> is no longer trusted by anti-nuke.", user.id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antinuke_untrust_help() -> String {
+    String::from(
+        "Stop trusting a user, so that anti-nuke checks them again.
+Example: %antinuke untrust @User
+        ",
+    )
+}
+
+/// Show the anti-nuke settings and trusted users
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "antinuke_status_help",
+    category = "antinuke",
+    rename = "status"
+)]
+pub async fn antinuke_status(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    ensure_owner(&ctx).await?;
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let settings = database::get_antinuke_settings(&ctx.data().database, guild_id).await?;
+    let trusted = database::get_antinuke_trusted_users(&ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::BLUE)
+                    .title("Anti-Nuke Protection")
+                    .field(
+                        "Status",
+                        match settings {
+                            Some(settings) => format!(
+                                "On ({} actions within {})",
+                                settings.threshold,
+                                humantime::format_duration(settings.interval)
+                            ),
+                            None => String::from("Off"),
+                        },
+                        false,
+                    )
+                    .field(
+                        "Trusted Users",
+                        if trusted.is_empty() {
+                            String::from("None")
+                        } else {
+                            trusted
+                                .iter()
+                                .map(|user_id| format!("<@{}>", user_id))
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        },
+                        false,
+                    ),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antinuke_status_help() -> String {
+    String::from(
+        "Show the anti-nuke settings of the server, and which users are trusted.
+Example: %antinuke status
+        ",
+    )
+}
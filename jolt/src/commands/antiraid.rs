@@ -0,0 +1,539 @@
+// anti-raid configuration commands
+
+use poise::serenity_prelude::{self, CreateEmbed};
+use poise::CreateReply;
+
+use crate::antiraid::types::{
+    AgeGateAction, AgeGateSettings, RaidAction, RaidSettings, RaidVerificationLevel,
+};
+use crate::antiraid::{self, MAX_INTERVAL};
+use crate::colors;
+use crate::database;
+use crate::error::{AntiraidError, Error};
+
+/// Configure anti-raid protection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "antiraid_enable",
+        "antiraid_disable",
+        "antiraid_addchannel",
+        "antiraid_removechannel",
+        "antiraid_status",
+        "antiraid_start",
+        "antiraid_end"
+    ),
+    help_text_fn = "antiraid_help",
+    category = "antiraid"
+)]
+pub async fn antiraid(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn antiraid_help() -> String {
+    String::from(
+        "Configure anti-raid protection.
+Use one of the subcommands `enable`, `disable`, `addchannel`, `removechannel`, `status`, `start` or `end`.
+        ",
+    )
+}
+
+/// Enable or update anti-raid protection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_enable_help",
+    category = "antiraid",
+    rename = "enable"
+)]
+pub async fn antiraid_enable(
+    ctx: crate::Context<'_>,
+    #[description = "Amount of joins that starts raid mode"] threshold: u32,
+    #[description = "Time period to count joins over"] interval: humantime::Duration,
+    #[description = "What to do with accounts that join during a raid"] action: RaidAction,
+    #[description = "Verification level to raise the server to during a raid (defaults to High)"]
+    verification_level: Option<RaidVerificationLevel>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if threshold < 2 {
+        return Err(AntiraidError::ThresholdTooLow.into());
+    }
+    if *interval > MAX_INTERVAL {
+        return Err(AntiraidError::IntervalTooLong(MAX_INTERVAL).into());
+    }
+    // Better to find out now than in the middle of a raid
+    if action == RaidAction::Quarantine
+        && database::get_quarantine_role(&ctx.data().database, guild_id)
+            .await?
+            .is_none()
+    {
+        return Err(Error::ConfigNotSetError(String::from("%quarantinerole")).into());
+    }
+
+    let settings = RaidSettings {
+        threshold,
+        interval: *interval,
+        action,
+        verification_level: verification_level.unwrap_or(RaidVerificationLevel::High),
+    };
+    database::set_antiraid_settings(&ctx.data().database, guild_id, &settings).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Anti-raid protection has been enabled. Raid mode will start when {} members join within {}.",
+                    threshold,
+                    humantime::format_duration(settings.interval)
+                ))
+                .field("Action", action.to_string(), true)
+                .field(
+                    "Verification Level",
+                    settings.verification_level.to_string(),
+                    true,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_enable_help() -> String {
+    String::from(
+        "Enable anti-raid protection, or update it if it is already enabled.
+Raid mode starts when the given amount of members join within the interval. During raid mode, the verification level \
+is raised, channels added with `antiraid addchannel` are locked, and new members are kicked or given the quarantine role.
+Example: %antiraid enable 10 30s Kick
+        ",
+    )
+}
+
+/// Disable anti-raid protection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_disable_help",
+    category = "antiraid",
+    rename = "disable"
+)]
+pub async fn antiraid_disable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_antiraid_settings(&ctx.data().database, guild_id).await? {
+        return Err(AntiraidError::NotEnabled.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Anti-raid protection has been disabled."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_disable_help() -> String {
+    String::from(
+        "Disable anti-raid protection. This does not end raid mode if it is already on.
+Example: %antiraid disable
+        ",
+    )
+}
+
+/// Lock a channel whenever raid mode starts
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_addchannel_help",
+    category = "antiraid",
+    rename = "addchannel"
+)]
+pub async fn antiraid_addchannel(
+    ctx: crate::Context<'_>,
+    #[description = "Channel to lock"] channel: serenity_prelude::ChannelId,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::add_raid_lock_channel(&ctx.data().database, guild_id, channel).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!("Channel <#{}> will be locked during raids.", channel),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_addchannel_help() -> String {
+    String::from(
+        "Add a channel to lock whenever raid mode starts. @everyone loses the permission to talk there until raid mode ends.
+Example: %antiraid addchannel #general
+        ",
+    )
+}
+
+/// Stop locking a channel when raid mode starts
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_removechannel_help",
+    category = "antiraid",
+    rename = "removechannel"
+)]
+pub async fn antiraid_removechannel(
+    ctx: crate::Context<'_>,
+    #[description = "Channel to stop locking"] channel: serenity_prelude::ChannelId,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_raid_lock_channel(&ctx.data().database, guild_id, channel).await? {
+        return Err(AntiraidError::ChannelNotLocked(channel.get()).into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Channel <#{}> will no longer be locked during raids.",
+                channel
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_removechannel_help() -> String {
+    String::from(
+        "Stop locking a channel when raid mode starts.
+Example: %antiraid removechannel #general
+        ",
+    )
+}
+
+/// Show the anti-raid settings and whether raid mode is on
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_status_help",
+    category = "antiraid",
+    rename = "status"
+)]
+pub async fn antiraid_status(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let settings = database::get_antiraid_settings(&ctx.data().database, guild_id).await?;
+    let channels = database::get_raid_lock_channels(&ctx.data().database, guild_id).await?;
+    let raid = antiraid::raid_status(ctx.data(), guild_id).await;
+    let age_gate = database::get_age_gate(&ctx.data().database, guild_id).await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Anti-Raid Protection");
+
+        e = match settings {
+            Some(settings) => e
+                .field(
+                    "Trigger",
+                    format!(
+                        "{} joins within {}",
+                        settings.threshold,
+                        humantime::format_duration(settings.interval)
+                    ),
+                    true,
+                )
+                .field("Action", settings.action.to_string(), true)
+                .field(
+                    "Verification Level",
+                    settings.verification_level.to_string(),
+                    true,
+                ),
+            None => e.description("Anti-raid protection is not enabled."),
+        };
+
+        e = e.field(
+            "Locked Channels",
+            if channels.is_empty() {
+                String::from("None")
+            } else {
+                channels
+                    .iter()
+                    .map(|channel| format!("<#{}>", channel))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            },
+            false,
+        );
+
+        e = e.field(
+            "Account Age Gate",
+            match age_gate {
+                Some(age_gate) => format!(
+                    "{} accounts younger than {}",
+                    age_gate.action,
+                    humantime::format_duration(age_gate.min_age)
+                ),
+                None => String::from("Off"),
+            },
+            false,
+        );
+
+        e.field(
+            "Raid Mode",
+            match raid {
+                Some((started_at, flagged)) => format!(
+                    "**On** since <t:{}:R> ({} flagged accounts)",
+                    started_at.unix_timestamp(),
+                    flagged
+                ),
+                None => String::from("Off"),
+            },
+            false,
+        )
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_status_help() -> String {
+    String::from(
+        "Show the anti-raid settings of the server, and whether raid mode is currently on.
+Example: %antiraid status
+        ",
+    )
+}
+
+/// Start raid mode
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_start_help",
+    category = "antiraid",
+    rename = "start"
+)]
+pub async fn antiraid_start(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    antiraid::begin_raid(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        ctx.author().id,
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Raid mode has been started."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_start_help() -> String {
+    String::from(
+        "Start raid mode without waiting for it to be triggered. Anti-raid protection has to be enabled first.
+Example: %antiraid start
+        ",
+    )
+}
+
+/// End raid mode
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "antiraid_end_help",
+    category = "antiraid",
+    rename = "end"
+)]
+pub async fn antiraid_end(
+    ctx: crate::Context<'_>,
+    #[description = "Ban every account flagged during the raid"] ban: Option<bool>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let ban = ban.unwrap_or(false);
+
+    if ban {
+        let can_ban = match ctx.author_member().await {
+            Some(member) => member.permissions(ctx.cache())?.ban_members(),
+            None => false,
+        };
+        if !can_ban {
+            return Err(AntiraidError::MissingBanPermission.into());
+        }
+    }
+
+    let banned = antiraid::end_raid(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id,
+        ban,
+        ctx.author().id,
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            if ban {
+                format!(
+                    "Raid mode has been ended, and {} accounts were banned.",
+                    banned
+                )
+            } else {
+                String::from("Raid mode has been ended.")
+            },
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn antiraid_end_help() -> String {
+    String::from(
+        "End raid mode, putting the verification level and locked channels back the way they were.
+Set `ban` to ban every account that was flagged during the raid.
+Example: %antiraid end true
+        ",
+    )
+}
+
+/// Configure the minimum account age needed to join the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands("agegate_set", "agegate_disable"),
+    help_text_fn = "agegate_help",
+    category = "antiraid"
+)]
+pub async fn agegate(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn agegate_help() -> String {
+    String::from(
+        "Configure the minimum account age needed to join the server.
+Use one of the subcommands `set` or `disable`.
+        ",
+    )
+}
+
+/// Set the minimum account age needed to join the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "agegate_set_help",
+    category = "antiraid",
+    rename = "set"
+)]
+pub async fn agegate_set(
+    ctx: crate::Context<'_>,
+    #[description = "Minimum account age"] age: humantime::Duration,
+    #[description = "What to do with accounts that are too new"] action: AgeGateAction,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if action == AgeGateAction::Quarantine
+        && database::get_quarantine_role(&ctx.data().database, guild_id)
+            .await?
+            .is_none()
+    {
+        return Err(Error::ConfigNotSetError(String::from("%quarantinerole")).into());
+    }
+
+    let settings = AgeGateSettings {
+        min_age: *age,
+        action,
+    };
+    database::set_age_gate(&ctx.data().database, guild_id, Some(&settings)).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Accounts younger than {} will now be {} when they join.",
+                humantime::format_duration(settings.min_age),
+                match action {
+                    AgeGateAction::Kick => "kicked",
+                    AgeGateAction::Timeout => "timed out until they are old enough",
+                    AgeGateAction::Quarantine => "quarantined",
+                }
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn agegate_set_help() -> String {
+    String::from(
+        "Set the minimum account age needed to join the server. Members with newer accounts are kicked, \
+quarantined, or timed out until their account is old enough (up to 28 days). They are sent a DM explaining why.
+Example: %agegate set 7d Kick
+        ",
+    )
+}
+
+/// Stop checking the account age of new members
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "agegate_disable_help",
+    category = "antiraid",
+    rename = "disable"
+)]
+pub async fn agegate_disable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if database::get_age_gate(&ctx.data().database, guild_id)
+        .await?
+        .is_none()
+    {
+        return Err(AntiraidError::AgeGateNotEnabled.into());
+    }
+    database::set_age_gate(&ctx.data().database, guild_id, None).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("The account age gate has been disabled."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn agegate_disable_help() -> String {
+    String::from(
+        "Stop checking the account age of new members.
+Example: %agegate disable
+        ",
+    )
+}
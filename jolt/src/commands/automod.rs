@@ -0,0 +1,1174 @@
+// automod configuration commands
+
+use std::time::Duration;
+
+use poise::serenity_prelude::{self, CreateEmbed};
+use poise::CreateReply;
+
+use crate::automod::actions;
+use crate::automod::error::AutomodError;
+use crate::automod::filter;
+use crate::automod::links;
+use crate::automod::phishing::Blocklist;
+use crate::automod::spam::MAX_INTERVAL;
+use crate::automod::types::{
+    AutomodAction, AutomodRule, AutomodRuleSettings, DomainList, ExemptionKind, FilterKind,
+    LinkFilterSettings, NameFilterAction, Punishment,
+};
+use crate::colors;
+use crate::database;
+
+/// Configure the automod rules of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "automod_enable",
+        "automod_disable",
+        "automod_list",
+        "automod_ghostpings"
+    ),
+    help_text_fn = "automod_help",
+    category = "automod"
+)]
+pub async fn automod(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn automod_help() -> String {
+    String::from(
+        "Configure the automod rules of the server.
+Use one of the subcommands `enable`, `disable`, `list` or `ghostpings`.
+        ",
+    )
+}
+
+/// Enable or update an automod rule
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "automod_enable_help",
+    category = "automod",
+    rename = "enable"
+)]
+pub async fn automod_enable(
+    ctx: crate::Context<'_>,
+    #[description = "Rule to enable"] rule: AutomodRule,
+    #[description = "Amount needed to trigger the rule (messages, mentions, percent of caps, etc.)"]
+    threshold: u32,
+    #[description = "Action to take when the rule is broken"] action: AutomodAction,
+    #[description = "Length of the action (for timeouts, mutes and bans)"] length: Option<
+        humantime::Duration,
+    >,
+    #[description = "Time period to count messages over (for message rate and duplicates)"]
+    interval: Option<humantime::Duration>,
+    #[description = "Only log what would have happened, without deleting or punishing"]
+    log_only: Option<bool>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if threshold == 0 {
+        return Err(AutomodError::ZeroThreshold.into());
+    }
+
+    let interval = interval
+        .map(|interval| *interval)
+        .unwrap_or(Duration::from_secs(10));
+    if interval > MAX_INTERVAL {
+        return Err(AutomodError::IntervalTooLong(MAX_INTERVAL).into());
+    }
+
+    let settings = AutomodRuleSettings {
+        rule,
+        threshold,
+        interval,
+        punishment: Punishment {
+            action,
+            duration: length.map(|length| *length),
+            log_only: log_only.unwrap_or(false),
+        },
+    };
+    actions::validate_punishment(&ctx.data().database, guild_id, &settings.punishment).await?;
+    database::set_automod_rule(&ctx.data().database, guild_id, &settings).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Automod rule **{}** has been enabled.", rule))
+                .field("Threshold", threshold.to_string(), true)
+                .field("Action", settings.punishment.to_string(), true),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn automod_enable_help() -> String {
+    String::from(
+        "Enable an automod rule, or update it if it is already enabled.
+The threshold is the amount of messages (message rate, duplicate messages), mentions, emojis, newlines, \
+or the percentage of capital letters in a message that is needed to break the rule.
+Mass mentions count every user and role mentioned in a message, along with @everyone and @here.
+With log only on, the rule reports what it would have done to the logs channel without deleting or punishing \
+anything, which is useful for tuning thresholds before enforcing them.
+Timeouts can be at most 28 days, and mutes need a mute role to be set with `muterole` first.
+Example: %automod enable \"Message Rate\" 5 Timeout 10m 5s
+        ",
+    )
+}
+
+/// Disable an automod rule
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "automod_disable_help",
+    category = "automod",
+    rename = "disable"
+)]
+pub async fn automod_disable(
+    ctx: crate::Context<'_>,
+    #[description = "Rule to disable"] rule: AutomodRule,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_automod_rule(&ctx.data().database, guild_id, rule).await? {
+        return Err(AutomodError::RuleNotEnabled(rule.to_string()).into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Automod rule **{}** has been disabled.", rule)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn automod_disable_help() -> String {
+    String::from(
+        "Disable an automod rule.
+Example: %automod disable \"Excessive Caps\"
+        ",
+    )
+}
+
+/// List the enabled automod rules
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "automod_list_help",
+    category = "automod",
+    rename = "list"
+)]
+pub async fn automod_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let rules = database::get_automod_rules(&ctx.data().database, guild_id).await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Automod Rules");
+
+        if rules.is_empty() {
+            e = e.description("No automod rules are enabled.");
+        }
+
+        for settings in rules {
+            e = e.field(
+                settings.rule.to_string(),
+                format!(
+                    "{}{}{}",
+                    format!("\n**Threshold:** {}", settings.threshold),
+                    if settings.rule.uses_interval() {
+                        format!(
+                            "\n**Interval:** {}",
+                            humantime::format_duration(settings.interval)
+                        )
+                    } else {
+                        String::new()
+                    },
+                    format!("\n**Action:** {}", settings.punishment),
+                ),
+                false,
+            );
+        }
+
+        e
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn automod_list_help() -> String {
+    String::from(
+        "List the automod rules that are enabled in the server.
+Example: %automod list
+        ",
+    )
+}
+
+/// Enable or disable ghost ping detection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "automod_ghostpings_help",
+    category = "automod",
+    rename = "ghostpings"
+)]
+pub async fn automod_ghostpings(
+    ctx: crate::Context<'_>,
+    #[description = "Whether or not to detect ghost pings"] enabled: bool,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_ghost_ping_detection(&ctx.data().database, guild_id, enabled).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Ghost ping detection has been {}.",
+                if enabled { "enabled" } else { "disabled" }
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn automod_ghostpings_help() -> String {
+    String::from(
+        "Enable or disable ghost ping detection. When a message that mentions users or roles is deleted, or edited to \
+remove its mentions, within 30 seconds of being sent, a notice naming the author and everyone they pinged is posted \
+in the channel.
+Example: %automod ghostpings true
+        ",
+    )
+}
+
+/// Configure the word and regex filter of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "filter_add",
+        "filter_remove",
+        "filter_list",
+        "filter_exempt",
+        "filter_unexempt",
+        "filter_names"
+    ),
+    help_text_fn = "filter_help",
+    category = "automod"
+)]
+pub async fn filter(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn filter_help() -> String {
+    String::from(
+        "Configure the word and regex filter of the server.
+Use one of the subcommands `add`, `remove`, `list`, `exempt`, `unexempt` or `names`.
+        ",
+    )
+}
+
+/// Add a word or regex to the filter
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "filter_add_help",
+    category = "automod",
+    rename = "add"
+)]
+pub async fn filter_add(
+    ctx: crate::Context<'_>,
+    #[description = "Whether the pattern is a word or a regex"] kind: FilterKind,
+    #[description = "Word or regex to filter"] pattern: String,
+    #[description = "Action to take when the filter is matched"] action: AutomodAction,
+    #[description = "Length of the action (for timeouts, mutes and bans)"] length: Option<
+        humantime::Duration,
+    >,
+    #[description = "Only log what would have happened, without deleting or punishing"]
+    log_only: Option<bool>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    // Compiling here is only to make sure the pattern is valid, the cache compiles it again later
+    filter::compile_pattern(kind, &pattern)?;
+
+    let punishment = Punishment {
+        action,
+        duration: length.map(|length| *length),
+        log_only: log_only.unwrap_or(false),
+    };
+    actions::validate_punishment(&ctx.data().database, guild_id, &punishment).await?;
+    let id = database::add_filter_rule(&ctx.data().database, guild_id, kind, &pattern, &punishment)
+        .await?;
+    filter::invalidate_filter(ctx.data(), guild_id).await;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::GREEN)
+                    .description(format!("Added filter rule **{}**.", id))
+                    .field(kind.to_string(), format!("`{}`", pattern), true)
+                    .field("Action", punishment.to_string(), true),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn filter_add_help() -> String {
+    String::from(
+        "Add a word or regex to the filter.
+Words are matched as whole words, and catch common tricks like leetspeak, lookalike letters and invisible characters. \
+Regexes are matched against the message as-is, as well as the normalized message.
+Patterns with spaces in them need to be wrapped in quotes.
+With log only on, matches are reported to the logs channel without deleting or punishing anything.
+Example: %filter add Word badword Timeout 1h
+        ",
+    )
+}
+
+/// Remove a rule from the filter
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "filter_remove_help",
+    category = "automod",
+    rename = "remove"
+)]
+pub async fn filter_remove(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the filter rule to remove"] id: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_filter_rule(&ctx.data().database, guild_id, id).await? {
+        return Err(AutomodError::FilterRuleNotFound(id).into());
+    }
+    filter::invalidate_filter(ctx.data(), guild_id).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Removed filter rule **{}**.", id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn filter_remove_help() -> String {
+    String::from(
+        "Remove a rule from the filter. Specified by ID.
+Example: %filter remove 3
+        ",
+    )
+}
+
+/// List the rules and exemptions of the filter
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "filter_list_help",
+    category = "automod",
+    rename = "list"
+)]
+pub async fn filter_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let rules = database::get_filter_rules(&ctx.data().database, guild_id).await?;
+    let exemptions = database::get_filter_exemptions(&ctx.data().database, guild_id).await?;
+    let name_filter = database::get_name_filter(&ctx.data().database, guild_id).await?;
+
+    // Filtered words are likely to be things people don't want posted in public, so this is kept ephemeral
+    ctx.send(
+        CreateReply::default()
+            .embed({
+                let mut e = CreateEmbed::default()
+                    .color(colors::BLUE)
+                    .title("Filter Rules");
+
+                if rules.is_empty() {
+                    e = e.description("The filter is empty.");
+                }
+
+                // Embeds can only hold 25 fields, two are saved for the exemptions and the name filter
+                for rule in rules.iter().take(23) {
+                    e = e.field(
+                        format!("ID {}", rule.id),
+                        format!(
+                            "{}{}",
+                            format!("\n**{}:** `{}`", rule.kind, rule.pattern),
+                            format!("\n**Action:** {}", rule.punishment),
+                        ),
+                        false,
+                    );
+                }
+
+                if !exemptions.is_empty() {
+                    e = e.field(
+                        "Exemptions",
+                        exemptions
+                            .iter()
+                            .map(|(target_id, kind)| match kind {
+                                ExemptionKind::Role => format!("<@&{}>", target_id),
+                                ExemptionKind::Channel => format!("<#{}>", target_id),
+                            })
+                            .collect::<Vec<String>>()
+                            .join(" "),
+                        false,
+                    );
+                }
+
+                if let Some(action) = name_filter {
+                    e = e.field("Name Filter", action.to_string(), false);
+                }
+
+                e
+            })
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn filter_list_help() -> String {
+    String::from(
+        "List the rules and exemptions of the filter.
+Example: %filter list
+        ",
+    )
+}
+
+/// Exempt a role or channel from the filter
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "filter_exempt_help",
+    category = "automod",
+    rename = "exempt"
+)]
+pub async fn filter_exempt(
+    ctx: crate::Context<'_>,
+    #[description = "Role to exempt"] role: Option<serenity_prelude::RoleId>,
+    #[description = "Channel to exempt"] channel: Option<serenity_prelude::ChannelId>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let (target_id, kind, mention) = match (role, channel) {
+        (Some(role_id), _) => (
+            role_id.get(),
+            ExemptionKind::Role,
+            format!("<@&{}>", role_id),
+        ),
+        (None, Some(channel_id)) => (
+            channel_id.get(),
+            ExemptionKind::Channel,
+            format!("<#{}>", channel_id),
+        ),
+        (None, None) => return Err(AutomodError::NoExemptionTarget.into()),
+    };
+
+    database::add_filter_exemption(&ctx.data().database, guild_id, target_id, kind).await?;
+    filter::invalidate_filter(ctx.data(), guild_id).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("{} is now exempt from the filter.", mention)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn filter_exempt_help() -> String {
+    String::from(
+        "Exempt a role or channel from the filter.
+Example: %filter exempt @Trusted
+        ",
+    )
+}
+
+/// Remove a filter exemption from a role or channel
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "filter_unexempt_help",
+    category = "automod",
+    rename = "unexempt"
+)]
+pub async fn filter_unexempt(
+    ctx: crate::Context<'_>,
+    #[description = "Role to remove the exemption from"] role: Option<serenity_prelude::RoleId>,
+    #[description = "Channel to remove the exemption from"] channel: Option<
+        serenity_prelude::ChannelId,
+    >,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let (target_id, mention) = match (role, channel) {
+        (Some(role_id), _) => (role_id.get(), format!("<@&{}>", role_id)),
+        (None, Some(channel_id)) => (channel_id.get(), format!("<#{}>", channel_id)),
+        (None, None) => return Err(AutomodError::NoExemptionTarget.into()),
+    };
+
+    database::remove_filter_exemption(&ctx.data().database, guild_id, target_id).await?;
+    filter::invalidate_filter(ctx.data(), guild_id).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("{} is no longer exempt from the filter.", mention)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn filter_unexempt_help() -> String {
+    String::from(
+        "Remove a filter exemption from a role or channel.
+Example: %filter unexempt #memes
+        ",
+    )
+}
+
+/// Apply the filter to member names
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "filter_names_help",
+    category = "automod",
+    rename = "names"
+)]
+pub async fn filter_names(
+    ctx: crate::Context<'_>,
+    #[description = "What to do to members with filtered names (leave empty to disable)"]
+    action: Option<NameFilterAction>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_name_filter(&ctx.data().database, guild_id, action).await?;
+
+    let description = match action {
+        Some(action) => format!(
+            "Member names are now checked against the filter. Members with filtered names get: {}.",
+            action
+        ),
+        None => String::from("The filter no longer applies to member names."),
+    };
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn filter_names_help() -> String {
+    String::from(
+        "Apply the filter to usernames, global display names and nicknames when members join or change their name.
+`Reset Nickname` removes a filtered nickname, or gives the member a placeholder nickname if their account name is \
+filtered. `Quarantine` needs a quarantine role to be set with `quarantinerole`. Role exemptions are respected.
+Leave the action empty to stop filtering names.
+Example: %filter names Quarantine
+        ",
+    )
+}
+
+/// Configure the invite and link filter of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "linkfilter_set",
+        "linkfilter_domain",
+        "linkfilter_removedomain",
+        "linkfilter_whitelist",
+        "linkfilter_unwhitelist",
+        "linkfilter_list"
+    ),
+    help_text_fn = "linkfilter_help",
+    category = "automod"
+)]
+pub async fn linkfilter(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn linkfilter_help() -> String {
+    String::from(
+        "Configure the invite and link filter of the server.
+Use one of the subcommands `set`, `domain`, `removedomain`, `whitelist`, `unwhitelist` or `list`.
+Roles and channels that are exempt from the filter are also exempt from the link filter.
+        ",
+    )
+}
+
+/// Turn invite and link filtering on or off
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "linkfilter_set_help",
+    category = "automod",
+    rename = "set"
+)]
+pub async fn linkfilter_set(
+    ctx: crate::Context<'_>,
+    #[description = "Filter invites to servers that aren't whitelisted"] invites: bool,
+    #[description = "Filter links using the allow and deny lists"] urls: bool,
+    #[description = "Action to take when the filter is matched"] action: AutomodAction,
+    #[description = "Length of the action (for timeouts, mutes and bans)"] length: Option<
+        humantime::Duration,
+    >,
+    #[description = "Only log what would have happened, without deleting or punishing"]
+    log_only: Option<bool>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let settings = LinkFilterSettings {
+        filter_invites: invites,
+        filter_urls: urls,
+        punishment: Punishment {
+            action,
+            duration: length.map(|length| *length),
+            log_only: log_only.unwrap_or(false),
+        },
+    };
+    actions::validate_punishment(&ctx.data().database, guild_id, &settings.punishment).await?;
+    database::set_link_filter_settings(&ctx.data().database, guild_id, &settings).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Link filter settings have been updated.")
+                .field("Invites", if invites { "On" } else { "Off" }, true)
+                .field("Links", if urls { "On" } else { "Off" }, true)
+                .field("Action", settings.punishment.to_string(), true),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn linkfilter_set_help() -> String {
+    String::from(
+        "Turn invite and link filtering on or off, and set what happens to people that break it.
+Invites to this server and to whitelisted servers are always allowed.
+With log only on, matches are reported to the logs channel without deleting or punishing anything.
+Example: %linkfilter set true false Delete
+        ",
+    )
+}
+
+/// Add a domain to the allow or deny list
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "linkfilter_domain_help",
+    category = "automod",
+    rename = "domain"
+)]
+pub async fn linkfilter_domain(
+    ctx: crate::Context<'_>,
+    #[description = "List to add the domain to"] list: DomainList,
+    #[description = "Domain to add (subdomains are included)"] domain: String,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let domain = links::clean_domain(&domain);
+
+    database::set_link_filter_domain(&ctx.data().database, guild_id, &domain, list).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Added `{}` to the **{}** list.", domain, list)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn linkfilter_domain_help() -> String {
+    String::from(
+        "Add a domain to the allow or deny list of the link filter. Subdomains are included.
+Links to domains in the deny list are always filtered. \
+If there is anything in the allow list, links to any domain that isn't in it are filtered too.
+Example: %linkfilter domain Deny example.com
+        ",
+    )
+}
+
+/// Remove a domain from the allow or deny list
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "linkfilter_removedomain_help",
+    category = "automod",
+    rename = "removedomain"
+)]
+pub async fn linkfilter_removedomain(
+    ctx: crate::Context<'_>,
+    #[description = "Domain to remove"] domain: String,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let domain = links::clean_domain(&domain);
+
+    if !database::remove_link_filter_domain(&ctx.data().database, guild_id, &domain).await? {
+        return Err(AutomodError::DomainNotFound(domain).into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Removed `{}` from the link filter.", domain)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn linkfilter_removedomain_help() -> String {
+    String::from(
+        "Remove a domain from the allow or deny list of the link filter.
+Example: %linkfilter removedomain example.com
+        ",
+    )
+}
+
+/// Gets a server ID from either an ID or an invite to the server.
+async fn parse_server(
+    ctx: &crate::Context<'_>,
+    server: &str,
+) -> Result<serenity_prelude::GuildId, AutomodError> {
+    if let Ok(id) = server.parse::<u64>() {
+        if id != 0 {
+            return Ok(serenity_prelude::GuildId::new(id));
+        }
+    }
+
+    let code = links::extract_invites(server)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| server.to_string());
+
+    links::resolve_invite(ctx.http(), &code)
+        .await
+        .ok_or_else(|| AutomodError::ServerNotFound(server.to_string()))
+}
+
+/// Allow invites to another server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "linkfilter_whitelist_help",
+    category = "automod",
+    rename = "whitelist"
+)]
+pub async fn linkfilter_whitelist(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the server, or an invite to it"] server: String,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let whitelisted_guild_id = parse_server(&ctx, &server).await?;
+
+    database::add_invite_whitelist(&ctx.data().database, guild_id, whitelisted_guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Invites to server **{}** are now allowed.",
+                whitelisted_guild_id
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn linkfilter_whitelist_help() -> String {
+    String::from(
+        "Allow invites to another server, using either the ID of the server or an invite to it.
+Example: %linkfilter whitelist discord.gg/rust-lang
+        ",
+    )
+}
+
+/// Stop allowing invites to another server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "linkfilter_unwhitelist_help",
+    category = "automod",
+    rename = "unwhitelist"
+)]
+pub async fn linkfilter_unwhitelist(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the server, or an invite to it"] server: String,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let whitelisted_guild_id = parse_server(&ctx, &server).await?;
+
+    if !database::remove_invite_whitelist(&ctx.data().database, guild_id, whitelisted_guild_id)
+        .await?
+    {
+        return Err(AutomodError::ServerNotWhitelisted(whitelisted_guild_id.get()).into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Invites to server **{}** are no longer allowed.",
+                whitelisted_guild_id
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn linkfilter_unwhitelist_help() -> String {
+    String::from(
+        "Stop allowing invites to another server.
+Example: %linkfilter unwhitelist 273534239310479360
+        ",
+    )
+}
+
+/// Show the settings of the link filter
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "linkfilter_list_help",
+    category = "automod",
+    rename = "list"
+)]
+pub async fn linkfilter_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let database = &ctx.data().database;
+
+    let settings = database::get_link_filter_settings(database, guild_id).await?;
+    let domains = database::get_link_filter_domains(database, guild_id).await?;
+    let whitelist = database::get_invite_whitelist(database, guild_id).await?;
+
+    let format_list = |list: DomainList| {
+        let entries: Vec<String> = domains
+            .iter()
+            .filter(|(_, domain_list)| *domain_list == list)
+            .map(|(domain, _)| format!("`{}`", domain))
+            .collect();
+
+        if entries.is_empty() {
+            String::from("None")
+        } else {
+            entries.join(", ")
+        }
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title("Link Filter")
+                .field(
+                    "Invites",
+                    match settings {
+                        Some(settings) if settings.filter_invites => "On",
+                        _ => "Off",
+                    },
+                    true,
+                )
+                .field(
+                    "Links",
+                    match settings {
+                        Some(settings) if settings.filter_urls => "On",
+                        _ => "Off",
+                    },
+                    true,
+                )
+                .field(
+                    "Action",
+                    settings
+                        .map(|settings| settings.punishment.to_string())
+                        .unwrap_or_else(|| String::from("None")),
+                    true,
+                )
+                .field("Allowed Domains", format_list(DomainList::Allow), false)
+                .field("Denied Domains", format_list(DomainList::Deny), false)
+                .field(
+                    "Whitelisted Servers",
+                    if whitelist.is_empty() {
+                        String::from("None")
+                    } else {
+                        whitelist
+                            .iter()
+                            .map(|id| id.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    },
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn linkfilter_list_help() -> String {
+    String::from(
+        "Show the settings of the link filter.
+Example: %linkfilter list
+        ",
+    )
+}
+
+/// Configure protection against phishing links
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands(
+        "phishing_enable",
+        "phishing_disable",
+        "phishing_status",
+        "phishing_reload"
+    ),
+    help_text_fn = "phishing_help",
+    category = "automod"
+)]
+pub async fn phishing(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn phishing_help() -> String {
+    String::from(
+        "Configure protection against phishing links.
+Use one of the subcommands `enable`, `disable`, `status` or `reload`.
+        ",
+    )
+}
+
+/// Delete phishing links and time out whoever posts them
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "phishing_enable_help",
+    category = "automod",
+    rename = "enable"
+)]
+pub async fn phishing_enable(
+    ctx: crate::Context<'_>,
+    #[description = "Length of the timeout (defaults to 1 day)"] length: Option<
+        humantime::Duration,
+    >,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let length = length
+        .map(|length| *length)
+        .unwrap_or(Duration::from_secs(24 * 60 * 60));
+    if length > actions::MAX_TIMEOUT {
+        return Err(AutomodError::TimeoutTooLong(actions::MAX_TIMEOUT).into());
+    }
+
+    database::set_phishing_protection(&ctx.data().database, guild_id, Some(length)).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Phishing protection has been enabled. Users that post phishing links will be timed out for {}.",
+                humantime::format_duration(length)
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn phishing_enable_help() -> String {
+    String::from(
+        "Delete messages with links to known phishing domains or to lookalikes of sites like Discord and Steam, and time out whoever posted them. Timeouts can be at most 28 days.
+Unlike other automod rules, phishing protection can't be set to log only, and it applies in exempt channels and to exempt roles too.
+Example: %phishing enable 7d
+        ",
+    )
+}
+
+/// Stop checking for phishing links
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "phishing_disable_help",
+    category = "automod",
+    rename = "disable"
+)]
+pub async fn phishing_disable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_phishing_protection(&ctx.data().database, guild_id, None).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Phishing protection has been disabled."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn phishing_disable_help() -> String {
+    String::from(
+        "Stop checking messages for phishing links.
+Example: %phishing disable
+        ",
+    )
+}
+
+/// Show the status of phishing protection
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "phishing_status_help",
+    category = "automod",
+    rename = "status"
+)]
+pub async fn phishing_status(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let timeout = database::get_phishing_protection(&ctx.data().database, guild_id).await?;
+    let domain_count = ctx.data().automod.blocklist.read().await.len();
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title("Phishing Protection")
+                .field(
+                    "Status",
+                    match timeout {
+                        Some(timeout) => {
+                            format!("On (timeout for {})", humantime::format_duration(timeout))
+                        }
+                        None => String::from("Off"),
+                    },
+                    true,
+                )
+                .field("Known Domains", domain_count.to_string(), true),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn phishing_status_help() -> String {
+    String::from(
+        "Show whether phishing protection is on, and how many phishing domains are known.
+Example: %phishing status
+        ",
+    )
+}
+
+/// Reload the phishing blocklist from disk (bot owner only)
+#[poise::command(
+    prefix_command,
+    slash_command,
+    owners_only,
+    help_text_fn = "phishing_reload_help",
+    category = "automod",
+    rename = "reload"
+)]
+pub async fn phishing_reload(
+    ctx: crate::Context<'_>,
+    #[description = "Path of the blocklist file (defaults to the one in the config)"] path: Option<
+        String,
+    >,
+) -> Result<(), crate::DynError> {
+    let path = path
+        .or_else(|| ctx.data().config.phishing_blocklist.clone())
+        .ok_or(AutomodError::NoBlocklistPath)?;
+
+    let blocklist = Blocklist::load(&path)?;
+    let domain_count = blocklist.len();
+    *ctx.data().automod.blocklist.write().await = blocklist;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::GREEN)
+                    .description(format!(
+                        "Loaded **{}** phishing domains from `{}`.",
+                        domain_count, path
+                    )),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn phishing_reload_help() -> String {
+    String::from(
+        "Reload the phishing blocklist from a file on the bot's machine. Only the bot owner can use this.
+The file should have one domain per line.
+Example: %phishing reload /etc/jolt/phishing.txt
+        ",
+    )
+}
@@ -0,0 +1,289 @@
+// autorole commands
+
+use poise::serenity_prelude::{self, CreateEmbed};
+use poise::CreateReply;
+
+use crate::autoroles;
+use crate::autoroles::types::AutoroleTarget;
+use crate::colors;
+use crate::commands::moderation::utilities::check_role_hierarchy;
+use crate::database;
+use crate::error::AutoroleError;
+
+/// Give roles to members when they join
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    subcommands(
+        "autorole_add",
+        "autorole_remove",
+        "autorole_delay",
+        "autorole_screening",
+        "autorole_list"
+    ),
+    help_text_fn = "autorole_help",
+    category = "roles"
+)]
+pub async fn autorole(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn autorole_help() -> String {
+    String::from(
+        "Give roles to members when they join, with separate roles for humans and bots.
+Autoroles can also be set up from the `configure` panel.
+Use one of the subcommands `add`, `remove`, `delay`, `screening` or `list`.
+        ",
+    )
+}
+
+/// Give a role to members when they join
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "autorole_add_help",
+    category = "roles",
+    rename = "add"
+)]
+pub async fn autorole_add(
+    ctx: crate::Context<'_>,
+    #[description = "Role to give"] role: serenity_prelude::Role,
+    #[description = "Who gets the role (defaults to humans)"] target: Option<AutoroleTarget>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let target = target.unwrap_or(AutoroleTarget::Humans);
+
+    let existing =
+        database::get_autoroles(&ctx.data().database, guild_id, target.is_bots()).await?;
+    if !existing.contains(&role.id) && existing.len() >= autoroles::MAX_AUTOROLES {
+        return Err(AutoroleError::TooManyRoles(autoroles::MAX_AUTOROLES).into());
+    }
+    check_role_hierarchy(&ctx, &role).await?;
+
+    database::add_autorole(&ctx.data().database, guild_id, role.id, target.is_bots()).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!("{} now get <@&{}> when they join.", target, role.id),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn autorole_add_help() -> String {
+    String::from(
+        "Give a role to members when they join. Humans and bots each have their own autoroles, up to 25 of each.
+If verification is turned on, humans only get their autoroles once they've verified.
+The role has to be below both your highest role and Jolt's.
+Example: %autorole add @Member Humans
+        ",
+    )
+}
+
+/// Stop giving a role to members when they join
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "autorole_remove_help",
+    category = "roles",
+    rename = "remove"
+)]
+pub async fn autorole_remove(
+    ctx: crate::Context<'_>,
+    #[description = "Role to stop giving"] role: serenity_prelude::Role,
+    #[description = "Who stops getting the role (defaults to both)"] target: Option<AutoroleTarget>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_autorole(
+        &ctx.data().database,
+        guild_id,
+        role.id,
+        target.map(|target| target.is_bots()),
+    )
+    .await?
+    {
+        return Err(AutoroleError::NotFound.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("<@&{}> is no longer an autorole.", role.id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn autorole_remove_help() -> String {
+    String::from(
+        "Stop giving a role to members when they join. Members keep the role if they already have it.
+Example: %autorole remove @Member
+        ",
+    )
+}
+
+/// Make humans wait before getting their autoroles
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "autorole_delay_help",
+    category = "roles",
+    rename = "delay"
+)]
+pub async fn autorole_delay(
+    ctx: crate::Context<'_>,
+    #[description = "How long to wait (leave empty to give roles right away)"] length: Option<
+        humantime::Duration,
+    >,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let delay = length.map(|length| *length);
+
+    database::set_autorole_delay(&ctx.data().database, guild_id, delay).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            match delay {
+                Some(delay) => format!(
+                    "Humans now get their autoroles {} after joining.",
+                    humantime::format_duration(delay)
+                ),
+                None => String::from("Humans now get their autoroles right away."),
+            },
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn autorole_delay_help() -> String {
+    String::from(
+        "Make humans wait before getting their autoroles, or give them right away if no length is given. Bots always get theirs right away.
+If members have to pass membership screening first, the delay starts once they do.
+Example: %autorole delay 10m
+        ",
+    )
+}
+
+/// Make humans pass membership screening before getting their autoroles
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "autorole_screening_help",
+    category = "roles",
+    rename = "screening"
+)]
+pub async fn autorole_screening(
+    ctx: crate::Context<'_>,
+    #[description = "Whether to wait for membership screening"] enabled: bool,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_autorole_after_screening(&ctx.data().database, guild_id, enabled).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            if enabled {
+                "Humans now get their autoroles after passing membership screening."
+            } else {
+                "Humans no longer wait for membership screening to get their autoroles."
+            },
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn autorole_screening_help() -> String {
+    String::from(
+        "Make humans pass membership screening (the server's rules screen) before getting their autoroles.
+This only does something if membership screening is turned on in the server settings.
+Example: %autorole screening true
+        ",
+    )
+}
+
+/// List the autoroles of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "autorole_list_help",
+    category = "roles",
+    rename = "list"
+)]
+pub async fn autorole_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let embed = autoroles_embed(&ctx.data().database, guild_id).await?;
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn autorole_list_help() -> String {
+    String::from(
+        "List the autoroles of the server, along with when humans get them.
+Example: %autorole list
+        ",
+    )
+}
+
+/// Shows the autoroles and their settings. Also used by the `configure` panel.
+pub async fn autoroles_embed(
+    database: &sqlx::SqlitePool,
+    guild_id: serenity_prelude::GuildId,
+) -> Result<CreateEmbed, crate::DynError> {
+    let humans = database::get_autoroles(database, guild_id, false).await?;
+    let bots = database::get_autoroles(database, guild_id, true).await?;
+    let settings = database::get_autorole_settings(database, guild_id).await?;
+
+    let mention_roles = |role_ids: &[serenity_prelude::RoleId]| {
+        if role_ids.is_empty() {
+            String::from("None")
+        } else {
+            role_ids
+                .iter()
+                .map(|role_id| format!("<@&{}>", role_id))
+                .collect::<Vec<String>>()
+                .join(", ")
+        }
+    };
+
+    Ok(CreateEmbed::default()
+        .color(colors::BLUE)
+        .title("Autoroles")
+        .field("Humans", mention_roles(&humans), false)
+        .field("Bots", mention_roles(&bots), false)
+        .field(
+            "Delay",
+            match settings.delay {
+                Some(delay) => humantime::format_duration(delay).to_string(),
+                None => String::from("None"),
+            },
+            true,
+        )
+        .field(
+            "After Screening",
+            if settings.after_screening {
+                "Yes"
+            } else {
+                "No"
+            },
+            true,
+        ))
+}
@@ -0,0 +1,323 @@
+// server backup commands
+
+use poise::serenity_prelude::{CreateEmbed, Timestamp};
+use poise::CreateReply;
+
+use crate::backup::{self, Snapshot, SNAPSHOT_VERSION};
+use crate::colors;
+use crate::database;
+use crate::error::BackupError;
+
+// How many snapshots `backup list` shows
+const LIST_LIMIT: u32 = 15;
+
+// Discord caps embed descriptions at 4096 characters, so long diffs are cut short before that
+const DIFF_LIMIT: usize = 3900;
+
+async fn load_snapshot(ctx: &crate::Context<'_>, id: u64) -> Result<Snapshot, crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let (info, document) = database::get_snapshot(&ctx.data().database, guild_id, id)
+        .await?
+        .ok_or(BackupError::SnapshotNotFound(id))?;
+
+    Ok(backup::parse(&info, &document)?)
+}
+
+/// Save and restore snapshots of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands(
+        "backup_create",
+        "backup_list",
+        "backup_diff",
+        "backup_restore",
+        "backup_delete"
+    ),
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "backup_help",
+    category = "backup"
+)]
+pub async fn backup(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn backup_help() -> String {
+    String::from(
+        "Save snapshots of the server's roles, channels, categories, permission overwrites and Jolt settings, and \
+restore them later.
+Use one of the subcommands `create`, `list`, `diff`, `restore` or `delete`.
+        ",
+    )
+}
+
+/// Save a snapshot of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "backup_create_help",
+    category = "backup",
+    rename = "create"
+)]
+pub async fn backup_create(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    ctx.defer().await?;
+
+    let snapshot = backup::capture(ctx.serenity_context(), ctx.data(), guild_id).await?;
+    let id = database::add_snapshot(
+        &ctx.data().database,
+        guild_id,
+        ctx.author().id,
+        Timestamp::now(),
+        SNAPSHOT_VERSION,
+        &serde_json::to_string(&snapshot)?,
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Saved snapshot #{} with {} roles and {} channels.",
+                id,
+                snapshot.roles.len(),
+                snapshot.channels.len()
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn backup_create_help() -> String {
+    String::from(
+        "Save a snapshot of the server's roles, channels, categories, permission overwrites and Jolt settings.
+Example: %backup create
+        ",
+    )
+}
+
+/// List the saved snapshots of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "backup_list_help",
+    category = "backup",
+    rename = "list"
+)]
+pub async fn backup_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let snapshots = database::get_snapshots(&ctx.data().database, guild_id, LIST_LIMIT).await?;
+
+    let description = if snapshots.is_empty() {
+        String::from("There are no saved snapshots.")
+    } else {
+        snapshots
+            .iter()
+            .map(|info| {
+                format!(
+                    "**#{}** - <t:{}:f> by <@{}>",
+                    info.id,
+                    info.created_at.unix_timestamp(),
+                    info.created_by
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title("Snapshots")
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn backup_list_help() -> String {
+    String::from(
+        "List the most recent snapshots of the server.
+Example: %backup list
+        ",
+    )
+}
+
+/// Show what changed between two snapshots
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "backup_diff_help",
+    category = "backup",
+    rename = "diff"
+)]
+pub async fn backup_diff(
+    ctx: crate::Context<'_>,
+    #[description = "Snapshot to compare from"] id: u64,
+    #[description = "Snapshot to compare to (defaults to now)"] other: Option<u64>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    ctx.defer().await?;
+
+    let old = load_snapshot(&ctx, id).await?;
+    let (new, new_name) = match other {
+        Some(other) => (load_snapshot(&ctx, other).await?, format!("#{}", other)),
+        None => (
+            backup::capture(ctx.serenity_context(), ctx.data(), guild_id).await?,
+            String::from("now"),
+        ),
+    };
+
+    let lines = backup::diff(&old, &new);
+    let mut description = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        if description.len() + line.len() > DIFF_LIMIT {
+            description.push_str(&format!("...and {} more", lines.len() - index));
+            break;
+        }
+        description.push_str(line);
+        description.push('\n');
+    }
+    if description.is_empty() {
+        description = String::from("Nothing has changed.");
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title(format!("Snapshot #{} -> {}", id, new_name))
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn backup_diff_help() -> String {
+    String::from(
+        "Show what changed between two snapshots, or between a snapshot and the server as it is now.
+Lines starting with `+` were added, `-` were removed and `~` were changed.
+Example: %backup diff 3
+        ",
+    )
+}
+
+/// Restore the server from a snapshot
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "backup_restore_help",
+    category = "backup",
+    rename = "restore"
+)]
+pub async fn backup_restore(
+    ctx: crate::Context<'_>,
+    #[description = "Snapshot to restore"] id: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    ctx.defer().await?;
+
+    let snapshot = load_snapshot(&ctx, id).await?;
+    let report = backup::restore(ctx.serenity_context(), ctx.data(), guild_id, &snapshot).await?;
+
+    let mut embed = CreateEmbed::default()
+        .color(if report.failures.is_empty() {
+            colors::GREEN
+        } else {
+            colors::RED
+        })
+        .description(format!("Restored snapshot #{}.", id))
+        .field(
+            "Roles",
+            format!(
+                "{} created, {} updated",
+                report.roles_created, report.roles_updated
+            ),
+            true,
+        )
+        .field(
+            "Channels",
+            format!(
+                "{} created, {} updated",
+                report.channels_created, report.channels_updated
+            ),
+            true,
+        );
+
+    if !report.failures.is_empty() {
+        let mut failures = String::new();
+        for (index, failure) in report.failures.iter().enumerate() {
+            if failures.len() + failure.len() > 1000 {
+                failures.push_str(&format!("...and {} more", report.failures.len() - index));
+                break;
+            }
+            failures.push_str(failure);
+            failures.push('\n');
+        }
+        embed = embed.field("Failed:", failures, false);
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn backup_restore_help() -> String {
+    String::from(
+        "Restore the server from a snapshot. Deleted roles and channels are created again, and edited ones are put \
+back how they were. Nothing made after the snapshot is deleted.
+Jolt's role has to be above every role it should restore.
+Example: %backup restore 3
+        ",
+    )
+}
+
+/// Delete a snapshot
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "backup_delete_help",
+    category = "backup",
+    rename = "delete"
+)]
+pub async fn backup_delete(
+    ctx: crate::Context<'_>,
+    #[description = "Snapshot to delete"] id: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_snapshot(&ctx.data().database, guild_id, id).await? {
+        return Err(BackupError::SnapshotNotFound(id).into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Deleted snapshot #{}.", id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn backup_delete_help() -> String {
+    String::from(
+        "Delete a saved snapshot.
+Example: %backup delete 3
+        ",
+    )
+}
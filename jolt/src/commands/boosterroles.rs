@@ -0,0 +1,425 @@
+// booster role commands
+
+use poise::serenity_prelude::{self, CreateAttachment, CreateEmbed, EditRole};
+use poise::CreateReply;
+
+use crate::boosterroles;
+use crate::colors;
+use crate::commands::moderation::utilities::check_role_hierarchy;
+use crate::database;
+use crate::error::{BoosterRoleError, Error};
+
+/// Make and manage your own role as a server booster
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands(
+        "boosterrole_create",
+        "boosterrole_edit",
+        "boosterrole_icon",
+        "boosterrole_delete",
+        "boosterrole_anchor",
+        "boosterrole_remove",
+        "boosterrole_list"
+    ),
+    help_text_fn = "boosterrole_help",
+    category = "roles"
+)]
+pub async fn boosterrole(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn boosterrole_help() -> String {
+    String::from(
+        "Server boosters can make one role of their own, which is deleted once they stop boosting.
+Boosters can use the subcommands `create`, `edit`, `icon` and `delete`.
+Admins can use the subcommands `anchor`, `remove` and `list`.
+        ",
+    )
+}
+
+/// Makes sure the author is boosting, returning their member.
+async fn booster(ctx: crate::Context<'_>) -> Result<serenity_prelude::Member, crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let member = guild_id.member(&ctx, ctx.author().id).await?;
+    if member.premium_since.is_none() {
+        return Err(BoosterRoleError::NotBoosting.into());
+    }
+
+    Ok(member)
+}
+
+/// Make your own role
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_create_help",
+    category = "roles",
+    rename = "create"
+)]
+pub async fn boosterrole_create(
+    ctx: crate::Context<'_>,
+    #[description = "Name of the role"] name: String,
+    #[description = "Color of the role, like #5865F2"] color: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let member = booster(ctx).await?;
+
+    if database::get_booster_role(&ctx.data().database, guild_id, member.user.id)
+        .await?
+        .is_some()
+    {
+        return Err(BoosterRoleError::AlreadyHasRole.into());
+    }
+
+    let anchor = match database::get_booster_role_anchor(&ctx.data().database, guild_id).await? {
+        Some(anchor) => guild_id.roles(&ctx).await?.remove(&anchor),
+        None => None,
+    }
+    .ok_or_else(|| Error::ConfigNotSetError(String::from("%boosterrole anchor")))?;
+
+    boosterroles::check_name(ctx.data(), guild_id, &member.roles, &name).await?;
+
+    let mut builder = EditRole::default()
+        .name(&name)
+        // New roles start at the bottom, so moving one up to just below the anchor shifts everything in between down
+        .position(anchor.position.saturating_sub(1).max(1))
+        .audit_log_reason("Booster role");
+    if let Some(color) = &color {
+        builder = builder.colour(boosterroles::parse_color(color)?);
+    }
+
+    let role = guild_id.create_role(&ctx, builder).await?;
+    database::set_booster_role(&ctx.data().database, guild_id, member.user.id, role.id).await?;
+    member.add_role(ctx.http(), role.id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Your booster role <@&{}> has been made.", role.id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_create_help() -> String {
+    String::from(
+        "Make your own role, if you're boosting the server. The name has to get past the server's word filter.
+Example: %boosterrole create Cool Kid #5865F2
+        ",
+    )
+}
+
+/// Change the name or color of your role
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_edit_help",
+    category = "roles",
+    rename = "edit"
+)]
+pub async fn boosterrole_edit(
+    ctx: crate::Context<'_>,
+    #[description = "New name of the role"] name: Option<String>,
+    #[description = "New color of the role, like #5865F2"] color: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let member = booster(ctx).await?;
+
+    let role_id = database::get_booster_role(&ctx.data().database, guild_id, member.user.id)
+        .await?
+        .ok_or(BoosterRoleError::NoRole)?;
+
+    let mut builder = EditRole::default().audit_log_reason("Booster role edited");
+    if let Some(name) = &name {
+        boosterroles::check_name(ctx.data(), guild_id, &member.roles, name).await?;
+        builder = builder.name(name);
+    }
+    if let Some(color) = &color {
+        builder = builder.colour(boosterroles::parse_color(color)?);
+    }
+
+    guild_id.edit_role(&ctx, role_id, builder).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!("Your booster role <@&{}> has been updated.", role_id),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_edit_help() -> String {
+    String::from(
+        "Change the name or color of your booster role. Anything left out stays the same.
+Example: %boosterrole edit \"Cooler Kid\" #FF73FA
+        ",
+    )
+}
+
+/// Change the icon of your role
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_icon_help",
+    category = "roles",
+    rename = "icon"
+)]
+pub async fn boosterrole_icon(
+    ctx: crate::Context<'_>,
+    #[description = "Emoji to use as the icon"] emoji: Option<String>,
+    #[description = "Image to use as the icon"] image: Option<serenity_prelude::Attachment>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let member = booster(ctx).await?;
+
+    let role_id = database::get_booster_role(&ctx.data().database, guild_id, member.user.id)
+        .await?
+        .ok_or(BoosterRoleError::NoRole)?;
+
+    let has_role_icons = ctx.guild().map_or(false, |guild| {
+        guild
+            .features
+            .iter()
+            .any(|feature| feature == boosterroles::ROLE_ICONS_FEATURE)
+    });
+    if !has_role_icons {
+        return Err(BoosterRoleError::IconsUnavailable.into());
+    }
+
+    // Leaving both out clears the icon
+    let icon = match &image {
+        Some(image) => Some(CreateAttachment::url(ctx.http(), &image.url).await?),
+        None => None,
+    };
+    let builder = EditRole::default()
+        .unicode_emoji(emoji)
+        .icon(icon.as_ref())
+        .audit_log_reason("Booster role icon changed");
+
+    guild_id.edit_role(&ctx, role_id, builder).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("The icon of <@&{}> has been updated.", role_id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_icon_help() -> String {
+    String::from(
+        "Change the icon of your booster role to an emoji or an image, or clear it by giving neither.
+Role icons are only available in servers with enough boosts.
+Example: %boosterrole icon ⚡
+        ",
+    )
+}
+
+/// Delete your role
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_delete_help",
+    category = "roles",
+    rename = "delete"
+)]
+pub async fn boosterrole_delete(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !boosterroles::delete(
+        &ctx,
+        &ctx.data().database,
+        guild_id,
+        ctx.author().id,
+        "Booster role deleted by its owner",
+    )
+    .await?
+    {
+        return Err(BoosterRoleError::NoRole.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Your booster role has been deleted."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_delete_help() -> String {
+    String::from(
+        "Delete your booster role. You can make a new one afterwards as long as you're still boosting.
+Example: %boosterrole delete
+        ",
+    )
+}
+
+/// Set the role booster roles are put under
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_anchor_help",
+    category = "roles",
+    rename = "anchor"
+)]
+pub async fn boosterrole_anchor(
+    ctx: crate::Context<'_>,
+    #[description = "Role to put booster roles under (leave empty to turn booster roles off)"]
+    role: Option<serenity_prelude::Role>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    // Booster roles end up right below the anchor, so it has to be somewhere the moderator could put roles too
+    if let Some(role) = &role {
+        check_role_hierarchy(&ctx, role).await?;
+    }
+
+    database::set_booster_role_anchor(
+        &ctx.data().database,
+        guild_id,
+        role.as_ref().map(|role| role.id),
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            match &role {
+                Some(role) => format!("Booster roles are now made right below <@&{}>.", role.id),
+                None => String::from(
+                    "Booster roles are now turned off. Existing ones are kept until their owners stop boosting.",
+                ),
+            },
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_anchor_help() -> String {
+    String::from(
+        "Set the role booster roles are made right below, which also turns booster roles on. Leave it out to turn them off.
+The anchor has to be below both your highest role and Jolt's.
+Example: %boosterrole anchor @Boosters
+        ",
+    )
+}
+
+/// Delete a member's booster role
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_remove_help",
+    category = "roles",
+    rename = "remove"
+)]
+pub async fn boosterrole_remove(
+    ctx: crate::Context<'_>,
+    #[description = "Member whose booster role to delete"] user: serenity_prelude::User,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !boosterroles::delete(
+        &ctx,
+        &ctx.data().database,
+        guild_id,
+        user.id,
+        &format!("Booster role deleted by {}", ctx.author().name),
+    )
+    .await?
+    {
+        return Err(BoosterRoleError::NoRole.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!("The booster role of <@{}> has been deleted.", user.id),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_remove_help() -> String {
+    String::from(
+        "Delete a member's booster role, like one with an inappropriate name or icon. They can make a new one afterwards.
+Example: %boosterrole remove @Joshument#0001
+        ",
+    )
+}
+
+/// List the booster roles of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "boosterrole_list_help",
+    category = "roles",
+    rename = "list"
+)]
+pub async fn boosterrole_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let anchor = database::get_booster_role_anchor(&ctx.data().database, guild_id).await?;
+    let booster_roles = database::get_booster_roles(&ctx.data().database, guild_id).await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Booster Roles")
+            .field(
+                "Anchor",
+                match anchor {
+                    Some(anchor) => format!("<@&{}>", anchor),
+                    None => String::from("None (booster roles are off)"),
+                },
+                false,
+            );
+
+        e = e.description(if booster_roles.is_empty() {
+            String::from("There are no booster roles.")
+        } else {
+            // Embed descriptions are capped at 4096 characters
+            booster_roles
+                .iter()
+                .take(60)
+                .map(|(user_id, role_id)| format!("<@{}>: <@&{}>", user_id, role_id))
+                .collect::<Vec<String>>()
+                .join("\n")
+        });
+
+        e
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn boosterrole_list_help() -> String {
+    String::from(
+        "List the booster roles of the server, along with who owns them.
+Example: %boosterrole list
+        ",
+    )
+}
@@ -8,15 +8,19 @@ use poise::futures_util::StreamExt;
 use poise::serenity_prelude::{
     self, collect, ActionRow, ActionRowComponent, ChannelType, ComponentInteractionDataKind,
     ComponentType, CreateActionRow, CreateButton, CreateEmbed, CreateInputText,
-    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal,
-    CreateSelectMenu, CreateSelectMenuKind, EditMessage, Event, InputTextStyle, Interaction,
-    InteractionCreateEvent,
+    CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
+    CreateMessage, CreateModal, CreateSelectMenu, CreateSelectMenuKind, EditMessage, Event,
+    InputTextStyle, Interaction, InteractionCreateEvent, PermissionOverwrite,
+    PermissionOverwriteType, Permissions,
 };
 use poise::{CreateReply, MessageDispatchTrigger};
 use tokio::select;
 
+use crate::autoroles;
 use crate::colors;
+use crate::commands::autoroles::autoroles_embed;
 use crate::commands::configuration::error::ConfigurationError;
+use crate::commands::moderation::utilities::check_roles_hierarchy;
 use crate::database;
 
 #[poise::command(
@@ -109,6 +113,7 @@ pub async fn configure(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
             CreateButton::new("prefix_button").label("Prefix"),
             CreateButton::new("mute_role_button").label("Mute Role"),
             CreateButton::new("logs_channel_button").label("Logs Channel"),
+            CreateButton::new("autoroles_button").label("Autoroles"),
             CreateButton::new("exit_button").label("Exit"),
         ])])
         .ephemeral(true);
@@ -192,6 +197,10 @@ pub async fn configure(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
         match event {
             Some(event) => match &event.interaction {
                 Interaction::Component(interaction) => {
+                    // Buttons on other messages (like raid alerts) come through this stream as well
+                    if interaction.message.id != message.id {
+                        continue;
+                    }
                     if interaction.user != *user {
                         continue;
                         /*
@@ -259,6 +268,35 @@ pub async fn configure(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
                                         )
                                         .await?
                                 }
+                                "autoroles_button" => {
+                                    interaction
+                                        .create_response(
+                                            &ctx,
+                                            CreateInteractionResponse::UpdateMessage(
+                                                autoroles_input(&database, guild_id).await?,
+                                            ),
+                                        )
+                                        .await?
+                                }
+                                "autoroles_screening_button" => {
+                                    let settings =
+                                        database::get_autorole_settings(&database, guild_id)
+                                            .await?;
+                                    database::set_autorole_after_screening(
+                                        &database,
+                                        guild_id,
+                                        !settings.after_screening,
+                                    )
+                                    .await?;
+                                    interaction
+                                        .create_response(
+                                            &ctx,
+                                            CreateInteractionResponse::UpdateMessage(
+                                                autoroles_input(&database, guild_id).await?,
+                                            ),
+                                        )
+                                        .await?
+                                }
                                 _ => panic!("button not expected or unimplemented!"),
                             }
                         }
@@ -276,6 +314,40 @@ pub async fn configure(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
                                         )
                                         .await?
                                 }
+                                "autoroles_humans_select_input" | "autoroles_bots_select_input" => {
+                                    let bots =
+                                        interaction.data.custom_id == "autoroles_bots_select_input";
+                                    // Same as `autorole add`, nobody can hand out a role above their own
+                                    let allowed = check_roles_hierarchy(&ctx, values).await;
+                                    if allowed.is_ok() {
+                                        database::set_autoroles(&database, guild_id, bots, values)
+                                            .await?;
+                                    }
+                                    // Staying on the page lets both lists be set in one go
+                                    interaction
+                                        .create_response(
+                                            &ctx.http(),
+                                            CreateInteractionResponse::UpdateMessage(
+                                                autoroles_input(&database, guild_id).await?,
+                                            ),
+                                        )
+                                        .await?;
+                                    if let Err(error) = allowed {
+                                        interaction
+                                            .create_followup(
+                                                &ctx.http(),
+                                                CreateInteractionResponseFollowup::default()
+                                                    .embed(
+                                                        CreateEmbed::default()
+                                                            .color(colors::RED)
+                                                            .title("Error!")
+                                                            .description(error.to_string()),
+                                                    )
+                                                    .ephemeral(true),
+                                            )
+                                            .await?;
+                                    }
+                                }
                                 _ => (),
                             }
                         }
@@ -328,6 +400,63 @@ pub async fn configure(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
     }
 }
 
+/// The autoroles page of the `configure` panel.
+/// Built when it's opened rather than up front, since it changes while it's open.
+async fn autoroles_input(
+    database: &sqlx::SqlitePool,
+    guild_id: serenity_prelude::GuildId,
+) -> Result<CreateInteractionResponseMessage, crate::DynError> {
+    let settings = database::get_autorole_settings(database, guild_id).await?;
+    let role_select = |custom_id: &str, placeholder: &str, roles: Vec<serenity_prelude::RoleId>| {
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                custom_id,
+                CreateSelectMenuKind::Role {
+                    default_roles: Some(roles),
+                },
+            )
+            .placeholder(placeholder)
+            .min_values(0)
+            .max_values(autoroles::MAX_AUTOROLES as u8),
+        )
+    };
+
+    Ok(CreateInteractionResponseMessage::default()
+        .embed(
+            autoroles_embed(database, guild_id)
+                .await?
+                .color(colors::GREEN)
+                .description(
+                    "Select the roles given to humans and bots when they join. \
+            The delay can be changed with `autorole delay`.",
+                ),
+        )
+        .components(vec![
+            role_select(
+                "autoroles_humans_select_input",
+                "Roles for humans",
+                database::get_autoroles(database, guild_id, false).await?,
+            ),
+            role_select(
+                "autoroles_bots_select_input",
+                "Roles for bots",
+                database::get_autoroles(database, guild_id, true).await?,
+            ),
+            CreateActionRow::Buttons(vec![
+                CreateButton::new("autoroles_screening_button").label(
+                    if settings.after_screening {
+                        "Don't Wait for Screening"
+                    } else {
+                        "Wait for Screening"
+                    },
+                ),
+                CreateButton::new("back_button").label("Back"),
+                CreateButton::new("exit_button").label("Exit"),
+            ]),
+        ])
+        .ephemeral(true))
+}
+
 /// Set or change the mute role of the server
 #[poise::command(
     prefix_command,
@@ -372,6 +501,144 @@ Example: %muterole @Muted
     )
 }
 
+/// Set or change the quarantine role of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "quarantine_role_help",
+    category = "configuration",
+    rename = "quarantinerole"
+)]
+pub async fn quarantine_role(
+    ctx: crate::Context<'_>,
+    #[description = "Quarantine role"]
+    #[rename = "role"]
+    role_id: serenity_prelude::RoleId,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Couldn't get guild id!");
+    let database = ctx.data().database.clone();
+    check_roles_hierarchy(&ctx, &[role_id]).await?;
+
+    database::set_quarantine_role(&database, guild_id, role_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Role <@&{}> has been assigned as the quarantine role.",
+                    role_id
+                ))
+                .field(
+                    "NOTE",
+                    "This action does *not* change the permissions of the role, make sure it can't see or talk in any channels.",
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn quarantine_role_help() -> String {
+    String::from(
+        "Set the role given to members that get quarantined, like accounts that join during a raid
+The role has to be below both your highest role and Jolt's.
+**NOTE**: This does *not* change the permissions of the role, you will have to set them up yourself.
+Example: %quarantinerole @Quarantined
+    ",
+    )
+}
+
+/// Set or change the jail role and channel of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    required_bot_permissions = "MANAGE_ROLES | MANAGE_CHANNELS",
+    help_text_fn = "jail_role_help",
+    category = "configuration",
+    rename = "jailrole"
+)]
+pub async fn jail_role(
+    ctx: crate::Context<'_>,
+    #[description = "Jail role"]
+    #[rename = "role"]
+    role_id: serenity_prelude::RoleId,
+    #[description = "The only channel jailed members can see"]
+    #[rename = "channel"]
+    channel_id: serenity_prelude::ChannelId,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Couldn't get guild id!");
+    let database = ctx.data().database.clone();
+    check_roles_hierarchy(&ctx, &[role_id]).await?;
+    ctx.defer().await?;
+
+    database::set_jail(&database, guild_id, role_id, channel_id).await?;
+
+    // Hide every other channel from the jail role, so that it ends up restricted to the jail channel
+    let mut failed: Vec<serenity_prelude::ChannelId> = Vec::new();
+    for other_channel_id in guild_id.channels(&ctx.http()).await?.into_keys() {
+        let overwrite = if other_channel_id == channel_id {
+            PermissionOverwrite {
+                allow: Permissions::VIEW_CHANNEL
+                    | Permissions::SEND_MESSAGES
+                    | Permissions::READ_MESSAGE_HISTORY,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Role(role_id),
+            }
+        } else {
+            PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::VIEW_CHANNEL,
+                kind: PermissionOverwriteType::Role(role_id),
+            }
+        };
+
+        if other_channel_id
+            .create_permission(&ctx.http(), overwrite)
+            .await
+            .is_err()
+        {
+            failed.push(other_channel_id);
+        }
+    }
+
+    let mut embed = CreateEmbed::default()
+        .color(colors::GREEN)
+        .description(format!(
+            "Role <@&{}> has been assigned as the jail role, and can now only see <#{}>.",
+            role_id, channel_id
+        ));
+    if !failed.is_empty() {
+        embed = embed.field(
+            "Could not update:",
+            failed
+                .iter()
+                .map(|channel_id| format!("<#{}>", channel_id))
+                .collect::<Vec<String>>()
+                .join(", "),
+            false,
+        );
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn jail_role_help() -> String {
+    String::from(
+        "Set the role given to jailed members, and the only channel they can see.
+This hides every other channel from the role, so run it again after making new channels.
+The role has to be below both your highest role and Jolt's.
+Example: %jailrole @Jailed #jail
+    ",
+    )
+}
+
 /// Set or change the logging channel of the server
 #[poise::command(
     prefix_command,
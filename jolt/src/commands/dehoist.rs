@@ -0,0 +1,175 @@
+// dehoisting commands
+
+use poise::serenity_prelude::{CreateEmbed, UserId};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::database;
+use crate::dehoist;
+
+// The most members Discord gives back at once
+const MEMBER_PAGE_SIZE: u64 = 1000;
+
+/// Keep names from being hoisted to the top of the member list
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_NICKNAMES",
+    required_bot_permissions = "MANAGE_NICKNAMES",
+    subcommands("dehoist_enable", "dehoist_disable", "dehoist_all"),
+    help_text_fn = "dehoist_help",
+    category = "dehoist"
+)]
+pub async fn dehoist(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn dehoist_help() -> String {
+    String::from(
+        "Keep names from being hoisted to the top of the member list with characters like `!` or `.`.
+Use one of the subcommands `enable`, `disable` or `all`.
+        ",
+    )
+}
+
+/// Automatically dehoist members when they join or change their name
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_NICKNAMES",
+    required_bot_permissions = "MANAGE_NICKNAMES",
+    help_text_fn = "dehoist_enable_help",
+    category = "dehoist",
+    rename = "enable"
+)]
+pub async fn dehoist_enable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_dehoist(&ctx.data().database, guild_id, true).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(
+                    "Automatic dehoisting has been enabled. Use `dehoist all` to dehoist members that are already \
+                    in the server.",
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn dehoist_enable_help() -> String {
+    String::from(
+        "Automatically dehoist members when they join or change their name. Hoisting characters are stripped from \
+the start of their name, and members whose whole name is hoisting characters are renamed to \"Dehoisted\".
+Example: %dehoist enable
+        ",
+    )
+}
+
+/// Stop automatically dehoisting members
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_NICKNAMES",
+    help_text_fn = "dehoist_disable_help",
+    category = "dehoist",
+    rename = "disable"
+)]
+pub async fn dehoist_disable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_dehoist(&ctx.data().database, guild_id, false).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Automatic dehoisting has been disabled."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn dehoist_disable_help() -> String {
+    String::from(
+        "Stop automatically dehoisting members. Names that were already changed stay changed.
+Example: %dehoist disable
+        ",
+    )
+}
+
+/// Dehoist every member of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_NICKNAMES",
+    required_bot_permissions = "MANAGE_NICKNAMES",
+    help_text_fn = "dehoist_all_help",
+    category = "dehoist",
+    rename = "all"
+)]
+pub async fn dehoist_all(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    ctx.defer().await?;
+
+    let mut dehoisted = 0;
+    let mut failed = 0;
+    let mut after: Option<UserId> = None;
+    loop {
+        let members = guild_id
+            .members(&ctx.http(), Some(MEMBER_PAGE_SIZE), after)
+            .await?;
+        after = members.last().map(|member| member.user.id);
+
+        for member in &members {
+            if member.user.bot {
+                continue;
+            }
+
+            match dehoist::dehoist_member(&ctx, guild_id, member.user.id, member.display_name())
+                .await
+            {
+                Ok(Some(_)) => dehoisted += 1,
+                Ok(None) => (),
+                Err(_) => failed += 1,
+            }
+        }
+
+        if (members.len() as u64) < MEMBER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    let mut embed = CreateEmbed::default()
+        .color(colors::GREEN)
+        .description(format!("Dehoisted {} members.", dehoisted));
+    if failed > 0 {
+        embed = embed.field(
+            "NOTE",
+            format!(
+                "{} members could not be renamed, most likely because their highest role is above Jolt's.",
+                failed
+            ),
+            false,
+        );
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn dehoist_all_help() -> String {
+    String::from(
+        "Dehoist every member of the server. This can take a while in large servers.
+Example: %dehoist all
+        ",
+    )
+}
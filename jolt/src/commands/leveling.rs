@@ -0,0 +1,1308 @@
+// leveling commands
+
+use poise::serenity_prelude::{self, CreateAttachment, CreateEmbed, CreateEmbedFooter};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::commands::moderation::utilities::check_role_hierarchy;
+use crate::database;
+use crate::error::LevelingError;
+use crate::leveling;
+use crate::leveling::card::{self, RankCard};
+use crate::leveling::curve::{Curve, MAX_LEVEL};
+use crate::leveling::rewards;
+use crate::leveling::transfer::{self, MAX_XP};
+use crate::leveling::types::{
+    ExportFormat, LevelCurve, RankCardTheme, RewardMode, XpMultiplierKind,
+};
+
+const RANK_CARD_FILE: &str = "rank.png";
+
+/// Show a member's rank card
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "rank_help",
+    category = "leveling"
+)]
+pub async fn rank(
+    ctx: crate::Context<'_>,
+    #[description = "Member to show the rank of (defaults to you)"] user: Option<
+        serenity_prelude::User,
+    >,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let user = user.as_ref().unwrap_or_else(|| ctx.author());
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    if !settings.enabled {
+        return Err(LevelingError::Disabled.into());
+    }
+    let curve = leveling::get_curve(ctx.data(), guild_id, &settings).await?;
+
+    let xp = database::get_xp(&ctx.data().database, guild_id, user.id).await?;
+    let rank = database::get_xp_rank(&ctx.data().database, guild_id, user.id).await?;
+    let level = curve.level_for_xp(xp);
+
+    let card = RankCard {
+        username: &user.name,
+        level,
+        rank,
+        progress: (level < MAX_LEVEL).then(|| {
+            let current = curve.xp_for_level(level);
+            (xp - current, curve.xp_for_level(level + 1) - current)
+        }),
+    };
+
+    ctx.send(CreateReply::default().attachment(CreateAttachment::bytes(
+        card::render(&card, &settings.card_theme),
+        RANK_CARD_FILE,
+    )))
+    .await?;
+
+    Ok(())
+}
+
+fn rank_help() -> String {
+    String::from(
+        "Show a member's rank card, with their level, place on the leaderboard and how far they are from the next level.
+The card's colors can be changed with `xp theme`.
+Example: %rank @Joshument#0001
+        ",
+    )
+}
+
+/// Show the members with the most XP
+#[poise::command(
+    prefix_command,
+    slash_command,
+    help_text_fn = "leaderboard_help",
+    category = "leveling"
+)]
+pub async fn leaderboard(
+    ctx: crate::Context<'_>,
+    #[description = "Leaderboard page"]
+    #[min = 1]
+    page: Option<usize>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let page = page.unwrap_or(1);
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    if !settings.enabled {
+        return Err(LevelingError::Disabled.into());
+    }
+    let curve = leveling::get_curve(ctx.data(), guild_id, &settings).await?;
+
+    let count = database::get_leaderboard_count(&ctx.data().database, guild_id).await?;
+    let max_page = ((count + 9) / 10).max(1);
+    let leaderboard_page =
+        database::get_leaderboard_page(&ctx.data().database, guild_id, page, 10).await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Leaderboard");
+
+        if leaderboard_page.is_empty() {
+            e = e.description("Nobody has gained any XP yet.");
+        } else {
+            e = e.description(
+                leaderboard_page
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (user_id, xp))| {
+                        format!(
+                            "**#{}** <@{}>: level {} ({} XP)",
+                            (page - 1) * 10 + i + 1,
+                            user_id,
+                            curve.level_for_xp(*xp),
+                            xp
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            );
+        }
+
+        e.footer(CreateEmbedFooter::new(format!(
+            "Page {} of {}",
+            page, max_page
+        )))
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn leaderboard_help() -> String {
+    String::from(
+        "Show the members with the most XP, 10 per page.
+Example: %leaderboard 2
+        ",
+    )
+}
+
+/// Configure XP and leveling
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "xp_enable",
+        "xp_gain",
+        "xp_cooldown",
+        "xp_curve",
+        "xp_rolemultiplier",
+        "xp_channelmultiplier",
+        "xp_levelup",
+        "xp_theme",
+        "xp_settings",
+        "xp_give",
+        "xp_take",
+        "xp_set",
+        "xp_setlevel",
+        "xp_reset",
+        "xp_export",
+        "xp_import"
+    ),
+    help_text_fn = "xp_help",
+    category = "leveling"
+)]
+pub async fn xp(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn xp_help() -> String {
+    String::from(
+        "Configure how members gain XP and level up by chatting.
+Use one of the subcommands `enable`, `gain`, `cooldown`, `curve`, `rolemultiplier`, `channelmultiplier`, `levelup`, `theme` or `settings`.
+XP can be changed with `give`, `take`, `set`, `setlevel` and `reset`, and moved between servers or bots with `export` and `import`.
+        ",
+    )
+}
+
+/// Turn leveling on or off
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_enable_help",
+    category = "leveling",
+    rename = "enable"
+)]
+pub async fn xp_enable(
+    ctx: crate::Context<'_>,
+    #[description = "Whether members gain XP"] enabled: bool,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_leveling_enabled(&ctx.data().database, guild_id, enabled).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(if enabled {
+                    "Members now gain XP by chatting."
+                } else {
+                    "Members no longer gain XP. Their XP is kept in case leveling is turned back on."
+                }),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_enable_help() -> String {
+    String::from(
+        "Turn leveling on or off. Turning it off keeps everyone's XP.
+Example: %xp enable true
+        ",
+    )
+}
+
+/// Set how much XP each message gives
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_gain_help",
+    category = "leveling",
+    rename = "gain"
+)]
+pub async fn xp_gain(
+    ctx: crate::Context<'_>,
+    #[description = "Least XP a message can give"] min: u32,
+    #[description = "Most XP a message can give"] max: u32,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if min > max || max > leveling::MAX_GAIN {
+        return Err(LevelingError::InvalidGain(leveling::MAX_GAIN).into());
+    }
+
+    database::set_xp_gain(&ctx.data().database, guild_id, min, max).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Messages now give {} to {} XP.", min, max)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_gain_help() -> String {
+    String::from(
+        "Set how much XP each message gives, picked at random between the minimum and maximum before multipliers.
+Example: %xp gain 15 25
+        ",
+    )
+}
+
+/// Set how long members wait between messages that give XP
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_cooldown_help",
+    category = "leveling",
+    rename = "cooldown"
+)]
+pub async fn xp_cooldown(
+    ctx: crate::Context<'_>,
+    #[description = "Time between messages that give XP"] length: humantime::Duration,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if *length > leveling::MAX_COOLDOWN {
+        return Err(LevelingError::CooldownTooLong.into());
+    }
+
+    database::set_xp_cooldown(&ctx.data().database, guild_id, *length).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!("Members now gain XP at most once every {}.", length),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_cooldown_help() -> String {
+    String::from(
+        "Set how long members have to wait after gaining XP before their messages give XP again, up to a day.
+Example: %xp cooldown 1m
+        ",
+    )
+}
+
+/// Set how much XP each level takes
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD | MANAGE_ROLES",
+    help_text_fn = "xp_curve_help",
+    category = "leveling",
+    rename = "curve"
+)]
+pub async fn xp_curve(
+    ctx: crate::Context<'_>,
+    #[description = "Level curve to use"] curve: LevelCurve,
+    #[description = "Total XP for a level, for custom curves (e.g. 100 * level ^ 1.5)"]
+    #[rest]
+    expression: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    // Checked before saving so a broken curve can never make it into the database
+    let parsed = Curve::new(curve, expression.as_deref())?;
+
+    database::set_level_curve(&ctx.data().database, guild_id, curve, expression.as_deref()).await?;
+    leveling::invalidate_curve(ctx.data(), guild_id).await;
+
+    // Members may have moved to another level, so their rewards need to follow
+    ctx.defer().await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("The level curve is now {}.", curve))
+                .field("Level 1", format!("{} XP", parsed.xp_for_level(1)), true)
+                .field("Level 10", format!("{} XP", parsed.xp_for_level(10)), true)
+                .field("Level 50", format!("{} XP", parsed.xp_for_level(50)), true),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_curve_help() -> String {
+    String::from(
+        "Set how much XP each level takes. Custom curves give the total XP needed to reach `level`, and can use numbers, `+ - * / ^`, `e`, `pi` and the functions `sqrt`, `ln`, `log`, `exp`, `abs`, `floor` and `ceil`.
+Every level has to take more XP than the one before it. Members are moved to their new level straight away, along with their level rewards, so this also needs the Manage Roles permission.
+Example: %xp curve Custom 100 * level ^ 1.5
+        ",
+    )
+}
+
+/// Change how much XP members with a role gain
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_rolemultiplier_help",
+    category = "leveling",
+    rename = "rolemultiplier"
+)]
+pub async fn xp_rolemultiplier(
+    ctx: crate::Context<'_>,
+    #[description = "Role to change the multiplier of"] role: serenity_prelude::Role,
+    #[description = "Multiplier (0 stops the role gaining XP, leave empty to remove)"]
+    multiplier: Option<f64>,
+) -> Result<(), crate::DynError> {
+    set_multiplier(
+        ctx,
+        role.id.get(),
+        XpMultiplierKind::Role,
+        format!("<@&{}>", role.id),
+        multiplier,
+    )
+    .await
+}
+
+fn xp_rolemultiplier_help() -> String {
+    String::from(
+        "Change how much XP members with a role gain. Members with several of these roles use the highest one.
+A multiplier of 0 means members with the role gain no XP at all. Leave the multiplier out to remove it.
+Example: %xp rolemultiplier @Booster 1.5
+        ",
+    )
+}
+
+/// Change how much XP messages in a channel give
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_channelmultiplier_help",
+    category = "leveling",
+    rename = "channelmultiplier"
+)]
+pub async fn xp_channelmultiplier(
+    ctx: crate::Context<'_>,
+    #[description = "Channel to change the multiplier of"] channel: serenity_prelude::GuildChannel,
+    #[description = "Multiplier (0 stops the channel giving XP, leave empty to remove)"]
+    multiplier: Option<f64>,
+) -> Result<(), crate::DynError> {
+    set_multiplier(
+        ctx,
+        channel.id.get(),
+        XpMultiplierKind::Channel,
+        format!("<#{}>", channel.id),
+        multiplier,
+    )
+    .await
+}
+
+fn xp_channelmultiplier_help() -> String {
+    String::from(
+        "Change how much XP messages in a channel give. This stacks with role multipliers.
+A multiplier of 0 means messages in the channel give no XP at all. Leave the multiplier out to remove it.
+Example: %xp channelmultiplier #spam 0
+        ",
+    )
+}
+
+async fn set_multiplier(
+    ctx: crate::Context<'_>,
+    target_id: u64,
+    kind: XpMultiplierKind,
+    mention: String,
+    multiplier: Option<f64>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let description = match multiplier {
+        Some(multiplier) => {
+            if !(0.0..=leveling::MAX_MULTIPLIER).contains(&multiplier) {
+                return Err(LevelingError::InvalidMultiplier(leveling::MAX_MULTIPLIER).into());
+            }
+
+            database::set_xp_multiplier(
+                &ctx.data().database,
+                guild_id,
+                target_id,
+                kind,
+                multiplier,
+            )
+            .await?;
+
+            if multiplier == 0.0 {
+                format!("{} no longer gains XP.", mention)
+            } else {
+                format!("{} now has an XP multiplier of {}x.", mention, multiplier)
+            }
+        }
+        None => {
+            database::remove_xp_multiplier(&ctx.data().database, guild_id, target_id).await?;
+            format!("{} no longer has an XP multiplier.", mention)
+        }
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Announce level ups in a channel
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_levelup_help",
+    category = "leveling",
+    rename = "levelup"
+)]
+pub async fn xp_levelup(
+    ctx: crate::Context<'_>,
+    #[description = "Channel to announce level ups in (leave empty to stop announcing)"]
+    channel: Option<serenity_prelude::GuildChannel>,
+    #[description = "Announcement, using {user}, {username} and {level}"]
+    #[rest]
+    message: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if let Some(message) = &message {
+        if message.chars().count() > rewards::MAX_LEVELUP_MESSAGE_LENGTH {
+            return Err(LevelingError::MessageTooLong(rewards::MAX_LEVELUP_MESSAGE_LENGTH).into());
+        }
+    }
+
+    database::set_levelup_announcements(
+        &ctx.data().database,
+        guild_id,
+        channel.as_ref().map(|channel| channel.id),
+        message.as_deref(),
+    )
+    .await?;
+
+    let embed = match &channel {
+        Some(channel) => CreateEmbed::default()
+            .color(colors::GREEN)
+            .description(format!("Level ups are now announced in <#{}>.", channel.id))
+            .field(
+                "Preview",
+                rewards::format_levelup_message(
+                    message
+                        .as_deref()
+                        .unwrap_or(rewards::DEFAULT_LEVELUP_MESSAGE),
+                    ctx.author(),
+                    5,
+                ),
+                false,
+            ),
+        None => CreateEmbed::default()
+            .color(colors::GREEN)
+            .description("Level ups are no longer announced."),
+    };
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn xp_levelup_help() -> String {
+    String::from(
+        "Announce level ups in a channel. The message can use `{user}` to ping the member, `{username}` for their name and `{level}` for their new level.
+Leave the message out to use the default one, or leave everything out to stop announcing level ups.
+Example: %xp levelup #levels GG {user}, you're now level {level}!
+        ",
+    )
+}
+
+/// Change the colors of rank cards
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_theme_help",
+    category = "leveling",
+    rename = "theme"
+)]
+pub async fn xp_theme(
+    ctx: crate::Context<'_>,
+    #[description = "Color of the progress bar and level, like #89B4FA"] accent: Option<String>,
+    #[description = "Color of the card itself"] background: Option<String>,
+    #[description = "Color of the text"] text: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let default = RankCardTheme::default();
+    let parse = |color: Option<String>, default: u32| match color {
+        Some(color) => colors::parse_hex(&color).ok_or(LevelingError::InvalidColor(color)),
+        None => Ok(default),
+    };
+    let theme = RankCardTheme {
+        background: parse(background, default.background)?,
+        accent: parse(accent, default.accent)?,
+        text: parse(text, default.text)?,
+    };
+
+    database::set_rank_card_theme(&ctx.data().database, guild_id, theme).await?;
+
+    // Shows off the new colors on the author's own card, as if they were halfway through level 5
+    let preview = RankCard {
+        username: &ctx.author().name,
+        level: 5,
+        rank: Some(1),
+        progress: Some((50, 100)),
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::GREEN)
+                    .description("Rank cards now use these colors."),
+            )
+            .attachment(CreateAttachment::bytes(
+                card::render(&preview, &theme),
+                RANK_CARD_FILE,
+            )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_theme_help() -> String {
+    String::from(
+        "Change the colors of rank cards. Colors are written in hex, and any that are left out go back to the default.
+Example: %xp theme #F38BA8 #1E1E2E #CDD6F4
+        ",
+    )
+}
+
+/// Show the leveling settings of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_settings_help",
+    category = "leveling",
+    rename = "settings"
+)]
+pub async fn xp_settings(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    let multipliers = database::get_xp_multipliers(&ctx.data().database, guild_id).await?;
+
+    let format_multipliers = |kind: XpMultiplierKind| {
+        let lines = multipliers
+            .iter()
+            .filter(|multiplier| multiplier.kind == kind)
+            .map(|multiplier| {
+                let mention = match kind {
+                    XpMultiplierKind::Role => format!("<@&{}>", multiplier.target_id),
+                    XpMultiplierKind::Channel => format!("<#{}>", multiplier.target_id),
+                };
+                if multiplier.multiplier == 0.0 {
+                    format!("{}: excluded", mention)
+                } else {
+                    format!("{}: {}x", mention, multiplier.multiplier)
+                }
+            })
+            .collect::<Vec<String>>();
+
+        if lines.is_empty() {
+            String::from("None")
+        } else {
+            lines.join("\n")
+        }
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title("Leveling Settings")
+                .field("Enabled", if settings.enabled { "Yes" } else { "No" }, true)
+                .field(
+                    "XP per Message",
+                    format!("{} to {}", settings.min_xp, settings.max_xp),
+                    true,
+                )
+                .field(
+                    "Cooldown",
+                    humantime::format_duration(settings.cooldown).to_string(),
+                    true,
+                )
+                .field(
+                    "Curve",
+                    match &settings.expression {
+                        Some(expression) => format!("{} (`{}`)", settings.curve, expression),
+                        None => settings.curve.to_string(),
+                    },
+                    false,
+                )
+                .field("Reward Mode", settings.reward_mode.to_string(), true)
+                .field(
+                    "Rank Card Colors",
+                    format!(
+                        "Accent #{:06X}, background #{:06X}, text #{:06X}",
+                        settings.card_theme.accent,
+                        settings.card_theme.background,
+                        settings.card_theme.text
+                    ),
+                    false,
+                )
+                .field(
+                    "Level Up Announcements",
+                    match settings.levelup_channel {
+                        Some(channel_id) => format!(
+                            "<#{}>: {}",
+                            channel_id,
+                            settings
+                                .levelup_message
+                                .as_deref()
+                                .unwrap_or(rewards::DEFAULT_LEVELUP_MESSAGE)
+                        ),
+                        None => String::from("Off"),
+                    },
+                    false,
+                )
+                .field(
+                    "Role Multipliers",
+                    format_multipliers(XpMultiplierKind::Role),
+                    true,
+                )
+                .field(
+                    "Channel Multipliers",
+                    format_multipliers(XpMultiplierKind::Channel),
+                    true,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_settings_help() -> String {
+    String::from(
+        "Show the leveling settings of the server, including every role and channel multiplier.
+Example: %xp settings
+        ",
+    )
+}
+
+/// Give a member XP
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD | MANAGE_ROLES",
+    help_text_fn = "xp_give_help",
+    category = "leveling",
+    rename = "give"
+)]
+pub async fn xp_give(
+    ctx: crate::Context<'_>,
+    #[description = "Member to give XP to"] user: serenity_prelude::User,
+    #[description = "XP to give"] amount: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let xp = database::get_xp(&ctx.data().database, guild_id, user.id).await?;
+    set_member_xp(ctx, &user, xp.saturating_add(amount)).await
+}
+
+fn xp_give_help() -> String {
+    String::from(
+        "Give a member XP. Their level rewards are updated to match, so this also needs the Manage Roles permission.
+Example: %xp give @Joshument#0001 500
+        ",
+    )
+}
+
+/// Take XP away from a member
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD | MANAGE_ROLES",
+    help_text_fn = "xp_take_help",
+    category = "leveling",
+    rename = "take"
+)]
+pub async fn xp_take(
+    ctx: crate::Context<'_>,
+    #[description = "Member to take XP from"] user: serenity_prelude::User,
+    #[description = "XP to take"] amount: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let xp = database::get_xp(&ctx.data().database, guild_id, user.id).await?;
+    set_member_xp(ctx, &user, xp.saturating_sub(amount)).await
+}
+
+fn xp_take_help() -> String {
+    String::from(
+        "Take XP away from a member, down to 0 at the least. Their level rewards are updated to match, so this also needs the Manage Roles permission.
+Example: %xp take @Joshument#0001 500
+        ",
+    )
+}
+
+/// Set how much XP a member has
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD | MANAGE_ROLES",
+    help_text_fn = "xp_set_help",
+    category = "leveling",
+    rename = "set"
+)]
+pub async fn xp_set(
+    ctx: crate::Context<'_>,
+    #[description = "Member to set the XP of"] user: serenity_prelude::User,
+    #[description = "XP the member has"] xp: u64,
+) -> Result<(), crate::DynError> {
+    set_member_xp(ctx, &user, xp).await
+}
+
+fn xp_set_help() -> String {
+    String::from(
+        "Set how much XP a member has. Their level rewards are updated to match, so this also needs the Manage Roles permission.
+Example: %xp set @Joshument#0001 0
+        ",
+    )
+}
+
+/// Set a member's level
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD | MANAGE_ROLES",
+    help_text_fn = "xp_setlevel_help",
+    category = "leveling",
+    rename = "setlevel"
+)]
+pub async fn xp_setlevel(
+    ctx: crate::Context<'_>,
+    #[description = "Member to set the level of"] user: serenity_prelude::User,
+    #[description = "Level the member is at"] level: u32,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if level > MAX_LEVEL {
+        return Err(LevelingError::InvalidLevel(0, MAX_LEVEL).into());
+    }
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    let curve = leveling::get_curve(ctx.data(), guild_id, &settings).await?;
+    set_member_xp(ctx, &user, curve.xp_for_level(level)).await
+}
+
+fn xp_setlevel_help() -> String {
+    String::from(
+        "Set a member's level, giving them exactly the XP it takes to reach it on the current curve.
+Their level rewards are updated to match, so this also needs the Manage Roles permission.
+Example: %xp setlevel @Joshument#0001 10
+        ",
+    )
+}
+
+async fn set_member_xp(
+    ctx: crate::Context<'_>,
+    user: &serenity_prelude::User,
+    xp: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if xp > MAX_XP {
+        return Err(LevelingError::TooMuchXp(MAX_XP).into());
+    }
+
+    database::set_xp(&ctx.data().database, guild_id, user.id, xp).await?;
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    let level = leveling::get_curve(ctx.data(), guild_id, &settings)
+        .await?
+        .level_for_xp(xp);
+    let failed = rewards::sync_user(&ctx, &ctx.data().database, guild_id, user.id)
+        .await
+        .is_err();
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "<@{}> now has {} XP (level {}).",
+                    user.id, xp, level
+                )),
+            (0, failed as usize),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Clear everyone's XP
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "xp_reset_help",
+    category = "leveling",
+    rename = "reset"
+)]
+pub async fn xp_reset(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let cleared = database::reset_xp(&ctx.data().database, guild_id).await?;
+
+    ctx.defer().await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Cleared the XP of {} members. The leaderboard is empty again.",
+                    cleared
+                )),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_reset_help() -> String {
+    String::from(
+        "Clear the XP of every member and take away their level rewards. This can't be undone, so consider using `xp export` first.
+Example: %xp reset
+        ",
+    )
+}
+
+/// Download everyone's XP as a file
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "xp_export_help",
+    category = "leveling",
+    rename = "export"
+)]
+pub async fn xp_export(
+    ctx: crate::Context<'_>,
+    #[description = "File type to export as (defaults to CSV)"] format: Option<ExportFormat>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let mut entries = database::get_all_xp(&ctx.data().database, guild_id)
+        .await?
+        .into_iter()
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let attachment = match format.unwrap_or(ExportFormat::Csv) {
+        ExportFormat::Csv => {
+            CreateAttachment::bytes(transfer::export_csv(&entries).into_bytes(), "xp.csv")
+        }
+        ExportFormat::Json => {
+            CreateAttachment::bytes(transfer::export_json(&entries)?.into_bytes(), "xp.json")
+        }
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::GREEN)
+                    .description(format!("Exported the XP of {} members.", entries.len())),
+            )
+            .attachment(attachment),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_export_help() -> String {
+    String::from(
+        "Download everyone's XP as a CSV or JSON file, which can be imported again with `xp import`.
+Example: %xp export JSON
+        ",
+    )
+}
+
+/// Load XP from a file
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "ADMINISTRATOR",
+    help_text_fn = "xp_import_help",
+    category = "leveling",
+    rename = "import"
+)]
+pub async fn xp_import(
+    ctx: crate::Context<'_>,
+    #[description = "CSV or JSON file to import"] file: serenity_prelude::Attachment,
+    #[description = "Clear everyone else's XP first (defaults to false)"] replace: Option<bool>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if file.size as usize > transfer::MAX_IMPORT_SIZE {
+        return Err(LevelingError::ImportTooLarge(transfer::MAX_IMPORT_SIZE / 1024 / 1024).into());
+    }
+
+    ctx.defer().await?;
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    let curve = leveling::get_curve(ctx.data(), guild_id, &settings).await?;
+    let entries = transfer::parse_import(&file.download().await?, &curve)?;
+
+    database::import_xp(
+        &ctx.data().database,
+        guild_id,
+        &entries,
+        replace.unwrap_or(false),
+    )
+    .await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Imported the XP of {} members.", entries.len())),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn xp_import_help() -> String {
+    String::from(
+        "Load XP from a CSV or JSON file. This takes files from `xp export`, as well as leaderboard dumps from other bots like MEE6.
+CSV files need a user ID column and either an XP or a level column, named like `user_id,xp`. Members with only a level get the XP it takes to reach it on the current curve.
+Members in the file have their XP overwritten. With `replace`, everyone else's XP is cleared too.
+Example: %xp import players.json true
+        ",
+    )
+}
+
+/// Give roles to members when they reach a level
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    subcommands(
+        "levelreward_add",
+        "levelreward_remove",
+        "levelreward_mode",
+        "levelreward_sync",
+        "levelreward_list"
+    ),
+    help_text_fn = "levelreward_help",
+    category = "leveling"
+)]
+pub async fn levelreward(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn levelreward_help() -> String {
+    String::from(
+        "Give roles to members when they reach a level.
+Use one of the subcommands `add`, `remove`, `mode`, `sync` or `list`.
+        ",
+    )
+}
+
+/// Give a role to members once they reach a level
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "levelreward_add_help",
+    category = "leveling",
+    rename = "add"
+)]
+pub async fn levelreward_add(
+    ctx: crate::Context<'_>,
+    #[description = "Level to give the role at"] level: u32,
+    #[description = "Role to give"] role: serenity_prelude::Role,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if level == 0 || level > MAX_LEVEL {
+        return Err(LevelingError::InvalidLevel(1, MAX_LEVEL).into());
+    }
+    check_role_hierarchy(&ctx, &role).await?;
+
+    database::set_level_reward(&ctx.data().database, guild_id, role.id, level).await?;
+
+    ctx.defer().await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Members now get <@&{}> at level {}.",
+                    role.id, level
+                )),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn levelreward_add_help() -> String {
+    String::from(
+        "Give a role to members once they reach a level. Using it on a role that is already a reward moves it to the new level.
+Members that are already past the level get the role straight away. The role has to be below both your highest role and Jolt's.
+Example: %levelreward add 10 @Regular
+        ",
+    )
+}
+
+/// Stop giving a role for reaching a level
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "levelreward_remove_help",
+    category = "leveling",
+    rename = "remove"
+)]
+pub async fn levelreward_remove(
+    ctx: crate::Context<'_>,
+    #[description = "Role to stop giving"] role: serenity_prelude::Role,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_level_reward(&ctx.data().database, guild_id, role.id).await? {
+        return Err(LevelingError::RewardNotFound.into());
+    }
+
+    // In replace mode, members may be owed the rewards of a lower level again
+    ctx.defer().await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("<@&{}> is no longer a level reward.", role.id)),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn levelreward_remove_help() -> String {
+    String::from(
+        "Stop giving a role for reaching a level. Members keep the role if they already have it.
+Example: %levelreward remove @Regular
+        ",
+    )
+}
+
+/// Choose whether members keep the rewards of lower levels
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "levelreward_mode_help",
+    category = "leveling",
+    rename = "mode"
+)]
+pub async fn levelreward_mode(
+    ctx: crate::Context<'_>,
+    #[description = "Stack keeps every reward, Replace only the highest"] mode: RewardMode,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::set_reward_mode(&ctx.data().database, guild_id, mode).await?;
+
+    ctx.defer().await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(match mode {
+                    RewardMode::Stack => "Members now keep the rewards of every level they reach.",
+                    RewardMode::Replace => {
+                        "Members now only keep the rewards of the highest level they've reached."
+                    }
+                }),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn levelreward_mode_help() -> String {
+    String::from(
+        "Choose whether members keep the rewards of every level they reach (Stack), or lose them when they get the next one (Replace).
+Example: %levelreward mode Replace
+        ",
+    )
+}
+
+/// Give and take away level rewards to match everyone's level
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "levelreward_sync_help",
+    category = "leveling",
+    rename = "sync"
+)]
+pub async fn levelreward_sync(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    ctx.defer().await?;
+    let sync_results = rewards::sync_guild(&ctx, &ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(sync_embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Updated the rewards of {} members.",
+                    sync_results.0
+                )),
+            sync_results,
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn levelreward_sync_help() -> String {
+    String::from(
+        "Give and take away level rewards so every member has exactly the ones their level earns.
+This already happens on its own when the rewards or curve change, so it's only needed if roles were changed by hand.
+Example: %levelreward sync
+        ",
+    )
+}
+
+/// List the level rewards of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "levelreward_list_help",
+    category = "leveling",
+    rename = "list"
+)]
+pub async fn levelreward_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let settings = database::get_leveling_settings(&ctx.data().database, guild_id).await?;
+    let level_rewards = database::get_level_rewards(&ctx.data().database, guild_id).await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Level Rewards");
+
+        if level_rewards.is_empty() {
+            e = e.description("There are no level rewards.");
+        } else {
+            e = e.description(
+                level_rewards
+                    .iter()
+                    .take(40)
+                    .map(|reward| format!("Level {}: <@&{}>", reward.level, reward.role_id))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            );
+        }
+
+        if level_rewards.len() > 40 {
+            e.footer(CreateEmbedFooter::new(format!(
+                "Mode: {} | And {} more",
+                settings.reward_mode,
+                level_rewards.len() - 40
+            )))
+        } else {
+            e.footer(CreateEmbedFooter::new(format!(
+                "Mode: {}",
+                settings.reward_mode
+            )))
+        }
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn levelreward_list_help() -> String {
+    String::from(
+        "List the level rewards of the server, lowest level first.
+Example: %levelreward list
+        ",
+    )
+}
+
+/// Notes how many members couldn't have their rewards synced, most likely because of the role hierarchy.
+fn sync_embed(embed: CreateEmbed, (_, failed): (usize, usize)) -> CreateEmbed {
+    if failed == 0 {
+        return embed;
+    }
+
+    embed.field(
+        "NOTE",
+        format!(
+            "{} members could not have their rewards updated, most likely because a reward is above Jolt's highest role.",
+            failed
+        ),
+        false,
+    )
+}
@@ -14,7 +14,7 @@ but when in doubt, go with KICK_MEMBERS.
 
 pub mod error;
 pub mod types;
-mod utilities;
+pub mod utilities;
 
 use crate::colors;
 use crate::commands::moderation::types::*;
@@ -143,6 +143,260 @@ Example: %delwarn 3872
     )
 }
 
+/// Jail a user (with an optional specified time)
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MODERATE_MEMBERS",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "jail_help",
+    category = "moderation"
+)]
+pub async fn jail(
+    ctx: crate::Context<'_>,
+    #[description = "User to jail"] user: serenity_prelude::User,
+    #[description = "Length of the jail"] length: Option<humantime::Duration>,
+    #[description = "Reason for jail"]
+    #[rest]
+    reason: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let moderator = ctx.author();
+
+    let mut member = guild_id.member(&ctx, user.id).await?;
+    if is_member_moderator(&ctx.cache(), &member)? {
+        return Err(ModerationError::MemberIsModerator(member).into());
+    }
+
+    let (jail_role, _) = database::get_jail(&ctx.data().database, guild_id)
+        .await?
+        .ok_or_else(|| Error::ConfigNotSetError(String::from("%jailrole")))?;
+    if member.roles.contains(&jail_role) {
+        return Err(ModerationError::MemberAlreadyJailed(user.id.get()).into());
+    }
+
+    let administered_at = ctx.created_at();
+    // Replaces Option<Duration> into Option<Timestamp>
+    // .transpose()? brings out the inner result to propagate upstream with `?`
+    let expiry_date = length
+        .map(|duration| {
+            serenity_prelude::Timestamp::from_unix_timestamp(
+                administered_at.unix_timestamp() + duration.as_secs() as i64,
+            )
+        })
+        .transpose()?;
+
+    // Roles managed by an integration (like the booster role) can't be taken away, so they're left alone
+    let guild_roles = guild_id.roles(&ctx.http()).await?;
+    let (kept_roles, stored_roles): (Vec<serenity_prelude::RoleId>, Vec<serenity_prelude::RoleId>) =
+        member
+            .roles
+            .iter()
+            .partition(|role_id| guild_roles.get(role_id).map_or(false, |role| role.managed));
+
+    let mut roles = kept_roles;
+    roles.push(jail_role);
+    member
+        .edit(&ctx, serenity_prelude::EditMember::default().roles(roles))
+        .await?;
+    // Only stored once the roles are actually gone, a failed jail shouldn't leave anything behind to give back later
+    database::set_jailed_roles(&ctx.data().database, guild_id, user.id, &stored_roles).await?;
+
+    let dm_channel = user.create_dm_channel(&ctx).await?;
+
+    send_moderation_messages(
+        &ctx,
+        &dm_channel,
+        &append_expiry_date(
+            &format!(
+                "You have been jailed in **{}**",
+                &guild_id
+                    .name(&ctx.cache())
+                    .expect("Failed to get guild name!")
+            ),
+            expiry_date,
+        ),
+        colors::RED,
+        "Zap!",
+        &append_expiry_date(&format!("User <@{}> has been jailed", user.id), expiry_date),
+        colors::GREEN,
+        &format!("I was unable to DM <@{}> about their moderation.", user.id),
+        colors::RED,
+        reason.as_deref(),
+    )
+    .await?;
+
+    database::add_moderation(
+        &ctx.data().database,
+        guild_id,
+        user.id,
+        moderator.id,
+        ModerationType::Jail,
+        administered_at,
+        expiry_date,
+        reason.as_deref(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn jail_help() -> String {
+    String::from(
+        "Jail a user (with an optional specified time).
+All of their roles are taken away and replaced with the jail role, which can only see the jail channel. \
+Their roles are given back when they are unjailed or the jail expires.
+Example: %jail @Joshument#0001 1d arguing in #general
+        ",
+    )
+}
+
+/// Unjail a user
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MODERATE_MEMBERS",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "unjail_help",
+    category = "moderation"
+)]
+pub async fn unjail(
+    ctx: crate::Context<'_>,
+    #[description = "User to unjail"] user: serenity_prelude::User,
+    #[description = "Reason for unjail"]
+    #[rest]
+    reason: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let moderator = ctx.author();
+    let administered_at = ctx.created_at();
+
+    if !release_from_jail(&ctx, &ctx.data().database, guild_id, user.id).await? {
+        return Err(ModerationError::MemberNotJailed(user.id.get()).into());
+    }
+
+    let dm_channel = user.create_dm_channel(&ctx).await?;
+
+    send_moderation_messages(
+        &ctx,
+        &dm_channel,
+        &format!(
+            "You have been unjailed in **{}**",
+            &guild_id
+                .name(&ctx.cache())
+                .expect("Failed to get guild name!")
+        ),
+        colors::RED,
+        "!paZ",
+        &format!("User <@{}> has been unjailed", user.id),
+        colors::GREEN,
+        &format!("I was unable to DM <@{}> about their moderation.", user.id),
+        colors::RED,
+        reason.as_deref(),
+    )
+    .await?;
+
+    database::clear_moderations(
+        &ctx.data().database,
+        guild_id,
+        user.id,
+        ModerationType::Jail,
+    )
+    .await?;
+
+    database::add_moderation(
+        &ctx.data().database,
+        guild_id,
+        user.id,
+        moderator.id,
+        ModerationType::Unjail,
+        administered_at,
+        None,
+        reason.as_deref(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn unjail_help() -> String {
+    String::from(
+        "Unjail a user, giving back the roles they had before they were jailed.
+Example: %unjail @Joshument#0001 apologized
+        ",
+    )
+}
+
+/// Set or reset a user's nickname
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_NICKNAMES",
+    required_bot_permissions = "MANAGE_NICKNAMES",
+    help_text_fn = "nick_help",
+    category = "moderation"
+)]
+pub async fn nick(
+    ctx: crate::Context<'_>,
+    #[description = "User to change the nickname of"] user: serenity_prelude::User,
+    #[description = "New nickname (leave empty to reset)"]
+    #[rest]
+    nickname: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let moderator = ctx.author();
+
+    let member = guild_id.member(&ctx, user.id).await?;
+    if is_member_moderator(&ctx.cache(), &member)? && user.id != moderator.id {
+        return Err(ModerationError::MemberIsModerator(member).into());
+    }
+
+    let before = member.nick.clone().unwrap_or_else(|| String::from("None"));
+    // An empty nickname resets it back to the user's own name
+    guild_id
+        .edit_member(
+            &ctx,
+            user.id,
+            serenity_prelude::EditMember::default().nickname(nickname.as_deref().unwrap_or("")),
+        )
+        .await?;
+    let after = nickname.unwrap_or_else(|| String::from("None"));
+
+    let message = format!("Nickname of <@{}> has been changed", user.id);
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(&message),
+        ),
+    )
+    .await?;
+
+    crate::messages::send_log(
+        &ctx,
+        &ctx.data().database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("NICKNAME")
+            .description(message)
+            .field("Before:", before, true)
+            .field("After:", after, true)
+            .field("Moderator:", format!("<@{}>", moderator.id), false),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn nick_help() -> String {
+    String::from(
+        "Set the nickname of a user, or reset it by leaving the nickname empty.
+Example: %nick @Joshument#0001 Josh
+        ",
+    )
+}
+
 /// Get the mod logs for a specified user
 #[poise::command(
     prefix_command,
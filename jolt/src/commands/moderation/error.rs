@@ -16,4 +16,12 @@ pub enum ModerationError {
     /// Contains one vaue, which is the member that is the moderator.
     #[error("User <@{0}> is a moderator!")]
     MemberIsModerator(serenity_prelude::Member),
+    /// An error representing an attempt to jail a member that is already jailed.
+    /// Contains one value, which is the ID of the member.
+    #[error("User <@{0}> is already jailed!")]
+    MemberAlreadyJailed(u64),
+    /// An error representing an attempt to unjail a member that isn't jailed.
+    /// Contains one value, which is the ID of the member.
+    #[error("User <@{0}> is not jailed!")]
+    MemberNotJailed(u64),
 }
@@ -14,6 +14,9 @@ pub enum ModerationType {
     Unmute = 5,
     Untimeout = 6,
     Unban = 7,
+    Quarantine = 8,
+    Jail = 9,
+    Unjail = 10,
 }
 
 impl TryFrom<u8> for ModerationType {
@@ -29,6 +32,9 @@ impl TryFrom<u8> for ModerationType {
             5 => Ok(ModerationType::Unmute),
             6 => Ok(ModerationType::Untimeout),
             7 => Ok(ModerationType::Unban),
+            8 => Ok(ModerationType::Quarantine),
+            9 => Ok(ModerationType::Jail),
+            10 => Ok(ModerationType::Unjail),
             _ => Err(Error::IntEnumError),
         }
     }
@@ -45,6 +51,9 @@ impl std::fmt::Display for ModerationType {
             ModerationType::Unmute => "Unmute",
             ModerationType::Untimeout => "Untimeout",
             ModerationType::Unban => "Unban",
+            ModerationType::Quarantine => "Quarantine",
+            ModerationType::Jail => "Jail",
+            ModerationType::Unjail => "Unjail",
         };
         write!(f, "{}", moderation_string)
     }
@@ -106,6 +106,62 @@ pub fn append_expiry_date(
     }
 }
 
+/// Takes a member out of jail, giving back the roles they had before (minus any that were deleted since).
+/// Returns whether or not the member was jailed in the first place.
+/// This lives here rather than in the command so that expired jails can use it too.
+pub async fn release_from_jail(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: serenity_prelude::GuildId,
+    user_id: serenity_prelude::UserId,
+) -> Result<bool, crate::error::Error> {
+    let jail_role = database::get_jail(database, guild_id)
+        .await?
+        .map(|(role_id, _)| role_id);
+    // The stored roles are only forgotten once they've been given back, so a failed release can be tried again
+    let stored_roles = database::get_jailed_roles(database, guild_id, user_id).await?;
+
+    let mut member = match guild_id.member(&cache_http, user_id).await {
+        Ok(member) => member,
+        // Nothing to give back to someone that left
+        Err(serenity_prelude::Error::Http(error))
+            if error.status_code() == Some(serenity_prelude::StatusCode::NOT_FOUND) =>
+        {
+            database::remove_jailed_roles(database, guild_id, user_id).await?;
+            return Ok(!stored_roles.is_empty());
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    let is_jailed = jail_role.map_or(false, |role_id| member.roles.contains(&role_id));
+    if !is_jailed && stored_roles.is_empty() {
+        return Ok(false);
+    }
+
+    let existing_roles = guild_id.roles(cache_http.http()).await?;
+    let mut roles: Vec<serenity_prelude::RoleId> = member
+        .roles
+        .iter()
+        .filter(|role_id| Some(**role_id) != jail_role)
+        .copied()
+        .collect();
+    for role_id in stored_roles {
+        if existing_roles.contains_key(&role_id) && !roles.contains(&role_id) {
+            roles.push(role_id);
+        }
+    }
+
+    member
+        .edit(
+            &cache_http,
+            serenity_prelude::EditMember::default().roles(roles),
+        )
+        .await?;
+    database::remove_jailed_roles(database, guild_id, user_id).await?;
+
+    Ok(true)
+}
+
 /// Checks if the member has any moderation related permissions.
 /// This is mostly used to determine if a moderation action can be done on the user.
 pub fn is_member_moderator(
@@ -132,6 +188,61 @@ pub fn is_member_moderator(
         || permissions.moderate_members())
 }
 
+/// Makes sure a role can be handed out through a command, so nobody can give out a role above their own.
+/// The role has to be below the highest role of Jolt, and of the member using the command unless they own the guild.
+pub async fn check_role_hierarchy(
+    ctx: &crate::Context<'_>,
+    role: &serenity_prelude::Role,
+) -> Result<(), crate::error::Error> {
+    check_roles_hierarchy(ctx, &[role.id]).await
+}
+
+/// Same as `check_role_hierarchy`, but for several roles at once, like the ones picked in a select menu.
+pub async fn check_roles_hierarchy(
+    ctx: &crate::Context<'_>,
+    role_ids: &[serenity_prelude::RoleId],
+) -> Result<(), crate::error::Error> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let roles = guild_id.roles(ctx.http()).await?;
+    let highest_position = |member: &serenity_prelude::Member| {
+        member
+            .roles
+            .iter()
+            .filter_map(|role_id| roles.get(role_id))
+            .map(|role| role.position)
+            .max()
+            .unwrap_or(0)
+    };
+
+    let bot_id = ctx.cache().current_user().id;
+    let bot_position = highest_position(&guild_id.member(ctx, bot_id).await?);
+
+    let owner_id = guild_id.to_partial_guild(ctx.http()).await?.owner_id;
+    let author_position = if ctx.author().id == owner_id {
+        None
+    } else {
+        Some(highest_position(
+            &guild_id.member(ctx, ctx.author().id).await?,
+        ))
+    };
+
+    for role_id in role_ids {
+        let position = match roles.get(role_id) {
+            Some(role) => role.position,
+            None => continue,
+        };
+
+        if position >= bot_position {
+            return Err(crate::error::Error::RoleAboveBot(role_id.get()));
+        }
+        if author_position.map_or(false, |author_position| position >= author_position) {
+            return Err(crate::error::Error::RoleAboveMember(role_id.get()));
+        }
+    }
+
+    Ok(())
+}
+
 // Formats a CreateEmbed into a modlog format
 pub fn modlog_embed(mut embed: CreateEmbed, modlogs: Vec<ModlogEntry>) -> CreateEmbed {
     for modlog in modlogs {
@@ -162,7 +273,8 @@ pub fn modlog_embed(mut embed: CreateEmbed, modlogs: Vec<ModlogEntry>) -> Create
                     ModerationType::Kick
                     | ModerationType::Unban
                     | ModerationType::Unmute
-                    | ModerationType::Untimeout => String::new(),
+                    | ModerationType::Untimeout
+                    | ModerationType::Unjail => String::new(),
                     _ => format!("\n**Active:** {}", modlog.active),
                 }
             ),
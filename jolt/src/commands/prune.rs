@@ -0,0 +1,408 @@
+// prune commands
+
+use poise::serenity_prelude::{self, CreateEmbed, UserId};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::commands::moderation::utilities::check_role_hierarchy;
+use crate::database;
+use crate::error::PruneError;
+use crate::prune::types::{PruneAction, PrunePolicy};
+use crate::prune::{self, MAX_POLICIES, MIN_INACTIVITY};
+
+// Keeps a preview of every policy well under Discord's embed size limit
+const PREVIEW_MENTIONS: usize = 15;
+
+/// Deal with members that have been inactive for a while
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "prune_add",
+        "prune_remove",
+        "prune_list",
+        "prune_exempt",
+        "prune_unexempt",
+        "prune_preview"
+    ),
+    help_text_fn = "prune_help",
+    category = "prune"
+)]
+pub async fn prune(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn prune_help() -> String {
+    String::from(
+        "Add roles to, remove roles from or kick members that haven't sent a message, joined a voice channel or reacted to anything in a while.
+Policies are run every hour. Use one of the subcommands `add`, `remove`, `list`, `exempt`, `unexempt` or `preview`.
+        ",
+    )
+}
+
+/// Add a policy for members that have been inactive for a while
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "prune_add_help",
+    category = "prune",
+    rename = "add"
+)]
+pub async fn prune_add(
+    ctx: crate::Context<'_>,
+    #[description = "How long members have to be inactive for"] inactive_for: humantime::Duration,
+    #[description = "What to do with inactive members"] action: PruneAction,
+    #[description = "Role to add or remove"] role: Option<serenity_prelude::Role>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if *inactive_for < MIN_INACTIVITY {
+        return Err(PruneError::PeriodTooShort.into());
+    }
+
+    let role_id = match (action, role) {
+        (PruneAction::Kick, _) => {
+            let can_kick = match ctx.author_member().await {
+                Some(member) => member.permissions(ctx.cache())?.kick_members(),
+                None => false,
+            };
+            if !can_kick {
+                return Err(PruneError::MissingKickPermission.into());
+            }
+            None
+        }
+        (_, Some(role)) => {
+            check_role_hierarchy(&ctx, &role).await?;
+            Some(role.id)
+        }
+        (_, None) => return Err(PruneError::MissingRole.into()),
+    };
+
+    let policies = database::get_prune_policies(&ctx.data().database, guild_id).await?;
+    if policies.len() >= MAX_POLICIES {
+        return Err(PruneError::TooManyPolicies(MAX_POLICIES).into());
+    }
+
+    let id = database::add_prune_policy(
+        &ctx.data().database,
+        guild_id,
+        *inactive_for,
+        action,
+        role_id,
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Added prune policy #{}: {} once members are inactive for {}.",
+                    id,
+                    describe_action(action, role_id),
+                    humantime::format_duration(*inactive_for)
+                ))
+                .field(
+                    "NOTE",
+                    "Inactivity is counted from now, so nobody will be affected until that long has passed. Use `prune preview` to see who it applies to.",
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn prune_add_help() -> String {
+    String::from(
+        "Add a policy that adds a role, removes a role or kicks once a member has been inactive for long enough. Periods have to be at least a day.
+Members given a role by a policy have it taken away again once they're active. Roles have to be below both your highest role and Jolt's, and kicking needs the Kick Members permission.
+Example: %prune add 30d \"Add role\" @Inactive
+        ",
+    )
+}
+
+/// Remove a prune policy
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "prune_remove_help",
+    category = "prune",
+    rename = "remove"
+)]
+pub async fn prune_remove(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the policy"] id: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_prune_policy(&ctx.data().database, guild_id, id).await? {
+        return Err(PruneError::NotFound.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Removed prune policy #{}.", id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn prune_remove_help() -> String {
+    String::from(
+        "Remove a prune policy. Members keep any roles it already gave them.
+Example: %prune remove 2
+        ",
+    )
+}
+
+/// List the prune policies and exempt roles of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "prune_list_help",
+    category = "prune",
+    rename = "list"
+)]
+pub async fn prune_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let policies = database::get_prune_policies(&ctx.data().database, guild_id).await?;
+    let exempt_roles = database::get_prune_exempt_roles(&ctx.data().database, guild_id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title("Prune Policies")
+                .description(if policies.is_empty() {
+                    String::from("There are no prune policies.")
+                } else {
+                    policies
+                        .iter()
+                        .map(describe_policy)
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                })
+                .field(
+                    "Exempt Roles:",
+                    if exempt_roles.is_empty() {
+                        String::from("None")
+                    } else {
+                        exempt_roles
+                            .iter()
+                            .map(|role_id| format!("<@&{}>", role_id))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    },
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn prune_list_help() -> String {
+    String::from(
+        "List the prune policies of the server and the roles that are exempt from them.
+Example: %prune list
+        ",
+    )
+}
+
+/// Stop members with a role from being pruned
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "prune_exempt_help",
+    category = "prune",
+    rename = "exempt"
+)]
+pub async fn prune_exempt(
+    ctx: crate::Context<'_>,
+    #[description = "Role to exempt"] role: serenity_prelude::Role,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    database::add_prune_exempt_role(&ctx.data().database, guild_id, role.id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!("Members with <@&{}> will no longer be pruned.", role.id),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn prune_exempt_help() -> String {
+    String::from(
+        "Stop members with a role from being pruned by any policy, no matter how long they've been inactive.
+Bots and the server owner are always exempt.
+Example: %prune exempt @Staff
+        ",
+    )
+}
+
+/// Let members with a role be pruned again
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "prune_unexempt_help",
+    category = "prune",
+    rename = "unexempt"
+)]
+pub async fn prune_unexempt(
+    ctx: crate::Context<'_>,
+    #[description = "Role to stop exempting"] role: serenity_prelude::Role,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_prune_exempt_role(&ctx.data().database, guild_id, role.id).await? {
+        return Err(PruneError::NotExempt.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Members with <@&{}> can be pruned again.", role.id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn prune_unexempt_help() -> String {
+    String::from(
+        "Let members with a role be pruned again.
+Example: %prune unexempt @Staff
+        ",
+    )
+}
+
+/// Show who the prune policies would affect right now
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "prune_preview_help",
+    category = "prune",
+    rename = "preview"
+)]
+pub async fn prune_preview(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the policy (defaults to every policy)"] id: Option<u64>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let mut policies = database::get_prune_policies(&ctx.data().database, guild_id).await?;
+    if let Some(id) = id {
+        policies.retain(|policy| policy.id == id);
+        if policies.is_empty() {
+            return Err(PruneError::NotFound.into());
+        }
+    }
+
+    // Going through every member can take a while
+    ctx.defer().await?;
+    let plans = prune::plan(
+        ctx.serenity_context(),
+        &ctx.data().database,
+        guild_id,
+        policies,
+    )
+    .await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Prune Preview");
+
+        if plans.is_empty() {
+            e = e.description("There are no prune policies.");
+        }
+
+        for plan in &plans {
+            // Mentions don't work in field names, so the policy goes in the value
+            let mut value = format!(
+                "{}\nAffected ({}): {}",
+                describe_policy(&plan.policy),
+                plan.targets.len(),
+                mention_list(&plan.targets)
+            );
+            if !plan.returning.is_empty() {
+                value.push_str(&format!(
+                    "\nActive again, losing the role ({}): {}",
+                    plan.returning.len(),
+                    mention_list(&plan.returning)
+                ));
+            }
+
+            e = e.field(format!("Policy #{}", plan.policy.id), value, false);
+        }
+
+        e
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn prune_preview_help() -> String {
+    String::from(
+        "Show who the prune policies would affect if they were run right now, without changing anything.
+Example: %prune preview 1
+        ",
+    )
+}
+
+fn describe_action(action: PruneAction, role_id: Option<serenity_prelude::RoleId>) -> String {
+    match (action, role_id) {
+        (PruneAction::AddRole, Some(role_id)) => format!("add <@&{}>", role_id),
+        (PruneAction::RemoveRole, Some(role_id)) => format!("remove <@&{}>", role_id),
+        _ => String::from("kick"),
+    }
+}
+
+fn describe_policy(policy: &PrunePolicy) -> String {
+    format!(
+        "#{}: {} after {} inactive",
+        policy.id,
+        describe_action(policy.action, policy.role_id),
+        humantime::format_duration(policy.inactive_for)
+    )
+}
+
+fn mention_list(users: &[UserId]) -> String {
+    if users.is_empty() {
+        return String::from("None");
+    }
+
+    let mut mentions = users
+        .iter()
+        .take(PREVIEW_MENTIONS)
+        .map(|user_id| format!("<@{}>", user_id))
+        .collect::<Vec<String>>()
+        .join(" ");
+    if users.len() > PREVIEW_MENTIONS {
+        mentions.push_str(&format!(" And {} more", users.len() - PREVIEW_MENTIONS));
+    }
+
+    mentions
+}
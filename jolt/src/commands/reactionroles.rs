@@ -0,0 +1,248 @@
+// reaction role commands
+
+use poise::serenity_prelude::{self, CreateEmbed, ReactionType};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::commands::moderation::utilities::check_role_hierarchy;
+use crate::database;
+use crate::error::ReactionRoleError;
+use crate::reactionroles::types::{ReactionRole, ReactionRoleMode};
+
+/// Give roles to members that react to a message
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES | ADD_REACTIONS",
+    subcommands("reactionrole_add", "reactionrole_remove", "reactionrole_list"),
+    help_text_fn = "reactionrole_help",
+    category = "roles"
+)]
+pub async fn reactionrole(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn reactionrole_help() -> String {
+    String::from(
+        "Give roles to members that react to a message.
+Use one of the subcommands `add`, `remove` or `list`.
+        ",
+    )
+}
+
+/// Bind an emoji on a message to a role
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES | ADD_REACTIONS",
+    help_text_fn = "reactionrole_add_help",
+    category = "roles",
+    rename = "add"
+)]
+pub async fn reactionrole_add(
+    ctx: crate::Context<'_>,
+    #[description = "Message to add the reaction role to (link or ID)"]
+    message: serenity_prelude::Message,
+    #[description = "Emoji members react with"] emoji: String,
+    #[description = "Role given or taken away"] role: serenity_prelude::Role,
+    #[description = "How the reaction role behaves"] mode: ReactionRoleMode,
+    #[description = "How long the role is kept for (for temporary reaction roles)"] length: Option<
+        humantime::Duration,
+    >,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if mode == ReactionRoleMode::Temporary && length.is_none() {
+        return Err(ReactionRoleError::MissingDuration.into());
+    }
+    check_role_hierarchy(&ctx, &role).await?;
+
+    let reaction_type = ReactionType::try_from(emoji.as_str())
+        .map_err(|_| ReactionRoleError::InvalidEmoji(emoji.clone()))?;
+    // Reacting first makes sure the emoji is usable, and gives members something to click on
+    message
+        .react(ctx.http(), reaction_type.clone())
+        .await
+        .map_err(|_| ReactionRoleError::InvalidEmoji(emoji.clone()))?;
+
+    // The same emoji could've been bound before under a different name
+    for existing in database::get_message_reaction_roles(&ctx.data().database, message.id).await? {
+        if existing.matches(&reaction_type) {
+            database::remove_reaction_role(&ctx.data().database, message.id, &existing.emoji)
+                .await?;
+        }
+    }
+
+    let reaction_role = ReactionRole {
+        channel_id: message.channel_id,
+        message_id: message.id,
+        emoji: reaction_type.to_string(),
+        role_id: role.id,
+        mode,
+        duration: (mode == ReactionRoleMode::Temporary)
+            .then(|| length.map(|length| *length))
+            .flatten(),
+    };
+    database::add_reaction_role(&ctx.data().database, guild_id, &reaction_role).await?;
+
+    let mut embed = CreateEmbed::default()
+        .color(colors::GREEN)
+        .description(format!(
+            "Reacting with {} on {} now affects <@&{}>.",
+            reaction_role.emoji,
+            message.link(),
+            role.id
+        ))
+        .field("Mode", mode.to_string(), true);
+    if let Some(duration) = reaction_role.duration {
+        embed = embed.field(
+            "Length",
+            humantime::format_duration(duration).to_string(),
+            true,
+        );
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn reactionrole_add_help() -> String {
+    String::from(
+        "Bind an emoji on a message to a role. Jolt reacts to the message with the emoji so members can click it.
+Modes:
+- Normal: reacting gives the role, unreacting takes it away
+- Unique: like normal, but members can only have one unique role from the same message
+- Verify: reacting gives the role, unreacting does nothing
+- Drop: reacting takes the role away, unreacting does nothing
+- Temporary: like normal, but the role is taken away again after the length
+The role has to be below both your highest role and Jolt's.
+Example: %reactionrole add https://discord.com/channels/1/2/3 ✅ @Member Verify
+        ",
+    )
+}
+
+/// Remove a reaction role from a message
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "reactionrole_remove_help",
+    category = "roles",
+    rename = "remove"
+)]
+pub async fn reactionrole_remove(
+    ctx: crate::Context<'_>,
+    #[description = "Message the reaction role is on (link or ID)"]
+    message: serenity_prelude::Message,
+    #[description = "Emoji of the reaction role"] emoji: String,
+) -> Result<(), crate::DynError> {
+    let reaction_type = ReactionType::try_from(emoji.as_str())
+        .map_err(|_| ReactionRoleError::InvalidEmoji(emoji.clone()))?;
+
+    let existing = database::get_message_reaction_roles(&ctx.data().database, message.id)
+        .await?
+        .into_iter()
+        .find(|existing| existing.matches(&reaction_type))
+        .ok_or_else(|| ReactionRoleError::NotFound(emoji.clone()))?;
+    database::remove_reaction_role(&ctx.data().database, message.id, &existing.emoji).await?;
+
+    // Leaving the bot's reaction would make it look like the reaction role still works
+    let _ = message
+        .delete_reaction(ctx.http(), None, reaction_type)
+        .await;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Removed the reaction role for {} on {}.",
+                existing.emoji,
+                message.link()
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn reactionrole_remove_help() -> String {
+    String::from(
+        "Remove a reaction role from a message. Members keep any roles they already got from it.
+Example: %reactionrole remove https://discord.com/channels/1/2/3 ✅
+        ",
+    )
+}
+
+/// List the reaction roles of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "reactionrole_list_help",
+    category = "roles",
+    rename = "list"
+)]
+pub async fn reactionrole_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let reaction_roles = database::get_reaction_roles(&ctx.data().database, guild_id).await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Reaction Roles");
+
+        if reaction_roles.is_empty() {
+            e = e.description("There are no reaction roles.");
+        }
+
+        // Embeds can only hold 25 fields, so reaction roles are grouped by message
+        let mut messages: Vec<(&ReactionRole, Vec<String>)> = Vec::new();
+        for reaction_role in &reaction_roles {
+            let line = format!(
+                "{} <@&{}> ({}{})",
+                reaction_role.emoji,
+                reaction_role.role_id,
+                reaction_role.mode,
+                match reaction_role.duration {
+                    Some(duration) => format!(", {}", humantime::format_duration(duration)),
+                    None => String::new(),
+                }
+            );
+
+            match messages.last_mut() {
+                Some((first, lines)) if first.message_id == reaction_role.message_id => {
+                    lines.push(line)
+                }
+                _ => messages.push((reaction_role, vec![line])),
+            }
+        }
+
+        for (first, lines) in messages.into_iter().take(25) {
+            e = e.field(
+                format!("Message {}", first.message_id),
+                format!(
+                    "{}\n{}",
+                    first.message_id.link(first.channel_id, Some(guild_id)),
+                    lines.join("\n")
+                ),
+                false,
+            );
+        }
+
+        e
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn reactionrole_list_help() -> String {
+    String::from(
+        "List the reaction roles of the server, grouped by message.
+Example: %reactionrole list
+        ",
+    )
+}
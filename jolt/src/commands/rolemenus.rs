@@ -0,0 +1,408 @@
+// role menu commands
+
+use poise::serenity_prelude::{self, CreateEmbed, ReactionType};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::commands::moderation::utilities::check_role_hierarchy;
+use crate::database;
+use crate::error::RoleMenuError;
+use crate::rolemenus;
+use crate::rolemenus::types::{RoleMenuOption, RoleMenuStyle};
+
+/// Let members pick their own roles with buttons or a select menu
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    subcommands(
+        "rolemenu_create",
+        "rolemenu_add",
+        "rolemenu_remove",
+        "rolemenu_post",
+        "rolemenu_delete",
+        "rolemenu_list"
+    ),
+    help_text_fn = "rolemenu_help",
+    category = "roles"
+)]
+pub async fn rolemenu(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn rolemenu_help() -> String {
+    String::from(
+        "Let members pick their own roles with buttons or a select menu.
+Create a menu, add roles to it and then post it in a channel.
+Use one of the subcommands `create`, `add`, `remove`, `post`, `delete` or `list`.
+        ",
+    )
+}
+
+/// Create a new role menu
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "rolemenu_create_help",
+    category = "roles",
+    rename = "create"
+)]
+pub async fn rolemenu_create(
+    ctx: crate::Context<'_>,
+    #[description = "What members pick their roles with"] style: RoleMenuStyle,
+    #[description = "Title of the menu"] title: String,
+    #[description = "Role members need to use the menu"] required_role: Option<
+        serenity_prelude::Role,
+    >,
+    #[description = "Fewest roles from the menu members can have"] min_roles: Option<u32>,
+    #[description = "Most roles from the menu members can have"] max_roles: Option<u32>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let min_roles = min_roles.unwrap_or(0);
+
+    if max_roles.map_or(false, |max_roles| min_roles > max_roles || max_roles == 0) {
+        return Err(RoleMenuError::InvalidLimits.into());
+    }
+
+    let id = database::add_role_menu(
+        &ctx.data().database,
+        guild_id,
+        &title,
+        style,
+        min_roles,
+        max_roles,
+        required_role.as_ref().map(|role| role.id),
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Created role menu {}. Add roles to it with `rolemenu add {} <role>`.",
+                    id, id
+                ))
+                .field("Style", style.to_string(), true),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn rolemenu_create_help() -> String {
+    String::from(
+        "Create a new role menu. Buttons toggle a single role each, while a select menu sets all of the member's roles from the menu at once.
+The minimum and maximum limit how many roles from the menu members can have, and members need the required role to use the menu at all.
+Members below the minimum can still pick roles, they just can't give any up until they reach it.
+Example: %rolemenu create Buttons Colors
+        ",
+    )
+}
+
+/// Add a role to a role menu
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "rolemenu_add_help",
+    category = "roles",
+    rename = "add"
+)]
+pub async fn rolemenu_add(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the role menu"] id: u64,
+    #[description = "Role to add"] role: serenity_prelude::Role,
+    #[description = "Text shown for the role (defaults to the role name)"] label: Option<String>,
+    #[description = "Emoji shown for the role"] emoji: Option<String>,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let menu = database::get_role_menu(&ctx.data().database, guild_id, id)
+        .await?
+        .ok_or(RoleMenuError::NotFound(id))?;
+    let already_added = menu.options.iter().any(|option| option.role_id == role.id);
+    if !already_added && menu.options.len() >= rolemenus::MAX_OPTIONS {
+        return Err(RoleMenuError::TooManyRoles(rolemenus::MAX_OPTIONS).into());
+    }
+    check_role_hierarchy(&ctx, &role).await?;
+
+    let emoji = match emoji {
+        Some(emoji) => Some(
+            ReactionType::try_from(emoji.as_str())
+                .map_err(|_| RoleMenuError::InvalidEmoji(emoji.clone()))?
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    let option = RoleMenuOption {
+        role_id: role.id,
+        label: label.unwrap_or_else(|| role.name.clone()),
+        emoji,
+    };
+    database::add_role_menu_option(&ctx.data().database, guild_id, id, &option).await?;
+
+    let refreshed = refresh(ctx, guild_id, id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Added <@&{}> to role menu {}.{}",
+                role.id,
+                id,
+                if refreshed {
+                    " The posted menu has been updated."
+                } else {
+                    ""
+                }
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn rolemenu_add_help() -> String {
+    String::from(
+        "Add a role to a role menu, or change its label and emoji if it's already in there. A menu can hold up to 25 roles.
+The role has to be below both your highest role and Jolt's. If the menu has been posted, the message is updated right away.
+Example: %rolemenu add 1 @Red Red 🔴
+        ",
+    )
+}
+
+/// Remove a role from a role menu
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "rolemenu_remove_help",
+    category = "roles",
+    rename = "remove"
+)]
+pub async fn rolemenu_remove(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the role menu"] id: u64,
+    #[description = "Role to remove"] role: serenity_prelude::Role,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if database::get_role_menu(&ctx.data().database, guild_id, id)
+        .await?
+        .is_none()
+    {
+        return Err(RoleMenuError::NotFound(id).into());
+    }
+    if !database::remove_role_menu_option(&ctx.data().database, guild_id, id, role.id).await? {
+        return Err(RoleMenuError::RoleNotInMenu.into());
+    }
+
+    refresh(ctx, guild_id, id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Removed <@&{}> from role menu {}.", role.id, id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn rolemenu_remove_help() -> String {
+    String::from(
+        "Remove a role from a role menu. Members keep the role if they already have it.
+Example: %rolemenu remove 1 @Red
+        ",
+    )
+}
+
+/// Post a role menu in a channel
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "SEND_MESSAGES | EMBED_LINKS",
+    help_text_fn = "rolemenu_post_help",
+    category = "roles",
+    rename = "post"
+)]
+pub async fn rolemenu_post(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the role menu"] id: u64,
+    #[description = "Channel to post the menu in"] channel: serenity_prelude::GuildChannel,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let menu = database::get_role_menu(&ctx.data().database, guild_id, id)
+        .await?
+        .ok_or(RoleMenuError::NotFound(id))?;
+    if menu.options.is_empty() {
+        return Err(RoleMenuError::Empty.into());
+    }
+
+    // Only one copy of a menu is kept up, so posting it elsewhere moves it
+    if let Some((channel_id, message_id)) = menu.message {
+        let _ = channel_id.delete_message(ctx.http(), message_id).await;
+    }
+
+    let message_id = rolemenus::post(&ctx, &menu, channel.id).await?;
+    database::set_role_menu_message(&ctx.data().database, guild_id, id, channel.id, message_id)
+        .await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            format!(
+                "Posted role menu {} in {}.",
+                id,
+                message_id.link(channel.id, Some(guild_id))
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn rolemenu_post_help() -> String {
+    String::from(
+        "Post a role menu in a channel. If the menu was already posted somewhere, the old message is deleted.
+Example: %rolemenu post 1 #roles
+        ",
+    )
+}
+
+/// Delete a role menu
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "rolemenu_delete_help",
+    category = "roles",
+    rename = "delete"
+)]
+pub async fn rolemenu_delete(
+    ctx: crate::Context<'_>,
+    #[description = "ID of the role menu"] id: u64,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let menu = database::get_role_menu(&ctx.data().database, guild_id, id)
+        .await?
+        .ok_or(RoleMenuError::NotFound(id))?;
+    if let Some((channel_id, message_id)) = menu.message {
+        let _ = channel_id.delete_message(ctx.http(), message_id).await;
+    }
+    database::remove_role_menu(&ctx.data().database, guild_id, id).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!("Deleted role menu {}.", id)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn rolemenu_delete_help() -> String {
+    String::from(
+        "Delete a role menu, along with its message if it has been posted. Members keep any roles they got from it.
+Example: %rolemenu delete 1
+        ",
+    )
+}
+
+/// List the role menus of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "rolemenu_list_help",
+    category = "roles",
+    rename = "list"
+)]
+pub async fn rolemenu_list(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    let mut menus = Vec::new();
+    for id in database::get_role_menu_ids(&ctx.data().database, guild_id).await? {
+        if let Some(menu) = database::get_role_menu(&ctx.data().database, guild_id, id).await? {
+            menus.push(menu);
+        }
+    }
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Role Menus");
+
+        if menus.is_empty() {
+            e = e.description("There are no role menus.");
+        }
+
+        // Embeds can only hold 25 fields
+        for menu in menus.iter().take(25) {
+            let mut lines = vec![format!("Style: {}", menu.style)];
+            if let Some((channel_id, message_id)) = menu.message {
+                lines.push(format!(
+                    "Posted: {}",
+                    message_id.link(channel_id, Some(guild_id))
+                ));
+            }
+            lines.push(format!(
+                "Roles: {}",
+                if menu.options.is_empty() {
+                    String::from("None")
+                } else {
+                    menu.options
+                        .iter()
+                        .map(|option| format!("<@&{}>", option.role_id))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                }
+            ));
+
+            e = e.field(
+                format!("{}: {}", menu.id, menu.title),
+                lines.join("\n"),
+                false,
+            );
+        }
+
+        e
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn rolemenu_list_help() -> String {
+    String::from(
+        "List the role menus of the server, along with where they're posted.
+Example: %rolemenu list
+        ",
+    )
+}
+
+/// Updates a menu's message after it has been changed, if it has been posted.
+async fn refresh(
+    ctx: crate::Context<'_>,
+    guild_id: serenity_prelude::GuildId,
+    id: u64,
+) -> Result<bool, crate::DynError> {
+    match database::get_role_menu(&ctx.data().database, guild_id, id).await? {
+        // A menu with nothing left in it can't have any components, so it's left as it was
+        Some(menu) if !menu.options.is_empty() => Ok(rolemenus::refresh(&ctx, &menu).await?),
+        _ => Ok(false),
+    }
+}
@@ -0,0 +1,144 @@
+// temporary role commands
+
+use poise::serenity_prelude::{self, CreateEmbed, CreateEmbedFooter};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::commands::moderation::utilities::{append_expiry_date, check_role_hierarchy};
+use crate::database;
+use crate::error::TempRoleError;
+use crate::temproles;
+
+/// Give a member a role for a while
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    help_text_fn = "temprole_help",
+    category = "roles"
+)]
+pub async fn temprole(
+    ctx: crate::Context<'_>,
+    #[description = "Member to give the role to"] user: serenity_prelude::User,
+    #[description = "Role to give"] role: serenity_prelude::Role,
+    #[description = "How long the member keeps the role"] length: humantime::Duration,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    check_role_hierarchy(&ctx, &role).await?;
+
+    // Only roles that are already temporary can be extended, anything else would be taken away for good
+    let member = guild_id.member(&ctx, user.id).await?;
+    let is_temporary = database::get_temporary_roles(&ctx.data().database, guild_id, Some(user.id))
+        .await?
+        .iter()
+        .any(|(_, role_id, _)| *role_id == role.id);
+    if member.roles.contains(&role.id) && !is_temporary {
+        return Err(TempRoleError::AlreadyHasRole.into());
+    }
+
+    let expiry_date = temproles::grant(
+        &ctx,
+        &ctx.data().database,
+        guild_id,
+        user.id,
+        role.id,
+        *length,
+        &format!("Temporary role given by {}", ctx.author().name),
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default().embed(CreateEmbed::default().color(colors::GREEN).description(
+            append_expiry_date(
+                &format!("<@{}> has been given <@&{}>", user.id, role.id),
+                Some(expiry_date),
+            ),
+        )),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn temprole_help() -> String {
+    String::from(
+        "Give a member a role, which is taken away again after the length. Using it again on the same role changes when it's taken away.
+The role has to be below both your highest role and Jolt's.
+Example: %temprole @Joshument#0001 @Event 2h
+        ",
+    )
+}
+
+/// List the temporary roles of the server
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_ROLES",
+    help_text_fn = "temprole_list_help",
+    category = "roles",
+    rename = "temproles"
+)]
+pub async fn temprole_list(
+    ctx: crate::Context<'_>,
+    #[description = "Only show the temporary roles of this member"] user: Option<
+        serenity_prelude::User,
+    >,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let temporary_roles = database::get_temporary_roles(
+        &ctx.data().database,
+        guild_id,
+        user.as_ref().map(|user| user.id),
+    )
+    .await?;
+
+    ctx.send(CreateReply::default().embed({
+        let mut e = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("Temporary Roles");
+
+        if temporary_roles.is_empty() {
+            e = e.description("There are no temporary roles.");
+        } else {
+            // Embed descriptions are capped at 4096 characters, and each line is well under 100
+            e = e.description(
+                temporary_roles
+                    .iter()
+                    .take(40)
+                    .map(|(user_id, role_id, expiry_date)| {
+                        format!(
+                            "<@{}>: <@&{}> until <t:{}:R>",
+                            user_id,
+                            role_id,
+                            expiry_date.unix_timestamp()
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            );
+        }
+
+        if temporary_roles.len() > 40 {
+            e = e.footer(CreateEmbedFooter::new(format!(
+                "And {} more",
+                temporary_roles.len() - 40
+            )));
+        }
+
+        e
+    }))
+    .await?;
+
+    Ok(())
+}
+
+fn temprole_list_help() -> String {
+    String::from(
+        "List the temporary roles of the server (or of one member), soonest to expire first.
+This includes temporary reaction roles.
+Example: %temproles @Joshument#0001
+        ",
+    )
+}
@@ -0,0 +1,270 @@
+// member verification configuration commands
+
+use std::time::Duration;
+
+use poise::serenity_prelude::{self, CreateEmbed};
+use poise::CreateReply;
+
+use crate::colors;
+use crate::commands::moderation::utilities::check_roles_hierarchy;
+use crate::database;
+use crate::error::VerificationError;
+use crate::verification::types::{VerificationChallenge, VerificationSettings};
+
+const MIN_TIME_LIMIT: Duration = Duration::from_secs(30);
+const MAX_TIME_LIMIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Configure verification for new members
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "verification_enable",
+        "verification_question",
+        "verification_disable",
+        "verification_status"
+    ),
+    help_text_fn = "verification_help",
+    category = "verification"
+)]
+pub async fn verification(_ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    Ok(())
+}
+
+fn verification_help() -> String {
+    String::from(
+        "Configure verification for new members.
+Use one of the subcommands `enable`, `question`, `disable` or `status`.
+        ",
+    )
+}
+
+/// Enable or update verification
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "verification_enable_help",
+    category = "verification",
+    rename = "enable"
+)]
+pub async fn verification_enable(
+    ctx: crate::Context<'_>,
+    #[description = "Channel new members verify in"] channel: serenity_prelude::GuildChannel,
+    #[description = "Role given to new members until they verify"]
+    unverified_role: serenity_prelude::Role,
+    #[description = "Role given to members once they verify"] verified_role: serenity_prelude::Role,
+    #[description = "Time new members have to verify before being kicked"]
+    time_limit: humantime::Duration,
+    #[description = "Kind of challenge new members have to complete"]
+    challenge: VerificationChallenge,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if unverified_role.id == verified_role.id {
+        return Err(VerificationError::SameRoles.into());
+    }
+    if *time_limit < MIN_TIME_LIMIT || *time_limit > MAX_TIME_LIMIT {
+        return Err(VerificationError::InvalidTimeLimit(MIN_TIME_LIMIT, MAX_TIME_LIMIT).into());
+    }
+    check_roles_hierarchy(&ctx, &[unverified_role.id, verified_role.id]).await?;
+
+    let question = database::get_verification_settings(&ctx.data().database, guild_id)
+        .await?
+        .and_then(|settings| settings.question);
+    if challenge == VerificationChallenge::Question && question.is_none() {
+        return Err(VerificationError::QuestionNotSet.into());
+    }
+
+    let settings = VerificationSettings {
+        channel: channel.id,
+        unverified_role: unverified_role.id,
+        verified_role: verified_role.id,
+        challenge,
+        time_limit: *time_limit,
+        question,
+    };
+    database::set_verification_settings(&ctx.data().database, guild_id, &settings).await?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description(format!(
+                    "Verification has been enabled. New members will get <@&{}> and have {} to verify in <#{}>.",
+                    settings.unverified_role,
+                    humantime::format_duration(settings.time_limit),
+                    settings.channel
+                ))
+                .field("Challenge", challenge.to_string(), true)
+                .field("Verified Role", format!("<@&{}>", settings.verified_role), true)
+                .field(
+                    "NOTE",
+                    "Make sure the unverified role can only see the verification channel, and that Jolt's role is \
+                    above both roles.",
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn verification_enable_help() -> String {
+    String::from(
+        "Enable verification, or update it if it is already enabled.
+New members get the unverified role and a message in the verification channel. They have to complete the challenge \
+within the time limit to get the verified role, or they are kicked. Getting it wrong 3 times also gets them kicked.
+The question challenge needs a question to be set with `verification question` first.
+Both roles have to be below your highest role and Jolt's.
+Example: %verification enable #verify @Unverified @Member 10m Text
+        ",
+    )
+}
+
+/// Set the question used by the question challenge
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "verification_question_help",
+    category = "verification",
+    rename = "question"
+)]
+pub async fn verification_question(
+    ctx: crate::Context<'_>,
+    #[description = "Question to ask new members"] question: String,
+    #[description = "Answer to the question (not case sensitive)"] answer: String,
+) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    // Discord doesn't allow modal labels longer than 45 characters
+    let question: String = question.chars().take(45).collect();
+    if !database::set_verification_question(&ctx.data().database, guild_id, &question, &answer)
+        .await?
+    {
+        return Err(VerificationError::NotEnabled.into());
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::default()
+                    .color(colors::GREEN)
+                    .description("The verification question has been set.")
+                    .field("Question", question, false)
+                    .field("Answer", answer, false),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn verification_question_help() -> String {
+    String::from(
+        "Set the question new members have to answer when using the question challenge. Answers aren't case \
+sensitive. Questions longer than 45 characters are cut short.
+Example: %verification question \"What is rule 3?\" \"No spam\"
+        ",
+    )
+}
+
+/// Disable verification
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "verification_disable_help",
+    category = "verification",
+    rename = "disable"
+)]
+pub async fn verification_disable(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+
+    if !database::remove_verification_settings(&ctx.data().database, guild_id).await? {
+        return Err(VerificationError::NotEnabled.into());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::GREEN)
+                .description("Verification has been disabled."),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn verification_disable_help() -> String {
+    String::from(
+        "Disable verification. Members that are still verifying keep the unverified role.
+Example: %verification disable
+        ",
+    )
+}
+
+/// Show the verification settings
+#[poise::command(
+    prefix_command,
+    slash_command,
+    required_permissions = "MANAGE_GUILD",
+    help_text_fn = "verification_status_help",
+    category = "verification",
+    rename = "status"
+)]
+pub async fn verification_status(ctx: crate::Context<'_>) -> Result<(), crate::DynError> {
+    let guild_id = ctx.guild_id().expect("Failed to get guild ID!");
+    let settings = database::get_verification_settings(&ctx.data().database, guild_id)
+        .await?
+        .ok_or(VerificationError::NotEnabled)?;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::default()
+                .color(colors::BLUE)
+                .title("Verification")
+                .field("Channel", format!("<#{}>", settings.channel), true)
+                .field(
+                    "Unverified Role",
+                    format!("<@&{}>", settings.unverified_role),
+                    true,
+                )
+                .field(
+                    "Verified Role",
+                    format!("<@&{}>", settings.verified_role),
+                    true,
+                )
+                .field("Challenge", settings.challenge.to_string(), true)
+                .field(
+                    "Time Limit",
+                    humantime::format_duration(settings.time_limit).to_string(),
+                    true,
+                )
+                .field(
+                    "Question",
+                    match &settings.question {
+                        Some((question, _)) => question.clone(),
+                        None => String::from("None"),
+                    },
+                    false,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn verification_status_help() -> String {
+    String::from(
+        "Show the verification settings of the server.
+Example: %verification status
+        ",
+    )
+}
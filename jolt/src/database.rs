@@ -3,11 +3,29 @@ This file provides a lot of syntatic sugar around database access to make it eas
 In all technicality, you don't even need to know SQL if you don't intend to touch this file. You're welcome
 */
 
+use std::collections::HashMap;
+
 use poise::serenity_prelude::{self, ChannelId};
-use serenity_prelude::{GuildId, RoleId, Timestamp, UserId};
+use serenity_prelude::{GuildId, MessageId, RoleId, Timestamp, UserId};
 
+use crate::antinuke::AntinukeSettings;
+use crate::antiraid::types::{AgeGateSettings, RaidSettings};
+use crate::automod::types::{
+    AutomodRule, AutomodRuleSettings, DomainList, ExemptionKind, FilterKind, FilterRule,
+    LinkFilterSettings, NameFilterAction, Punishment,
+};
+use crate::autoroles::types::AutoroleSettings;
+use crate::backup::SnapshotInfo;
 use crate::commands::moderation::types::{ModerationType, ModlogEntry};
 use crate::error::Error;
+use crate::leveling::types::{
+    LevelCurve, LevelReward, LevelingSettings, RankCardTheme, RewardMode, XpMultiplier,
+    XpMultiplierKind,
+};
+use crate::prune::types::{PruneAction, PrunePolicy};
+use crate::reactionroles::types::ReactionRole;
+use crate::rolemenus::types::{RoleMenu, RoleMenuOption, RoleMenuStyle};
+use crate::verification::types::VerificationSettings;
 
 /// Sets all existing moderations of the type `ModerationType` to inactive.
 pub async fn clear_moderations(
@@ -20,7 +38,11 @@ pub async fn clear_moderations(
     let guild_id: i64 = guild_id.into().into();
     let user_id: i64 = user_id.into().into();
 
-    if let ModerationType::Ban | ModerationType::Mute | ModerationType::Timeout = moderation_type {
+    if let ModerationType::Ban
+    | ModerationType::Mute
+    | ModerationType::Timeout
+    | ModerationType::Jail = moderation_type
+    {
         sqlx::query!(
             "UPDATE moderations SET active = FALSE WHERE guild_id = ? AND user_id = ? AND moderation_type = ?",
             guild_id,
@@ -69,13 +91,18 @@ pub async fn add_moderation(
         ModerationType::Kick
         | ModerationType::Unban
         | ModerationType::Unmute
-        | ModerationType::Untimeout => false,
+        | ModerationType::Untimeout
+        | ModerationType::Unjail => false,
         _ => true,
     };
 
-    // Bans, Mutes, and Timeouts should only occur once per guild per member
+    // Bans, Mutes, Timeouts, and Jails should only occur once per guild per member
     // This is to prevent double expiries, which could cause unexpected unban times
-    if let ModerationType::Ban | ModerationType::Mute | ModerationType::Timeout = moderation_type {
+    if let ModerationType::Ban
+    | ModerationType::Mute
+    | ModerationType::Timeout
+    | ModerationType::Jail = moderation_type
+    {
         clear_moderations(
             &database,
             guild_id.clone(),
@@ -161,6 +188,95 @@ pub async fn get_mute_role(
     }
 }
 
+pub async fn set_quarantine_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, quarantine_role_id) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET quarantine_role_id=excluded.quarantine_role_id",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_quarantine_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Option<RoleId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT quarantine_role_id FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.and_then(|some| {
+        some.quarantine_role_id
+            .map(|unwrapped| RoleId::new(unwrapped as u64))
+    }))
+}
+
+pub async fn set_jail(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+    channel_id: impl Into<ChannelId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+    let channel_id_i64: i64 = channel_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, jail_role_id, jail_channel_id) VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id) DO UPDATE SET jail_role_id=excluded.jail_role_id, \
+        jail_channel_id=excluded.jail_channel_id",
+        guild_id_i64,
+        role_id_i64,
+        channel_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the jail role of the guild, along with the channel it's restricted to.
+pub async fn get_jail(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Option<(RoleId, ChannelId)>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT jail_role_id, jail_channel_id FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.and_then(|some| {
+        some.jail_role_id
+            .zip(some.jail_channel_id)
+            .map(|(role_id, channel_id)| {
+                (
+                    RoleId::new(role_id as u64),
+                    ChannelId::new(channel_id as u64),
+                )
+            })
+    }))
+}
+
 pub async fn set_logs_channel(
     database: &sqlx::SqlitePool,
     guild_id: impl Into<GuildId>,
@@ -371,3 +487,3182 @@ pub async fn get_single_modlog(database: &sqlx::SqlitePool, id: u64) -> sqlx::Re
         active: modlog.active,
     })
 }
+
+/// Gets every active moderation whose expiry date is before `current_time`, so that it can be undone.
+pub async fn get_expired_moderations(
+    database: &sqlx::SqlitePool,
+    current_time: i64,
+) -> Result<Vec<(GuildId, UserId, ModerationType)>, Error> {
+    let entries = sqlx::query!(
+        "SELECT guild_id, user_id, moderation_type FROM moderations WHERE expiry_date < ? AND active = TRUE",
+        current_time
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok((
+                GuildId::new(entry.guild_id as u64),
+                UserId::new(entry.user_id as u64),
+                (entry.moderation_type as u8).try_into()?,
+            ))
+        })
+        .collect()
+}
+
+pub async fn set_automod_rule(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    settings: &AutomodRuleSettings,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let rule_u8 = settings.rule as u8;
+    let threshold = settings.threshold as i64;
+    let interval_seconds = settings.interval.as_secs() as i64;
+    let action_u8 = settings.punishment.action as u8;
+    let action_duration = settings
+        .punishment
+        .duration
+        .map(|duration| duration.as_secs() as i64);
+
+    sqlx::query!(
+        "INSERT INTO automod_rules (guild_id, rule, threshold, interval_seconds, action, action_duration, log_only) \
+        VALUES (?, ?, ?, ?, ?, ?, ?) \
+        ON CONFLICT (guild_id, rule) DO UPDATE SET \
+        threshold=excluded.threshold, interval_seconds=excluded.interval_seconds, \
+        action=excluded.action, action_duration=excluded.action_duration, log_only=excluded.log_only",
+        guild_id_i64,
+        rule_u8,
+        threshold,
+        interval_seconds,
+        action_u8,
+        action_duration,
+        settings.punishment.log_only
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes a rule from the guild.
+/// Returns whether or not the rule was enabled in the first place.
+pub async fn remove_automod_rule(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    rule: AutomodRule,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let rule_u8 = rule as u8;
+
+    let result = sqlx::query!(
+        "DELETE FROM automod_rules WHERE guild_id = ? AND rule = ?",
+        guild_id_i64,
+        rule_u8
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_automod_rules(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Vec<AutomodRuleSettings>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT * FROM automod_rules WHERE guild_id = ? ORDER BY rule",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(AutomodRuleSettings {
+                rule: (entry.rule as u8).try_into()?,
+                threshold: entry.threshold as u32,
+                interval: std::time::Duration::from_secs(entry.interval_seconds as u64),
+                punishment: Punishment {
+                    action: (entry.action as u8).try_into()?,
+                    duration: entry
+                        .action_duration
+                        .map(|duration| std::time::Duration::from_secs(duration as u64)),
+                    log_only: entry.log_only,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Adds a rule to the guild's filter, returning the ID of the new rule.
+pub async fn add_filter_rule(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    kind: FilterKind,
+    pattern: &str,
+    punishment: &Punishment,
+) -> sqlx::Result<u64> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let kind_u8 = kind as u8;
+    let action_u8 = punishment.action as u8;
+    let action_duration = punishment
+        .duration
+        .map(|duration| duration.as_secs() as i64);
+
+    // Same as moderations, IDs are counted per guild
+    let id: i64 = sqlx::query!(
+        "SELECT MAX(id) AS max_id FROM filter_rules WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_one(database)
+    .await?
+    .max_id
+    .unwrap_or(0)
+        + 1;
+
+    sqlx::query!(
+        "INSERT INTO filter_rules (id, guild_id, kind, pattern, action, action_duration, log_only) \
+        VALUES (?, ?, ?, ?, ?, ?, ?)",
+        id,
+        guild_id_i64,
+        kind_u8,
+        pattern,
+        action_u8,
+        action_duration,
+        punishment.log_only
+    )
+    .execute(database)
+    .await?;
+
+    Ok(id as u64)
+}
+
+/// Removes a rule from the guild's filter.
+/// Returns whether or not the rule existed.
+pub async fn remove_filter_rule(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id = id as i64;
+
+    let result = sqlx::query!(
+        "DELETE FROM filter_rules WHERE guild_id = ? AND id = ?",
+        guild_id_i64,
+        id
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_filter_rules(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Vec<FilterRule>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT * FROM filter_rules WHERE guild_id = ? ORDER BY id",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(FilterRule {
+                id: entry.id as u64,
+                kind: (entry.kind as u8).try_into()?,
+                pattern: entry.pattern,
+                punishment: Punishment {
+                    action: (entry.action as u8).try_into()?,
+                    duration: entry
+                        .action_duration
+                        .map(|duration| std::time::Duration::from_secs(duration as u64)),
+                    log_only: entry.log_only,
+                },
+            })
+        })
+        .collect()
+}
+
+pub async fn add_filter_exemption(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    target_id: u64,
+    kind: ExemptionKind,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let target_id_i64 = target_id as i64;
+    let kind_u8 = kind as u8;
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO filter_exemptions (guild_id, target_id, kind) VALUES (?, ?, ?)",
+        guild_id_i64,
+        target_id_i64,
+        kind_u8
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_filter_exemption(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    target_id: u64,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let target_id_i64 = target_id as i64;
+
+    let result = sqlx::query!(
+        "DELETE FROM filter_exemptions WHERE guild_id = ? AND target_id = ?",
+        guild_id_i64,
+        target_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets every filter exemption in the guild as pairs of the exempted ID and what kind of ID it is.
+pub async fn get_filter_exemptions(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Vec<(u64, ExemptionKind)>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT target_id, kind FROM filter_exemptions WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| Ok((entry.target_id as u64, (entry.kind as u8).try_into()?)))
+        .collect()
+}
+
+pub async fn set_link_filter_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    settings: &LinkFilterSettings,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let action_u8 = settings.punishment.action as u8;
+    let action_duration = settings
+        .punishment
+        .duration
+        .map(|duration| duration.as_secs() as i64);
+
+    sqlx::query!(
+        "INSERT INTO link_filter_settings (guild_id, filter_invites, filter_urls, action, action_duration, log_only) \
+        VALUES (?, ?, ?, ?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET \
+        filter_invites=excluded.filter_invites, filter_urls=excluded.filter_urls, \
+        action=excluded.action, action_duration=excluded.action_duration, log_only=excluded.log_only",
+        guild_id_i64,
+        settings.filter_invites,
+        settings.filter_urls,
+        action_u8,
+        action_duration,
+        settings.punishment.log_only
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the link filter settings of the guild.
+/// Returns `None` if the link filter isn't enabled at all.
+pub async fn get_link_filter_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Option<LinkFilterSettings>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT * FROM link_filter_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    match entry {
+        Some(entry) if entry.filter_invites || entry.filter_urls => Ok(Some(LinkFilterSettings {
+            filter_invites: entry.filter_invites,
+            filter_urls: entry.filter_urls,
+            punishment: Punishment {
+                action: (entry.action as u8).try_into()?,
+                duration: entry
+                    .action_duration
+                    .map(|duration| std::time::Duration::from_secs(duration as u64)),
+                log_only: entry.log_only,
+            },
+        })),
+        _ => Ok(None),
+    }
+}
+
+pub async fn set_link_filter_domain(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    domain: &str,
+    list: DomainList,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let allowed = list == DomainList::Allow;
+
+    sqlx::query!(
+        "INSERT INTO link_filter_domains (guild_id, domain, allowed) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id, domain) DO UPDATE SET allowed=excluded.allowed",
+        guild_id_i64,
+        domain,
+        allowed
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_link_filter_domain(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    domain: &str,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM link_filter_domains WHERE guild_id = ? AND domain = ?",
+        guild_id_i64,
+        domain
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_link_filter_domains(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<(String, DomainList)>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT domain, allowed FROM link_filter_domains WHERE guild_id = ? ORDER BY domain",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let list = if entry.allowed {
+                DomainList::Allow
+            } else {
+                DomainList::Deny
+            };
+            (entry.domain, list)
+        })
+        .collect())
+}
+
+pub async fn add_invite_whitelist(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    whitelisted_guild_id: impl Into<GuildId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let whitelisted_guild_id_i64: i64 = whitelisted_guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO invite_whitelist (guild_id, whitelisted_guild_id) VALUES (?, ?)",
+        guild_id_i64,
+        whitelisted_guild_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_invite_whitelist(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    whitelisted_guild_id: impl Into<GuildId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let whitelisted_guild_id_i64: i64 = whitelisted_guild_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM invite_whitelist WHERE guild_id = ? AND whitelisted_guild_id = ?",
+        guild_id_i64,
+        whitelisted_guild_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_invite_whitelist(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<GuildId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT whitelisted_guild_id FROM invite_whitelist WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| GuildId::new(entry.whitelisted_guild_id as u64))
+        .collect())
+}
+
+/// Enables or disables phishing protection.
+/// `timeout` is how long users that post phishing links get timed out for, and `None` disables protection.
+pub async fn set_phishing_protection(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    timeout: Option<std::time::Duration>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let enabled = timeout.is_some();
+    let timeout = timeout.map(|timeout| timeout.as_secs() as i64);
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, phishing_protection, phishing_timeout) VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id) DO UPDATE SET \
+        phishing_protection=excluded.phishing_protection, phishing_timeout=excluded.phishing_timeout",
+        guild_id_i64,
+        enabled,
+        timeout
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_dehoist(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    enabled: bool,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, dehoist) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET dehoist=excluded.dehoist",
+        guild_id_i64,
+        enabled
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_dehoist(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT dehoist FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.map_or(false, |some| some.dehoist))
+}
+
+/// Sets what happens to members whose name trips the filter.
+/// `None` turns the name filter off.
+pub async fn set_name_filter(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    action: Option<NameFilterAction>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let action_u8 = action.map(|action| action as u8);
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, name_filter_action) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET name_filter_action=excluded.name_filter_action",
+        guild_id_i64,
+        action_u8
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_name_filter(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Option<NameFilterAction>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT name_filter_action FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    entry
+        .and_then(|some| some.name_filter_action)
+        .map(|action| (action as u8).try_into())
+        .transpose()
+}
+
+pub async fn set_ghost_ping_detection(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    enabled: bool,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, ghost_ping_detection) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET ghost_ping_detection=excluded.ghost_ping_detection",
+        guild_id_i64,
+        enabled
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_ghost_ping_detection(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT ghost_ping_detection FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.map_or(false, |some| some.ghost_ping_detection))
+}
+
+/// Gets how long users get timed out for posting phishing links.
+/// Returns `None` if phishing protection is disabled.
+pub async fn get_phishing_protection(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Option<std::time::Duration>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT phishing_protection, phishing_timeout FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.and_then(|some| {
+        some.phishing_protection
+            .then(|| std::time::Duration::from_secs(some.phishing_timeout.unwrap_or(0) as u64))
+    }))
+}
+
+pub async fn set_antiraid_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    settings: &RaidSettings,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let threshold = settings.threshold as i64;
+    let interval_seconds = settings.interval.as_secs() as i64;
+    let action_u8 = settings.action as u8;
+    let verification_level_u8 = settings.verification_level as u8;
+
+    sqlx::query!(
+        "INSERT INTO antiraid_settings (guild_id, join_threshold, interval_seconds, action, verification_level) \
+        VALUES (?, ?, ?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET \
+        join_threshold=excluded.join_threshold, interval_seconds=excluded.interval_seconds, \
+        action=excluded.action, verification_level=excluded.verification_level",
+        guild_id_i64,
+        threshold,
+        interval_seconds,
+        action_u8,
+        verification_level_u8
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Turns off anti-raid protection in the guild.
+/// Returns whether or not it was enabled in the first place.
+pub async fn remove_antiraid_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM antiraid_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets the anti-raid settings of the guild.
+/// Returns `None` if anti-raid protection isn't enabled.
+pub async fn get_antiraid_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Option<RaidSettings>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT * FROM antiraid_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    entry
+        .map(|entry| {
+            Ok(RaidSettings {
+                threshold: entry.join_threshold as u32,
+                interval: std::time::Duration::from_secs(entry.interval_seconds as u64),
+                action: (entry.action as u8).try_into()?,
+                verification_level: (entry.verification_level as u8).try_into()?,
+            })
+        })
+        .transpose()
+}
+
+pub async fn add_raid_lock_channel(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    channel_id: impl Into<ChannelId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let channel_id_i64: i64 = channel_id.into().into();
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO antiraid_lock_channels (guild_id, channel_id) VALUES (?, ?)",
+        guild_id_i64,
+        channel_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_raid_lock_channel(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    channel_id: impl Into<ChannelId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let channel_id_i64: i64 = channel_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM antiraid_lock_channels WHERE guild_id = ? AND channel_id = ?",
+        guild_id_i64,
+        channel_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets every channel that gets locked when raid mode starts.
+pub async fn get_raid_lock_channels(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<ChannelId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT channel_id FROM antiraid_lock_channels WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ChannelId::new(entry.channel_id as u64))
+        .collect())
+}
+
+/// Sets the account age gate of the guild, or turns it off if `settings` is `None`.
+pub async fn set_age_gate(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    settings: Option<&AgeGateSettings>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let min_account_age = settings.map(|settings| settings.min_age.as_secs() as i64);
+    let action_u8 = settings.map(|settings| settings.action as u8);
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, min_account_age, account_age_action) VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id) DO UPDATE SET \
+        min_account_age=excluded.min_account_age, account_age_action=excluded.account_age_action",
+        guild_id_i64,
+        min_account_age,
+        action_u8
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the account age gate of the guild.
+/// Returns `None` if the age gate is off.
+pub async fn get_age_gate(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Option<AgeGateSettings>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT min_account_age, account_age_action FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    match entry {
+        Some(entry) => match (entry.min_account_age, entry.account_age_action) {
+            (Some(min_age), Some(action)) => Ok(Some(AgeGateSettings {
+                min_age: std::time::Duration::from_secs(min_age as u64),
+                action: (action as u8).try_into()?,
+            })),
+            _ => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+pub async fn set_antinuke_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    settings: &AntinukeSettings,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let threshold = settings.threshold as i64;
+    let interval_seconds = settings.interval.as_secs() as i64;
+
+    sqlx::query!(
+        "INSERT INTO antinuke_settings (guild_id, threshold, interval_seconds) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET \
+        threshold=excluded.threshold, interval_seconds=excluded.interval_seconds",
+        guild_id_i64,
+        threshold,
+        interval_seconds
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Turns off anti-nuke protection in the guild.
+/// Returns whether or not it was enabled in the first place.
+pub async fn remove_antinuke_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM antinuke_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets the anti-nuke settings of the guild.
+/// Returns `None` if anti-nuke protection isn't enabled.
+pub async fn get_antinuke_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Option<AntinukeSettings>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT threshold, interval_seconds FROM antinuke_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.map(|entry| AntinukeSettings {
+        threshold: entry.threshold as u32,
+        interval: std::time::Duration::from_secs(entry.interval_seconds as u64),
+    }))
+}
+
+pub async fn add_antinuke_trusted_user(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO antinuke_trusted_users (guild_id, user_id) VALUES (?, ?)",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_antinuke_trusted_user(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM antinuke_trusted_users WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_antinuke_trusted_users(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<UserId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT user_id FROM antinuke_trusted_users WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| UserId::new(entry.user_id as u64))
+        .collect())
+}
+
+/// Saves a snapshot of the guild, returning the ID of the new snapshot.
+pub async fn add_snapshot(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    created_by: impl Into<UserId>,
+    created_at: Timestamp,
+    version: u32,
+    data: &str,
+) -> sqlx::Result<u64> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let created_by_i64: i64 = created_by.into().into();
+    let created_at = created_at.unix_timestamp();
+    let version = version as i64;
+
+    // Same as moderations, IDs are counted per guild
+    let id: i64 = sqlx::query!(
+        "SELECT MAX(id) AS max_id FROM snapshots WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_one(database)
+    .await?
+    .max_id
+    .unwrap_or(0)
+        + 1;
+
+    sqlx::query!(
+        "INSERT INTO snapshots (id, guild_id, created_by, created_at, version, data) \
+        VALUES (?, ?, ?, ?, ?, ?)",
+        id,
+        guild_id_i64,
+        created_by_i64,
+        created_at,
+        version,
+        data
+    )
+    .execute(database)
+    .await?;
+
+    Ok(id as u64)
+}
+
+pub async fn remove_snapshot(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id = id as i64;
+
+    let result = sqlx::query!(
+        "DELETE FROM snapshots WHERE guild_id = ? AND id = ?",
+        guild_id_i64,
+        id
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets a single snapshot of the guild along with its JSON document.
+pub async fn get_snapshot(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+) -> sqlx::Result<Option<(SnapshotInfo, String)>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id = id as i64;
+
+    let entry = sqlx::query!(
+        "SELECT * FROM snapshots WHERE guild_id = ? AND id = ?",
+        guild_id_i64,
+        id
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.map(|entry| {
+        (
+            SnapshotInfo {
+                id: entry.id as u64,
+                created_by: UserId::new(entry.created_by as u64),
+                created_at: Timestamp::from_unix_timestamp(entry.created_at)
+                    .expect("Snapshot has an invalid timestamp!"),
+                version: entry.version as u32,
+            },
+            entry.data,
+        )
+    }))
+}
+
+/// Gets the most recent snapshots of the guild, newest first. The JSON documents themselves are left out.
+pub async fn get_snapshots(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    limit: u32,
+) -> sqlx::Result<Vec<SnapshotInfo>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT id, created_by, created_at, version FROM snapshots WHERE guild_id = ? \
+        ORDER BY id DESC LIMIT ?",
+        guild_id_i64,
+        limit
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| SnapshotInfo {
+            id: entry.id as u64,
+            created_by: UserId::new(entry.created_by as u64),
+            created_at: Timestamp::from_unix_timestamp(entry.created_at)
+                .expect("Snapshot has an invalid timestamp!"),
+            version: entry.version as u32,
+        })
+        .collect())
+}
+
+/// Turns on verification in the guild, or updates its settings.
+/// The question is left alone, since it's set separately.
+pub async fn set_verification_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    settings: &VerificationSettings,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let channel_id_i64: i64 = settings.channel.into();
+    let unverified_role_id_i64: i64 = settings.unverified_role.into();
+    let verified_role_id_i64: i64 = settings.verified_role.into();
+    let challenge_u8 = settings.challenge as u8;
+    let time_limit_seconds = settings.time_limit.as_secs() as i64;
+
+    sqlx::query!(
+        "INSERT INTO verification_settings \
+        (guild_id, channel_id, unverified_role_id, verified_role_id, challenge, time_limit_seconds) \
+        VALUES (?, ?, ?, ?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET \
+        channel_id=excluded.channel_id, unverified_role_id=excluded.unverified_role_id, \
+        verified_role_id=excluded.verified_role_id, challenge=excluded.challenge, \
+        time_limit_seconds=excluded.time_limit_seconds",
+        guild_id_i64,
+        channel_id_i64,
+        unverified_role_id_i64,
+        verified_role_id_i64,
+        challenge_u8,
+        time_limit_seconds
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the question used by the question challenge.
+/// Returns whether or not verification is enabled, since there's nowhere to store the question otherwise.
+pub async fn set_verification_question(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    question: &str,
+    answer: &str,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let result = sqlx::query!(
+        "UPDATE verification_settings SET question = ?, answer = ? WHERE guild_id = ?",
+        question,
+        answer,
+        guild_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Turns off verification in the guild.
+/// Returns whether or not it was enabled in the first place.
+pub async fn remove_verification_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM verification_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets the verification settings of the guild.
+/// Returns `None` if verification isn't enabled.
+pub async fn get_verification_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Option<VerificationSettings>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT * FROM verification_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    entry
+        .map(|entry| {
+            Ok(VerificationSettings {
+                channel: ChannelId::new(entry.channel_id as u64),
+                unverified_role: RoleId::new(entry.unverified_role_id as u64),
+                verified_role: RoleId::new(entry.verified_role_id as u64),
+                challenge: (entry.challenge as u8).try_into()?,
+                time_limit: std::time::Duration::from_secs(entry.time_limit_seconds as u64),
+                question: entry.question.zip(entry.answer),
+            })
+        })
+        .transpose()
+}
+
+/// Remembers a member that has to verify by the deadline, replacing any earlier deadline they had.
+pub async fn add_pending_verification(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    deadline: i64,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO pending_verifications (guild_id, user_id, deadline) VALUES (?, ?, ?)
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET deadline=excluded.deadline",
+        guild_id_i64,
+        user_id_i64,
+        deadline
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Checks if a member still has to verify.
+pub async fn is_verification_pending(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT deadline FROM pending_verifications WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.is_some())
+}
+
+/// Stops waiting on a member to verify.
+/// Returns whether or not the member still had to verify.
+pub async fn remove_pending_verification(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM pending_verifications WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets and forgets every member whose verification deadline has passed.
+pub async fn take_due_verifications(
+    database: &sqlx::SqlitePool,
+    current_time: i64,
+) -> sqlx::Result<Vec<(GuildId, UserId)>> {
+    let entries = sqlx::query!(
+        "SELECT guild_id, user_id FROM pending_verifications WHERE deadline < ?",
+        current_time
+    )
+    .fetch_all(database)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM pending_verifications WHERE deadline < ?",
+        current_time
+    )
+    .execute(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            (
+                GuildId::new(entry.guild_id as u64),
+                UserId::new(entry.user_id as u64),
+            )
+        })
+        .collect())
+}
+
+/// Remembers the roles a member had before being jailed, so that they can be given back afterwards.
+/// Replaces whatever was left over from an earlier jail, so only the roles from this one are given back.
+pub async fn set_jailed_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    role_ids: &[RoleId],
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+    let mut transaction = database.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM jailed_roles WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(&mut transaction)
+    .await?;
+
+    for role_id in role_ids {
+        let role_id_i64: i64 = (*role_id).into();
+
+        sqlx::query!(
+            "INSERT OR IGNORE INTO jailed_roles (guild_id, user_id, role_id) VALUES (?, ?, ?)",
+            guild_id_i64,
+            user_id_i64,
+            role_id_i64
+        )
+        .execute(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Gets the roles a member had before being jailed.
+pub async fn get_jailed_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<Vec<RoleId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT role_id FROM jailed_roles WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| RoleId::new(entry.role_id as u64))
+        .collect())
+}
+
+/// Forgets the roles a member had before being jailed, once they've been given back.
+pub async fn remove_jailed_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM jailed_roles WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Binds an emoji on a message to a role, replacing whatever the emoji was bound to before.
+pub async fn add_reaction_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    reaction_role: &ReactionRole,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let channel_id_i64: i64 = reaction_role.channel_id.into();
+    let message_id_i64: i64 = reaction_role.message_id.into();
+    let role_id_i64: i64 = reaction_role.role_id.into();
+    let mode_u8 = reaction_role.mode as u8;
+    let duration = reaction_role
+        .duration
+        .map(|duration| duration.as_secs() as i64);
+
+    sqlx::query!(
+        "INSERT INTO reaction_roles (guild_id, channel_id, message_id, emoji, role_id, mode, duration) \
+        VALUES (?, ?, ?, ?, ?, ?, ?) \
+        ON CONFLICT (message_id, emoji) DO UPDATE SET \
+        role_id=excluded.role_id, mode=excluded.mode, duration=excluded.duration",
+        guild_id_i64,
+        channel_id_i64,
+        message_id_i64,
+        reaction_role.emoji,
+        role_id_i64,
+        mode_u8,
+        duration
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes a reaction role from a message.
+/// Returns whether or not the emoji was bound to a role in the first place.
+pub async fn remove_reaction_role(
+    database: &sqlx::SqlitePool,
+    message_id: impl Into<MessageId>,
+    emoji: &str,
+) -> sqlx::Result<bool> {
+    let message_id_i64: i64 = message_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM reaction_roles WHERE message_id = ? AND emoji = ?",
+        message_id_i64,
+        emoji
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn remove_message_reaction_roles(
+    database: &sqlx::SqlitePool,
+    message_id: impl Into<MessageId>,
+) -> sqlx::Result<()> {
+    let message_id_i64: i64 = message_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM reaction_roles WHERE message_id = ?",
+        message_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_channel_reaction_roles(
+    database: &sqlx::SqlitePool,
+    channel_id: impl Into<ChannelId>,
+) -> sqlx::Result<()> {
+    let channel_id_i64: i64 = channel_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM reaction_roles WHERE channel_id = ?",
+        channel_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_role_reaction_roles(
+    database: &sqlx::SqlitePool,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!("DELETE FROM reaction_roles WHERE role_id = ?", role_id_i64)
+        .execute(database)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_message_reaction_roles(
+    database: &sqlx::SqlitePool,
+    message_id: impl Into<MessageId>,
+) -> Result<Vec<ReactionRole>, Error> {
+    let message_id_i64: i64 = message_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT channel_id, message_id, emoji, role_id, mode, duration FROM reaction_roles \
+        WHERE message_id = ?",
+        message_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(ReactionRole {
+                channel_id: ChannelId::new(entry.channel_id as u64),
+                message_id: MessageId::new(entry.message_id as u64),
+                emoji: entry.emoji,
+                role_id: RoleId::new(entry.role_id as u64),
+                mode: (entry.mode as u8).try_into()?,
+                duration: entry
+                    .duration
+                    .map(|duration| std::time::Duration::from_secs(duration as u64)),
+            })
+        })
+        .collect()
+}
+
+pub async fn get_reaction_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Vec<ReactionRole>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT channel_id, message_id, emoji, role_id, mode, duration FROM reaction_roles \
+        WHERE guild_id = ? ORDER BY message_id",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(ReactionRole {
+                channel_id: ChannelId::new(entry.channel_id as u64),
+                message_id: MessageId::new(entry.message_id as u64),
+                emoji: entry.emoji,
+                role_id: RoleId::new(entry.role_id as u64),
+                mode: (entry.mode as u8).try_into()?,
+                duration: entry
+                    .duration
+                    .map(|duration| std::time::Duration::from_secs(duration as u64)),
+            })
+        })
+        .collect()
+}
+
+/// Gives a member a role until the expiry date, after which the scheduler takes it away again.
+/// Giving the same role again moves the expiry date.
+pub async fn add_temporary_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    role_id: impl Into<RoleId>,
+    expiry_date: Timestamp,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+    let expiry_date = expiry_date.unix_timestamp();
+
+    sqlx::query!(
+        "INSERT INTO temporary_roles (guild_id, user_id, role_id, expiry_date) VALUES (?, ?, ?, ?) \
+        ON CONFLICT (guild_id, user_id, role_id) DO UPDATE SET expiry_date=excluded.expiry_date",
+        guild_id_i64,
+        user_id_i64,
+        role_id_i64,
+        expiry_date
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_temporary_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM temporary_roles WHERE guild_id = ? AND user_id = ? AND role_id = ?",
+        guild_id_i64,
+        user_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets the temporary roles of the guild, or only of one member, soonest to expire first.
+pub async fn get_temporary_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: Option<UserId>,
+) -> sqlx::Result<Vec<(UserId, RoleId, Timestamp)>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: Option<i64> = user_id.map(|user_id| user_id.into());
+
+    let entries = sqlx::query!(
+        "SELECT user_id, role_id, expiry_date FROM temporary_roles \
+        WHERE guild_id = ? AND (? IS NULL OR user_id = ?) ORDER BY expiry_date",
+        guild_id_i64,
+        user_id_i64,
+        user_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            (
+                UserId::new(entry.user_id as u64),
+                RoleId::new(entry.role_id as u64),
+                Timestamp::from_unix_timestamp(entry.expiry_date)
+                    .expect("Temporary role has an invalid expiry date!"),
+            )
+        })
+        .collect())
+}
+
+/// Forgets every temporary role that has expired, returning them so that they can be taken away.
+pub async fn take_expired_temporary_roles(
+    database: &sqlx::SqlitePool,
+    current_time: i64,
+) -> sqlx::Result<Vec<(GuildId, UserId, RoleId)>> {
+    let entries = sqlx::query!(
+        "SELECT guild_id, user_id, role_id FROM temporary_roles WHERE expiry_date < ?",
+        current_time
+    )
+    .fetch_all(database)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM temporary_roles WHERE expiry_date < ?",
+        current_time
+    )
+    .execute(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            (
+                GuildId::new(entry.guild_id as u64),
+                UserId::new(entry.user_id as u64),
+                RoleId::new(entry.role_id as u64),
+            )
+        })
+        .collect())
+}
+
+/// Creates a role menu with no roles in it, returning the ID of the new menu.
+pub async fn add_role_menu(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    title: &str,
+    style: RoleMenuStyle,
+    min_roles: u32,
+    max_roles: Option<u32>,
+    required_role: Option<RoleId>,
+) -> sqlx::Result<u64> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let style_u8 = style as u8;
+    let required_role_id: Option<i64> = required_role.map(|role_id| role_id.into());
+
+    // Same as moderations, IDs are counted per guild
+    let id: i64 = sqlx::query!(
+        "SELECT MAX(id) AS max_id FROM role_menus WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_one(database)
+    .await?
+    .max_id
+    .unwrap_or(0)
+        + 1;
+
+    sqlx::query!(
+        "INSERT INTO role_menus (id, guild_id, title, style, min_roles, max_roles, required_role_id) \
+        VALUES (?, ?, ?, ?, ?, ?, ?)",
+        id,
+        guild_id_i64,
+        title,
+        style_u8,
+        min_roles,
+        max_roles,
+        required_role_id
+    )
+    .execute(database)
+    .await?;
+
+    Ok(id as u64)
+}
+
+/// Deletes a role menu along with its roles.
+/// Returns whether or not the menu existed.
+pub async fn remove_role_menu(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id = id as i64;
+
+    sqlx::query!(
+        "DELETE FROM role_menu_options WHERE guild_id = ? AND menu_id = ?",
+        guild_id_i64,
+        id
+    )
+    .execute(database)
+    .await?;
+
+    let result = sqlx::query!(
+        "DELETE FROM role_menus WHERE guild_id = ? AND id = ?",
+        guild_id_i64,
+        id
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remembers where a role menu has been posted.
+pub async fn set_role_menu_message(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+    channel_id: impl Into<ChannelId>,
+    message_id: impl Into<MessageId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id = id as i64;
+    let channel_id_i64: i64 = channel_id.into().into();
+    let message_id_i64: i64 = message_id.into().into();
+
+    sqlx::query!(
+        "UPDATE role_menus SET channel_id = ?, message_id = ? WHERE guild_id = ? AND id = ?",
+        channel_id_i64,
+        message_id_i64,
+        guild_id_i64,
+        id
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Forgets where a role menu was posted, for when its message gets deleted.
+pub async fn clear_role_menu_message(
+    database: &sqlx::SqlitePool,
+    message_id: impl Into<MessageId>,
+) -> sqlx::Result<()> {
+    let message_id_i64: i64 = message_id.into().into();
+
+    sqlx::query!(
+        "UPDATE role_menus SET channel_id = NULL, message_id = NULL WHERE message_id = ?",
+        message_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Adds a role to a role menu, or updates its label and emoji if it's already in there.
+pub async fn add_role_menu_option(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    menu_id: u64,
+    option: &RoleMenuOption,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let menu_id = menu_id as i64;
+    let role_id_i64: i64 = option.role_id.into();
+
+    // New roles go at the end of the menu
+    let position: i64 = sqlx::query!(
+        "SELECT MAX(position) AS max_position FROM role_menu_options WHERE guild_id = ? AND menu_id = ?",
+        guild_id_i64,
+        menu_id
+    )
+    .fetch_one(database)
+    .await?
+    .max_position
+    .unwrap_or(0)
+        + 1;
+
+    sqlx::query!(
+        "INSERT INTO role_menu_options (guild_id, menu_id, role_id, label, emoji, position) \
+        VALUES (?, ?, ?, ?, ?, ?) \
+        ON CONFLICT (guild_id, menu_id, role_id) DO UPDATE SET label=excluded.label, emoji=excluded.emoji",
+        guild_id_i64,
+        menu_id,
+        role_id_i64,
+        option.label,
+        option.emoji,
+        position
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Takes a role out of a role menu.
+/// Returns whether or not the role was in the menu.
+pub async fn remove_role_menu_option(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    menu_id: u64,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let menu_id = menu_id as i64;
+    let role_id_i64: i64 = role_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM role_menu_options WHERE guild_id = ? AND menu_id = ? AND role_id = ?",
+        guild_id_i64,
+        menu_id,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Takes a role out of every role menu in the guild, for when the role gets deleted.
+pub async fn remove_role_menu_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM role_menu_options WHERE guild_id = ? AND role_id = ?",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_role_menu(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+) -> Result<Option<RoleMenu>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id_i64 = id as i64;
+
+    let entry = sqlx::query!(
+        "SELECT * FROM role_menus WHERE guild_id = ? AND id = ?",
+        guild_id_i64,
+        id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    let entry = match entry {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+
+    let options = sqlx::query!(
+        "SELECT role_id, label, emoji FROM role_menu_options WHERE guild_id = ? AND menu_id = ? \
+        ORDER BY position",
+        guild_id_i64,
+        id_i64
+    )
+    .fetch_all(database)
+    .await?
+    .into_iter()
+    .map(|option| RoleMenuOption {
+        role_id: RoleId::new(option.role_id as u64),
+        label: option.label,
+        emoji: option.emoji,
+    })
+    .collect();
+
+    Ok(Some(RoleMenu {
+        id,
+        title: entry.title,
+        style: (entry.style as u8).try_into()?,
+        min_roles: entry.min_roles as u32,
+        max_roles: entry.max_roles.map(|max_roles| max_roles as u32),
+        required_role: entry
+            .required_role_id
+            .map(|role_id| RoleId::new(role_id as u64)),
+        message: entry
+            .channel_id
+            .zip(entry.message_id)
+            .map(|(channel_id, message_id)| {
+                (
+                    ChannelId::new(channel_id as u64),
+                    MessageId::new(message_id as u64),
+                )
+            }),
+        options,
+    }))
+}
+
+/// Gets the IDs of every role menu in the guild.
+pub async fn get_role_menu_ids(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<u64>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT id FROM role_menus WHERE guild_id = ? ORDER BY id",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries.into_iter().map(|entry| entry.id as u64).collect())
+}
+
+/// Makes a role an autorole for humans or bots.
+pub async fn add_autorole(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+    bots: bool,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO autoroles (guild_id, role_id, bots) VALUES (?, ?, ?) ON CONFLICT DO NOTHING",
+        guild_id_i64,
+        role_id_i64,
+        bots
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Replaces every autorole for humans or bots.
+pub async fn set_autoroles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    bots: bool,
+    role_ids: &[RoleId],
+) -> sqlx::Result<()> {
+    let guild_id: GuildId = guild_id.into();
+    let guild_id_i64: i64 = guild_id.into();
+
+    sqlx::query!(
+        "DELETE FROM autoroles WHERE guild_id = ? AND bots = ?",
+        guild_id_i64,
+        bots
+    )
+    .execute(database)
+    .await?;
+
+    for role_id in role_ids {
+        add_autorole(database, guild_id, *role_id, bots).await?;
+    }
+
+    Ok(())
+}
+
+/// Stops a role from being an autorole for humans, bots, or both if `bots` is `None`.
+/// Returns whether or not the role was an autorole.
+pub async fn remove_autorole(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+    bots: Option<bool>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM autoroles WHERE guild_id = ? AND role_id = ? AND (? IS NULL OR bots = ?)",
+        guild_id_i64,
+        role_id_i64,
+        bots,
+        bots
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_autoroles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    bots: bool,
+) -> sqlx::Result<Vec<RoleId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT role_id FROM autoroles WHERE guild_id = ? AND bots = ?",
+        guild_id_i64,
+        bots
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| RoleId::new(entry.role_id as u64))
+        .collect())
+}
+
+/// Sets how long humans wait for their autoroles. `None` gives them right away.
+pub async fn set_autorole_delay(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    delay: Option<std::time::Duration>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let delay_seconds = delay.map(|delay| delay.as_secs() as i64);
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, autorole_delay) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET autorole_delay=excluded.autorole_delay",
+        guild_id_i64,
+        delay_seconds
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_autorole_after_screening(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    enabled: bool,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, autorole_after_screening) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET autorole_after_screening=excluded.autorole_after_screening",
+        guild_id_i64,
+        enabled
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_autorole_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<AutoroleSettings> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT autorole_delay, autorole_after_screening FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(
+        entry.map_or_else(AutoroleSettings::default, |some| AutoroleSettings {
+            delay: some
+                .autorole_delay
+                .map(|delay| std::time::Duration::from_secs(delay as u64)),
+            after_screening: some.autorole_after_screening,
+        }),
+    )
+}
+
+/// Remembers a member that is waiting for their autoroles.
+/// An `assign_date` of `None` means they're waiting to pass membership screening or verification.
+pub async fn add_pending_autoroles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    assign_date: Option<i64>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO pending_autoroles (guild_id, user_id, assign_date) VALUES (?, ?, ?)
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET assign_date=excluded.assign_date",
+        guild_id_i64,
+        user_id_i64,
+        assign_date
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Stops waiting on a member to pass membership screening or verification.
+/// Returns whether or not the member was waiting on either.
+pub async fn take_waiting_autoroles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM pending_autoroles WHERE guild_id = ? AND user_id = ? AND assign_date IS NULL",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn remove_pending_autoroles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM pending_autoroles WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets and forgets every member whose autorole delay has passed.
+pub async fn take_due_autoroles(
+    database: &sqlx::SqlitePool,
+    current_time: i64,
+) -> sqlx::Result<Vec<(GuildId, UserId)>> {
+    let entries = sqlx::query!(
+        "SELECT guild_id, user_id FROM pending_autoroles WHERE assign_date < ?",
+        current_time
+    )
+    .fetch_all(database)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM pending_autoroles WHERE assign_date < ?",
+        current_time
+    )
+    .execute(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            (
+                GuildId::new(entry.guild_id as u64),
+                UserId::new(entry.user_id as u64),
+            )
+        })
+        .collect())
+}
+
+/// Sets the role booster roles are put under. `None` turns booster roles off.
+pub async fn set_booster_role_anchor(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: Option<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: Option<i64> = role_id.map(|role_id| role_id.into());
+
+    sqlx::query!(
+        "INSERT INTO guild_settings (guild_id, booster_role_anchor_id) VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET booster_role_anchor_id=excluded.booster_role_anchor_id",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_booster_role_anchor(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Option<RoleId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT booster_role_anchor_id FROM guild_settings WHERE guild_id=?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.and_then(|some| {
+        some.booster_role_anchor_id
+            .map(|unwrapped| RoleId::new(unwrapped as u64))
+    }))
+}
+
+pub async fn set_booster_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO booster_roles (guild_id, user_id, role_id) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET role_id=excluded.role_id",
+        guild_id_i64,
+        user_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_booster_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<Option<RoleId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT role_id FROM booster_roles WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.map(|entry| RoleId::new(entry.role_id as u64)))
+}
+
+/// Forgets a booster role that was deleted.
+pub async fn remove_booster_role_by_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM booster_roles WHERE guild_id = ? AND role_id = ?",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_booster_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<(UserId, RoleId)>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT user_id, role_id FROM booster_roles WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            (
+                UserId::new(entry.user_id as u64),
+                RoleId::new(entry.role_id as u64),
+            )
+        })
+        .collect())
+}
+
+pub async fn set_leveling_enabled(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    enabled: bool,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, enabled) VALUES (?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET enabled=excluded.enabled",
+        guild_id_i64,
+        enabled
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_xp_gain(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    min_xp: u32,
+    max_xp: u32,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, min_xp, max_xp) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET min_xp=excluded.min_xp, max_xp=excluded.max_xp",
+        guild_id_i64,
+        min_xp,
+        max_xp
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_xp_cooldown(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    cooldown: std::time::Duration,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let cooldown_seconds = cooldown.as_secs() as i64;
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, cooldown_seconds) VALUES (?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET cooldown_seconds=excluded.cooldown_seconds",
+        guild_id_i64,
+        cooldown_seconds
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the level curve. The expression is only kept for custom curves.
+pub async fn set_level_curve(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    curve: LevelCurve,
+    expression: Option<&str>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let curve_u8 = curve as u8;
+    let expression = expression.filter(|_| curve == LevelCurve::Custom);
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, curve, curve_expression) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET curve=excluded.curve, curve_expression=excluded.curve_expression",
+        guild_id_i64,
+        curve_u8,
+        expression
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_leveling_settings(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<LevelingSettings, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT * FROM leveling_settings WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    match entry {
+        Some(entry) => Ok(LevelingSettings {
+            enabled: entry.enabled,
+            min_xp: entry.min_xp as u32,
+            max_xp: entry.max_xp as u32,
+            cooldown: std::time::Duration::from_secs(entry.cooldown_seconds as u64),
+            curve: (entry.curve as u8).try_into()?,
+            expression: entry.curve_expression,
+            reward_mode: (entry.reward_mode as u8).try_into()?,
+            levelup_channel: entry
+                .levelup_channel_id
+                .map(|channel_id| ChannelId::new(channel_id as u64)),
+            levelup_message: entry.levelup_message,
+            card_theme: RankCardTheme {
+                background: entry.card_background as u32,
+                accent: entry.card_accent as u32,
+                text: entry.card_text as u32,
+            },
+        }),
+        None => Ok(LevelingSettings::default()),
+    }
+}
+
+pub async fn set_xp_multiplier(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    target_id: u64,
+    kind: XpMultiplierKind,
+    multiplier: f64,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let target_id_i64 = target_id as i64;
+    let kind_u8 = kind as u8;
+
+    sqlx::query!(
+        "INSERT INTO xp_multipliers (guild_id, target_id, kind, multiplier) VALUES (?, ?, ?, ?) \
+        ON CONFLICT (guild_id, target_id) DO UPDATE SET kind=excluded.kind, multiplier=excluded.multiplier",
+        guild_id_i64,
+        target_id_i64,
+        kind_u8,
+        multiplier
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns whether or not the role or channel had a multiplier.
+pub async fn remove_xp_multiplier(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    target_id: u64,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let target_id_i64 = target_id as i64;
+
+    let result = sqlx::query!(
+        "DELETE FROM xp_multipliers WHERE guild_id = ? AND target_id = ?",
+        guild_id_i64,
+        target_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_xp_multipliers(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Vec<XpMultiplier>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT target_id, kind, multiplier FROM xp_multipliers WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(XpMultiplier {
+                target_id: entry.target_id as u64,
+                kind: (entry.kind as u8).try_into()?,
+                multiplier: entry.multiplier,
+            })
+        })
+        .collect()
+}
+
+/// Gives a member XP, returning how much they have now.
+pub async fn add_xp(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    amount: u64,
+) -> sqlx::Result<u64> {
+    let guild_id: GuildId = guild_id.into();
+    let user_id: UserId = user_id.into();
+    let guild_id_i64: i64 = guild_id.into();
+    let user_id_i64: i64 = user_id.into();
+    let amount_i64 = amount as i64;
+
+    sqlx::query!(
+        "INSERT INTO member_xp (guild_id, user_id, xp) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET xp=xp + excluded.xp",
+        guild_id_i64,
+        user_id_i64,
+        amount_i64
+    )
+    .execute(database)
+    .await?;
+
+    get_xp(database, guild_id, user_id).await
+}
+
+pub async fn get_xp(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<u64> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT xp FROM member_xp WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .fetch_optional(database)
+    .await?;
+
+    Ok(entry.map_or(0, |entry| entry.xp as u64))
+}
+
+/// Gets where a member is on the leaderboard, starting from 1.
+/// Returns `None` if they haven't gained any XP yet.
+pub async fn get_xp_rank(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<Option<u64>> {
+    let guild_id: GuildId = guild_id.into();
+    let guild_id_i64: i64 = guild_id.into();
+
+    let xp = get_xp(database, guild_id, user_id).await? as i64;
+    if xp == 0 {
+        return Ok(None);
+    }
+
+    let entry = sqlx::query!(
+        "SELECT COUNT(*) AS ahead FROM member_xp WHERE guild_id = ? AND xp > ?",
+        guild_id_i64,
+        xp
+    )
+    .fetch_one(database)
+    .await?;
+
+    Ok(Some(entry.ahead as u64 + 1))
+}
+
+/// Gets how many members are on the leaderboard.
+pub async fn get_leaderboard_count(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<usize> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entry = sqlx::query!(
+        "SELECT COUNT(*) AS count FROM member_xp WHERE guild_id = ? AND xp > 0",
+        guild_id_i64
+    )
+    .fetch_one(database)
+    .await?;
+
+    Ok(entry.count as usize)
+}
+
+/// Gets a page of the leaderboard, most XP first. Pages start from 1.
+pub async fn get_leaderboard_page(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    page: usize,
+    page_size: usize,
+) -> Result<Vec<(UserId, u64)>, Error> {
+    let guild_id: GuildId = guild_id.into();
+    let guild_id_i64: i64 = guild_id.into();
+    let count = get_leaderboard_count(database, guild_id).await?;
+    let max_page = (count + page_size - 1) / page_size;
+
+    if page == 0 || (page > max_page && page > 1) {
+        return Err(Error::PageOutOfBounds(page, max_page.max(1)));
+    }
+
+    let page_size_i64 = page_size as i64;
+    let offset = ((page - 1) * page_size) as i64;
+
+    let entries = sqlx::query!(
+        "SELECT user_id, xp FROM member_xp WHERE guild_id = ? AND xp > 0 \
+        ORDER BY xp DESC, user_id LIMIT ? OFFSET ?",
+        guild_id_i64,
+        page_size_i64,
+        offset
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| (UserId::new(entry.user_id as u64), entry.xp as u64))
+        .collect())
+}
+
+pub async fn set_reward_mode(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    mode: RewardMode,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let mode_u8 = mode as u8;
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, reward_mode) VALUES (?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET reward_mode=excluded.reward_mode",
+        guild_id_i64,
+        mode_u8
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Sets where level ups are announced. A channel of `None` turns announcements off.
+pub async fn set_levelup_announcements(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    channel_id: Option<ChannelId>,
+    message: Option<&str>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let channel_id_i64 = channel_id.map(|channel_id| channel_id.get() as i64);
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, levelup_channel_id, levelup_message) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET levelup_channel_id=excluded.levelup_channel_id, levelup_message=excluded.levelup_message",
+        guild_id_i64,
+        channel_id_i64,
+        message
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Adds a level reward, moving the role to the new level if it was already a reward.
+pub async fn set_level_reward(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+    level: u32,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO level_rewards (guild_id, role_id, level) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id, role_id) DO UPDATE SET level=excluded.level",
+        guild_id_i64,
+        role_id_i64,
+        level
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns whether or not the role was a level reward.
+pub async fn remove_level_reward(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM level_rewards WHERE guild_id = ? AND role_id = ?",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Gets the level rewards of a guild, lowest level first.
+pub async fn get_level_rewards(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<LevelReward>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT role_id, level FROM level_rewards WHERE guild_id = ? ORDER BY level",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| LevelReward {
+            level: entry.level as u32,
+            role_id: RoleId::new(entry.role_id as u64),
+        })
+        .collect())
+}
+
+/// Gets the XP of every member of a guild that has any.
+pub async fn get_all_xp(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<HashMap<UserId, u64>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT user_id, xp FROM member_xp WHERE guild_id = ? AND xp > 0",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| (UserId::new(entry.user_id as u64), entry.xp as u64))
+        .collect())
+}
+
+pub async fn set_xp(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    xp: u64,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+    let xp_i64 = xp as i64;
+
+    sqlx::query!(
+        "INSERT INTO member_xp (guild_id, user_id, xp) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET xp=excluded.xp",
+        guild_id_i64,
+        user_id_i64,
+        xp_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the XP of many members at once. If `replace` is set, everyone else's XP is cleared first.
+/// Either every member is imported or none are.
+pub async fn import_xp(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    entries: &[(UserId, u64)],
+    replace: bool,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let mut transaction = database.begin().await?;
+
+    if replace {
+        sqlx::query!("DELETE FROM member_xp WHERE guild_id = ?", guild_id_i64)
+            .execute(&mut transaction)
+            .await?;
+    }
+
+    for (user_id, xp) in entries {
+        let user_id_i64: i64 = (*user_id).into();
+        let xp_i64 = *xp as i64;
+
+        sqlx::query!(
+            "INSERT INTO member_xp (guild_id, user_id, xp) VALUES (?, ?, ?) \
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET xp=excluded.xp",
+            guild_id_i64,
+            user_id_i64,
+            xp_i64
+        )
+        .execute(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Clears the XP of every member of a guild, returning how many members were cleared.
+pub async fn reset_xp(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<u64> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let result = sqlx::query!("DELETE FROM member_xp WHERE guild_id = ?", guild_id_i64)
+        .execute(database)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn set_rank_card_theme(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    theme: RankCardTheme,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO leveling_settings (guild_id, card_background, card_accent, card_text) VALUES (?, ?, ?, ?) \
+        ON CONFLICT (guild_id) DO UPDATE SET card_background=excluded.card_background, card_accent=excluded.card_accent, card_text=excluded.card_text",
+        guild_id_i64,
+        theme.background,
+        theme.accent,
+        theme.text
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Remembers when a member was last active. `last_active` is a Unix timestamp.
+pub async fn set_member_activity(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+    last_active: i64,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "INSERT INTO member_activity (guild_id, user_id, last_active) VALUES (?, ?, ?) \
+        ON CONFLICT (guild_id, user_id) DO UPDATE SET last_active=excluded.last_active",
+        guild_id_i64,
+        user_id_i64,
+        last_active
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove_member_activity(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    user_id: impl Into<UserId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let user_id_i64: i64 = user_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM member_activity WHERE guild_id = ? AND user_id = ?",
+        guild_id_i64,
+        user_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets when every member of a guild was last active, as Unix timestamps.
+pub async fn get_member_activity(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<HashMap<UserId, i64>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT user_id, last_active FROM member_activity WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| (UserId::new(entry.user_id as u64), entry.last_active))
+        .collect())
+}
+
+/// Adds a prune policy, returning its ID.
+pub async fn add_prune_policy(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    inactive_for: std::time::Duration,
+    action: PruneAction,
+    role_id: Option<RoleId>,
+) -> sqlx::Result<u64> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let inactive_seconds = inactive_for.as_secs() as i64;
+    let action_u8 = action as u8;
+    let role_id_i64: Option<i64> = role_id.map(|role_id| role_id.into());
+    let current_time = Timestamp::now().unix_timestamp();
+
+    // Same as moderations, IDs are counted per guild
+    let id: i64 = sqlx::query!(
+        "SELECT MAX(id) AS max_id FROM prune_policies WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_one(database)
+    .await?
+    .max_id
+    .unwrap_or(0)
+        + 1;
+
+    sqlx::query!(
+        "INSERT INTO prune_policies (guild_id, id, inactive_seconds, action, role_id, created_at, last_run) \
+        VALUES (?, ?, ?, ?, ?, ?, ?)",
+        guild_id_i64,
+        id,
+        inactive_seconds,
+        action_u8,
+        role_id_i64,
+        current_time,
+        current_time
+    )
+    .execute(database)
+    .await?;
+
+    Ok(id as u64)
+}
+
+/// Returns whether or not the policy existed.
+pub async fn remove_prune_policy(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    id: u64,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let id_i64 = id as i64;
+
+    let result = sqlx::query!(
+        "DELETE FROM prune_policies WHERE guild_id = ? AND id = ?",
+        guild_id_i64,
+        id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes every prune policy that adds or removes a role, for when the role is deleted.
+pub async fn remove_prune_policies_by_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "DELETE FROM prune_policies WHERE guild_id = ? AND role_id = ?",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_prune_policies(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> Result<Vec<PrunePolicy>, Error> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT id, inactive_seconds, action, role_id, created_at FROM prune_policies \
+        WHERE guild_id = ? ORDER BY id",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(PrunePolicy {
+                id: entry.id as u64,
+                inactive_for: std::time::Duration::from_secs(entry.inactive_seconds as u64),
+                action: (entry.action as u8).try_into()?,
+                role_id: entry.role_id.map(|role_id| RoleId::new(role_id as u64)),
+                created_at: Timestamp::from_unix_timestamp(entry.created_at)
+                    .expect("Failed to convert Unix timestamp!"),
+            })
+        })
+        .collect()
+}
+
+/// Gets every guild with a policy that hasn't been run since `threshold`, marking them all as run at `current_time`.
+pub async fn take_due_prune_guilds(
+    database: &sqlx::SqlitePool,
+    threshold: i64,
+    current_time: i64,
+) -> sqlx::Result<Vec<GuildId>> {
+    let entries = sqlx::query!(
+        "SELECT DISTINCT guild_id FROM prune_policies WHERE last_run <= ?",
+        threshold
+    )
+    .fetch_all(database)
+    .await?;
+
+    // Every policy of the guild is run together, so they all count as run
+    sqlx::query!(
+        "UPDATE prune_policies SET last_run = ? \
+        WHERE guild_id IN (SELECT guild_id FROM prune_policies WHERE last_run <= ?)",
+        current_time,
+        threshold
+    )
+    .execute(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| GuildId::new(entry.guild_id as u64))
+        .collect())
+}
+
+pub async fn add_prune_exempt_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<()> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    sqlx::query!(
+        "INSERT OR IGNORE INTO prune_exempt_roles (guild_id, role_id) VALUES (?, ?)",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns whether or not the role was exempt.
+pub async fn remove_prune_exempt_role(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+    role_id: impl Into<RoleId>,
+) -> sqlx::Result<bool> {
+    let guild_id_i64: i64 = guild_id.into().into();
+    let role_id_i64: i64 = role_id.into().into();
+
+    let result = sqlx::query!(
+        "DELETE FROM prune_exempt_roles WHERE guild_id = ? AND role_id = ?",
+        guild_id_i64,
+        role_id_i64
+    )
+    .execute(database)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_prune_exempt_roles(
+    database: &sqlx::SqlitePool,
+    guild_id: impl Into<GuildId>,
+) -> sqlx::Result<Vec<RoleId>> {
+    let guild_id_i64: i64 = guild_id.into().into();
+
+    let entries = sqlx::query!(
+        "SELECT role_id FROM prune_exempt_roles WHERE guild_id = ?",
+        guild_id_i64
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| RoleId::new(entry.role_id as u64))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_database() -> sqlx::SqlitePool {
+        // In-memory databases only live as long as their connection, so the pool has to keep exactly one around
+        let database = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to open the test database!");
+
+        sqlx::migrate!("./../migrations")
+            .run(&database)
+            .await
+            .expect("Failed to run migrations!");
+
+        database
+    }
+
+    async fn count_expiring(
+        database: &sqlx::SqlitePool,
+        moderation_type: ModerationType,
+        before: i64,
+    ) -> usize {
+        get_expired_moderations(database, before)
+            .await
+            .expect("Failed to get expired moderations!")
+            .into_iter()
+            .filter(|(_, _, expired_type)| *expired_type == moderation_type)
+            .count()
+    }
+
+    async fn count_active(database: &sqlx::SqlitePool, moderation_type: ModerationType) -> usize {
+        get_modlog_page(database, GuildId::new(1), UserId::new(2), 1, 25)
+            .await
+            .expect("Failed to get modlogs!")
+            .into_iter()
+            .filter(|entry| entry.moderation_type == moderation_type && entry.active)
+            .count()
+    }
+
+    fn timestamp(unix_timestamp: i64) -> Timestamp {
+        Timestamp::from_unix_timestamp(unix_timestamp).expect("Invalid timestamp!")
+    }
+
+    async fn jail(database: &sqlx::SqlitePool, expiry_date: i64) {
+        add_moderation(
+            database,
+            GuildId::new(1),
+            UserId::new(2),
+            UserId::new(3),
+            ModerationType::Jail,
+            timestamp(0),
+            Some(timestamp(expiry_date)),
+            None,
+        )
+        .await
+        .expect("Failed to jail!");
+    }
+
+    #[tokio::test]
+    async fn unjail_then_rejail_is_not_cut_short() {
+        let database = test_database().await;
+
+        jail(&database, 1_000).await;
+
+        clear_moderations(
+            &database,
+            GuildId::new(1),
+            UserId::new(2),
+            ModerationType::Jail,
+        )
+        .await
+        .expect("Failed to clear jails!");
+        add_moderation(
+            &database,
+            GuildId::new(1),
+            UserId::new(2),
+            UserId::new(3),
+            ModerationType::Unjail,
+            timestamp(500),
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to unjail!");
+
+        jail(&database, 2_000).await;
+
+        // The first jail's deadline passing must not release the member from the second one
+        assert_eq!(
+            count_expiring(&database, ModerationType::Jail, 1_001).await,
+            0
+        );
+        assert_eq!(
+            count_expiring(&database, ModerationType::Jail, 2_001).await,
+            1
+        );
+        assert_eq!(count_active(&database, ModerationType::Unjail).await, 0);
+    }
+
+    #[tokio::test]
+    async fn rejail_replaces_previous_jail() {
+        let database = test_database().await;
+
+        jail(&database, 1_000).await;
+        jail(&database, 2_000).await;
+
+        assert_eq!(count_active(&database, ModerationType::Jail).await, 1);
+        assert_eq!(
+            count_expiring(&database, ModerationType::Jail, 1_001).await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn rejoining_replaces_the_verification_deadline() {
+        let database = test_database().await;
+        let (guild_id, user_id) = (GuildId::new(1), UserId::new(2));
+
+        add_pending_verification(&database, guild_id, user_id, 1_000)
+            .await
+            .expect("Failed to add pending verification!");
+        add_pending_verification(&database, guild_id, user_id, 2_000)
+            .await
+            .expect("Failed to add pending verification!");
+
+        // The first join's deadline passing must not kick the member
+        assert!(take_due_verifications(&database, 1_001)
+            .await
+            .expect("Failed to get due verifications!")
+            .is_empty());
+        assert_eq!(
+            take_due_verifications(&database, 2_001)
+                .await
+                .expect("Failed to get due verifications!"),
+            vec![(guild_id, user_id)]
+        );
+        assert!(!is_verification_pending(&database, guild_id, user_id)
+            .await
+            .expect("Failed to check pending verification!"));
+    }
+}
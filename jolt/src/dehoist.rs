@@ -0,0 +1,147 @@
+/*
+Automatic dehoisting
+
+Discord sorts the member list by name, so names starting with punctuation like `!` or `.` (or with invisible
+characters) end up at the top of it. When dehoisting is on, those characters are stripped from the start of display
+names whenever members join or change their name, and `dehoist all` does the same for everyone already in the server.
+*/
+
+use poise::serenity_prelude::{
+    self, CreateEmbed, EditMember, GuildId, GuildMemberUpdateEvent, Member, UserId,
+};
+
+use crate::automod::normalize;
+use crate::colors;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+/// The nickname given to members whose whole name is made of hoisting characters.
+pub const FALLBACK_NAME: &str = "Dehoisted";
+
+const DEHOIST_REASON: &str = "Dehoisting";
+
+/// Whether a character puts names that start with it at the top of the member list.
+fn is_hoisting(c: char) -> bool {
+    // Everything below '0' in ascii: ! " # $ % & ' ( ) * + , - . /
+    (c.is_ascii_punctuation() && c < '0')
+        || c.is_whitespace()
+        || normalize::is_invisible(c)
+        // Hangul fillers look like spaces but aren't treated as whitespace
+        || matches!(c as u32, 0x115F | 0x1160 | 0x3164 | 0xFFA0)
+}
+
+/// Gets the dehoisted version of a name.
+/// Returns `None` if the name isn't hoisted in the first place.
+pub fn dehoisted(name: &str) -> Option<String> {
+    if !name.starts_with(is_hoisting) {
+        return None;
+    }
+
+    let trimmed = name.trim_start_matches(is_hoisting);
+    Some(if trimmed.is_empty() {
+        String::from(FALLBACK_NAME)
+    } else {
+        trimmed.to_string()
+    })
+}
+
+/// Dehoists a single member if their display name is hoisted, returning their new nickname.
+pub async fn dehoist_member(
+    cache_http: impl serenity_prelude::CacheHttp,
+    guild_id: GuildId,
+    user_id: UserId,
+    display_name: &str,
+) -> Result<Option<String>, Error> {
+    let nickname = match dehoisted(display_name) {
+        Some(nickname) => nickname,
+        None => return Ok(None),
+    };
+
+    guild_id
+        .edit_member(
+            cache_http,
+            user_id,
+            EditMember::default()
+                .nickname(&nickname)
+                .audit_log_reason(DEHOIST_REASON),
+        )
+        .await?;
+
+    Ok(Some(nickname))
+}
+
+async fn dehoist_and_log(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+    display_name: &str,
+) -> Result<(), Error> {
+    if !database::get_dehoist(&data.database, guild_id).await? {
+        return Ok(());
+    }
+
+    // Members above the bot (like the owner) can't be renamed, which isn't worth erroring over
+    let nickname = match dehoist_member(ctx, guild_id, user_id, display_name).await {
+        Ok(Some(nickname)) => nickname,
+        Ok(None) => return Ok(()),
+        Err(error) => {
+            println!(
+                "Failed to dehoist user {} in {}: {}",
+                user_id, guild_id, error
+            );
+            return Ok(());
+        }
+    };
+
+    messages::send_log(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("DEHOIST")
+            .description(format!("User <@{}> has been dehoisted", user_id))
+            .field("Before:", display_name, true)
+            .field("After:", nickname, true),
+    )
+    .await
+}
+
+pub async fn member_join(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<(), Error> {
+    if member.user.bot {
+        return Ok(());
+    }
+
+    dehoist_and_log(
+        ctx,
+        data,
+        member.guild_id,
+        member.user.id,
+        member.display_name(),
+    )
+    .await
+}
+
+pub async fn member_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    event: &GuildMemberUpdateEvent,
+) -> Result<(), Error> {
+    if event.user.bot {
+        return Ok(());
+    }
+
+    let display_name = event
+        .nick
+        .as_deref()
+        .or(event.user.global_name.as_deref())
+        .unwrap_or(&event.user.name);
+
+    dehoist_and_log(ctx, data, event.guild_id, event.user.id, display_name).await
+}
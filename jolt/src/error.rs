@@ -1,11 +1,35 @@
+use crate::antinuke::error as antinuke_error;
+use crate::antiraid::error as antiraid_error;
+use crate::automod::error as automod_error;
+use crate::autoroles::error as autorole_error;
+use crate::backup::error as backup_error;
+use crate::boosterroles::error as boosterrole_error;
 use crate::commands::configuration::error as configuration_error;
 use crate::commands::moderation::error as moderation_error;
+use crate::leveling::error as leveling_error;
+use crate::prune::error as prune_error;
+use crate::reactionroles::error as reactionrole_error;
+use crate::rolemenus::error as rolemenu_error;
+use crate::temproles::error as temprole_error;
+use crate::verification::error as verification_error;
 
+pub use antinuke_error::AntinukeError;
+pub use antiraid_error::AntiraidError;
+pub use automod_error::AutomodError;
+pub use autorole_error::AutoroleError;
+pub use backup_error::BackupError;
+pub use boosterrole_error::BoosterRoleError;
 pub use configuration_error::ConfigurationError;
+pub use leveling_error::LevelingError;
 pub use moderation_error::ModerationError;
 pub use poise::serenity_prelude::prelude::SerenityError;
 pub use poise::FrameworkError;
+pub use prune_error::PruneError;
+pub use reactionrole_error::ReactionRoleError;
+pub use rolemenu_error::RoleMenuError;
 pub use sqlx::error::Error as SqlxError;
+pub use temprole_error::TempRoleError;
+pub use verification_error::VerificationError;
 
 /// General error struct for all variants in the program
 #[derive(thiserror::Error, Debug)]
@@ -21,6 +45,14 @@ pub enum Error {
     /// First value is the requested page, second value is the maximum page.
     #[error("Attempted to access page {0} when maximum page is {1}")]
     PageOutOfBounds(usize, usize),
+    /// An error representing a role that is not below the highest role of the member trying to hand it out.
+    /// Contains one value, which is the ID of the role.
+    #[error("Role <@&{0}> is not below your highest role!")]
+    RoleAboveMember(u64),
+    /// An error representing a role that is not below Jolt's highest role, so Jolt can't hand it out.
+    /// Contains one value, which is the ID of the role.
+    #[error("Role <@&{0}> is not below Jolt's highest role!")]
+    RoleAboveBot(u64),
     /// Errors relating to the `serenity` crate (re-exported by poise).
     #[error(transparent)]
     SerenityError(#[from] SerenityError),
@@ -33,4 +65,40 @@ pub enum Error {
     /// Errors related to moderation command problems
     #[error(transparent)]
     ModerationError(#[from] ModerationError),
+    /// Errors related to automod configuration
+    #[error(transparent)]
+    AutomodError(#[from] AutomodError),
+    /// Errors related to anti-raid protection
+    #[error(transparent)]
+    AntiraidError(#[from] AntiraidError),
+    /// Errors related to anti-nuke protection
+    #[error(transparent)]
+    AntinukeError(#[from] AntinukeError),
+    /// Errors related to server backups
+    #[error(transparent)]
+    BackupError(#[from] BackupError),
+    /// Errors related to member verification
+    #[error(transparent)]
+    VerificationError(#[from] VerificationError),
+    /// Errors related to reaction roles
+    #[error(transparent)]
+    ReactionRoleError(#[from] ReactionRoleError),
+    /// Errors related to role menus
+    #[error(transparent)]
+    RoleMenuError(#[from] RoleMenuError),
+    /// Errors related to autoroles
+    #[error(transparent)]
+    AutoroleError(#[from] AutoroleError),
+    /// Errors related to temporary roles
+    #[error(transparent)]
+    TempRoleError(#[from] TempRoleError),
+    /// Errors related to booster roles
+    #[error(transparent)]
+    BoosterRoleError(#[from] BoosterRoleError),
+    /// Errors related to XP and leveling
+    #[error(transparent)]
+    LevelingError(#[from] LevelingError),
+    /// Errors related to inactivity pruning
+    #[error(transparent)]
+    PruneError(#[from] PruneError),
 }
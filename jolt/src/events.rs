@@ -0,0 +1,317 @@
+/*
+Gateway events
+
+Anything that isn't a command but still needs to react to what's happening in a server comes through here.
+This file should stay as a dispatcher; the actual handling belongs to whatever module the event is for.
+*/
+
+use poise::serenity_prelude::{self, FullEvent};
+
+use crate::antinuke;
+use crate::antiraid;
+use crate::automod;
+use crate::autoroles;
+use crate::boosterroles;
+use crate::dehoist;
+use crate::leveling;
+use crate::prune;
+use crate::reactionroles;
+use crate::rolemenus;
+use crate::serverlog;
+use crate::verification;
+
+pub async fn event_handler(
+    ctx: &serenity_prelude::Context,
+    event: &FullEvent,
+    _framework: poise::FrameworkContext<'_, crate::Data, crate::DynError>,
+    data: &crate::Data,
+) -> Result<(), crate::DynError> {
+    match event {
+        FullEvent::GuildCreate { guild, .. } => {
+            serverlog::snapshot_emojis(data, guild.id, &guild.emojis).await;
+        }
+
+        FullEvent::Message { new_message } => {
+            if let Some(guild_id) = new_message.guild_id.filter(|_| !new_message.author.bot) {
+                log_error(
+                    "prune",
+                    prune::record_activity(data, guild_id, new_message.author.id).await,
+                );
+            }
+
+            // Messages automod deleted don't earn XP
+            let deleted = log_error(
+                "automod",
+                automod::handle_message(ctx, data, new_message).await,
+            )
+            .unwrap_or(false);
+            if !deleted {
+                log_error(
+                    "leveling",
+                    leveling::handle_message(ctx, data, new_message).await,
+                );
+            }
+        }
+        FullEvent::MessageDelete {
+            channel_id,
+            deleted_message_id,
+            ..
+        } => {
+            log_error(
+                "ghostping",
+                automod::ghostping::message_delete(ctx, data, *channel_id, *deleted_message_id)
+                    .await,
+            );
+            log_error(
+                "reactionroles",
+                reactionroles::message_delete(data, *deleted_message_id).await,
+            );
+            log_error(
+                "rolemenus",
+                rolemenus::message_delete(data, *deleted_message_id).await,
+            );
+        }
+        FullEvent::MessageDeleteBulk {
+            multiple_deleted_messages_ids,
+            ..
+        } => {
+            for message_id in multiple_deleted_messages_ids {
+                log_error(
+                    "reactionroles",
+                    reactionroles::message_delete(data, *message_id).await,
+                );
+                log_error(
+                    "rolemenus",
+                    rolemenus::message_delete(data, *message_id).await,
+                );
+            }
+        }
+        FullEvent::MessageUpdate { event, .. } => {
+            log_error(
+                "ghostping",
+                automod::ghostping::message_update(ctx, data, event).await,
+            );
+        }
+        FullEvent::GuildMemberAddition { new_member } => {
+            log_error(
+                "antiraid",
+                antiraid::member_join(ctx, data, new_member).await,
+            );
+            log_error(
+                "verification",
+                verification::member_join(ctx, data, new_member).await,
+            );
+            // Dehoisting would only undo whatever the name filter just did
+            let renamed = log_error(
+                "names",
+                automod::names::member_join(ctx, data, new_member).await,
+            )
+            .unwrap_or(false);
+            if !renamed {
+                log_error("dehoist", dehoist::member_join(ctx, data, new_member).await);
+            }
+            log_error(
+                "autoroles",
+                autoroles::member_join(ctx, data, new_member).await,
+            );
+        }
+        FullEvent::GuildMemberUpdate {
+            old_if_available,
+            event,
+            ..
+        } => {
+            let renamed = log_error(
+                "names",
+                automod::names::member_update(ctx, data, old_if_available.as_ref(), event).await,
+            )
+            .unwrap_or(false);
+            if !renamed {
+                log_error("dehoist", dehoist::member_update(ctx, data, event).await);
+            }
+            log_error(
+                "autoroles",
+                autoroles::member_update(ctx, data, event).await,
+            );
+            log_error(
+                "boosterroles",
+                boosterroles::member_update(ctx, data, event).await,
+            );
+        }
+        FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+            log_error(
+                "verification",
+                verification::member_remove(data, *guild_id, user.id).await,
+            );
+            log_error(
+                "autoroles",
+                autoroles::member_remove(data, *guild_id, user.id).await,
+            );
+            log_error(
+                "boosterroles",
+                boosterroles::member_remove(ctx, data, *guild_id, user.id).await,
+            );
+            log_error(
+                "prune",
+                prune::member_remove(data, *guild_id, user.id).await,
+            );
+        }
+        FullEvent::GuildAuditLogEntryCreate { entry, guild_id } => {
+            log_error(
+                "antinuke",
+                antinuke::audit_log_entry(ctx, data, *guild_id, entry).await,
+            );
+        }
+        FullEvent::ReactionAdd { add_reaction } => {
+            if let (Some(guild_id), Some(user_id)) = (add_reaction.guild_id, add_reaction.user_id) {
+                log_error(
+                    "prune",
+                    prune::record_activity(data, guild_id, user_id).await,
+                );
+            }
+            log_error(
+                "reactionroles",
+                reactionroles::reaction_add(ctx, data, add_reaction).await,
+            );
+        }
+        FullEvent::ReactionRemove { removed_reaction } => {
+            log_error(
+                "reactionroles",
+                reactionroles::reaction_remove(ctx, data, removed_reaction).await,
+            );
+        }
+        FullEvent::VoiceStateUpdate { new, .. } => {
+            // Only joining or moving counts, leaving a channel isn't much of a sign of activity
+            if let (Some(guild_id), Some(_)) = (new.guild_id, new.channel_id) {
+                log_error(
+                    "prune",
+                    prune::record_activity(data, guild_id, new.user_id).await,
+                );
+            }
+        }
+        FullEvent::InteractionCreate { interaction } => {
+            log_error(
+                "antiraid",
+                antiraid::handle_interaction(ctx, data, interaction).await,
+            );
+            log_error(
+                "verification",
+                verification::handle_interaction(ctx, data, interaction).await,
+            );
+            log_error(
+                "rolemenus",
+                rolemenus::handle_interaction(ctx, data, interaction).await,
+            );
+        }
+
+        // Server configuration logging
+        FullEvent::ChannelCreate { channel } | FullEvent::CategoryCreate { category: channel } => {
+            log_error(
+                "serverlog",
+                serverlog::channel_create(ctx, data, channel).await,
+            );
+        }
+        FullEvent::ChannelDelete { channel, .. }
+        | FullEvent::CategoryDelete { category: channel } => {
+            log_error(
+                "reactionroles",
+                reactionroles::channel_delete(data, channel.id).await,
+            );
+            log_error(
+                "serverlog",
+                serverlog::channel_delete(ctx, data, channel).await,
+            );
+        }
+        FullEvent::ChannelUpdate { old, new } => {
+            log_error(
+                "serverlog",
+                serverlog::channel_update(ctx, data, old.as_ref(), new).await,
+            );
+        }
+        FullEvent::GuildRoleCreate { new } => {
+            log_error("serverlog", serverlog::role_create(ctx, data, new).await);
+        }
+        FullEvent::GuildRoleDelete {
+            guild_id,
+            removed_role_id,
+            removed_role_data_if_available,
+        } => {
+            log_error(
+                "reactionroles",
+                reactionroles::role_delete(data, *removed_role_id).await,
+            );
+            log_error(
+                "rolemenus",
+                rolemenus::role_delete(data, *guild_id, *removed_role_id).await,
+            );
+            log_error(
+                "autoroles",
+                autoroles::role_delete(data, *guild_id, *removed_role_id).await,
+            );
+            log_error(
+                "boosterroles",
+                boosterroles::role_delete(data, *guild_id, *removed_role_id).await,
+            );
+            log_error(
+                "leveling",
+                leveling::role_delete(data, *guild_id, *removed_role_id).await,
+            );
+            log_error(
+                "prune",
+                prune::role_delete(data, *guild_id, *removed_role_id).await,
+            );
+            log_error(
+                "serverlog",
+                serverlog::role_delete(
+                    ctx,
+                    data,
+                    *guild_id,
+                    *removed_role_id,
+                    removed_role_data_if_available.as_ref(),
+                )
+                .await,
+            );
+        }
+        FullEvent::GuildRoleUpdate {
+            old_data_if_available,
+            new,
+        } => {
+            log_error(
+                "serverlog",
+                serverlog::role_update(ctx, data, old_data_if_available.as_ref(), new).await,
+            );
+        }
+        FullEvent::GuildEmojisUpdate {
+            guild_id,
+            current_state,
+        } => {
+            log_error(
+                "serverlog",
+                serverlog::emojis_update(ctx, data, *guild_id, current_state).await,
+            );
+        }
+        FullEvent::GuildUpdate {
+            old_data_if_available,
+            new_data,
+        } => {
+            log_error(
+                "serverlog",
+                serverlog::guild_update(ctx, data, old_data_if_available.as_ref(), new_data).await,
+            );
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Logs the error of a module's handler instead of passing it on, so one module failing doesn't stop the rest from
+/// seeing the event. Returns what the handler returned if it succeeded.
+fn log_error<T, E: std::fmt::Display>(module: &str, result: Result<T, E>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(error) => {
+            println!("Error in the {} event handler: {}", module, error);
+            None
+        }
+    }
+}
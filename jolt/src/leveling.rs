@@ -0,0 +1,172 @@
+/*
+XP and leveling
+
+Members gain a random amount of XP for chatting, at most once per cooldown. How much is decided by the guild's gain
+range, multiplied by the multiplier of the channel and the best multiplier among the member's roles. A multiplier of 0
+on the channel or any of the roles means no XP at all, which is how channels and roles get excluded.
+
+Only total XP is stored; levels are worked out from it with the guild's curve (see curve.rs), so changing the curve
+moves everyone to the right level straight away. Level ups hand out reward roles and get announced (see rewards.rs).
+*/
+
+pub mod card;
+pub mod curve;
+pub mod error;
+pub mod expression;
+pub mod rewards;
+pub mod transfer;
+pub mod types;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude::{self, GuildId, Message, RoleId, UserId};
+use rand::Rng;
+use tokio::sync::Mutex;
+
+use crate::database;
+
+use curve::Curve;
+use error::LevelingError;
+use types::{LevelingSettings, XpMultiplierKind};
+
+/// The longest cooldown a guild can set, which is also how long cooldowns are remembered for.
+pub const MAX_COOLDOWN: Duration = Duration::from_secs(24 * 60 * 60);
+/// The largest multiplier a role or channel can have.
+pub const MAX_MULTIPLIER: f64 = 100.0;
+/// The most XP a single message can give before multipliers.
+pub const MAX_GAIN: u32 = 10_000;
+
+/// In-memory state that leveling needs to keep track of between messages.
+#[derive(Default)]
+pub struct LevelingState {
+    cooldowns: Mutex<HashMap<(GuildId, UserId), Instant>>,
+    // Custom curves take a while to parse and check, which is too much to do on every message
+    curves: Mutex<HashMap<GuildId, Arc<Curve>>>,
+}
+
+/// Gets the curve of a guild, only building it if it isn't cached yet.
+pub async fn get_curve(
+    data: &crate::Data,
+    guild_id: GuildId,
+    settings: &LevelingSettings,
+) -> Result<Arc<Curve>, LevelingError> {
+    if let Some(curve) = data.leveling.curves.lock().await.get(&guild_id) {
+        return Ok(curve.clone());
+    }
+
+    let curve = Arc::new(Curve::from_settings(settings)?);
+    data.leveling
+        .curves
+        .lock()
+        .await
+        .insert(guild_id, curve.clone());
+
+    Ok(curve)
+}
+
+/// Forgets the cached curve of a guild, so the next use builds it again from the database.
+pub async fn invalidate_curve(data: &crate::Data, guild_id: GuildId) {
+    data.leveling.curves.lock().await.remove(&guild_id);
+}
+
+pub async fn handle_message(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    message: &Message,
+) -> Result<(), crate::DynError> {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+    if message.author.bot {
+        return Ok(());
+    }
+
+    let settings = database::get_leveling_settings(&data.database, guild_id).await?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let roles = message
+        .member
+        .as_ref()
+        .map(|member| member.roles.clone())
+        .unwrap_or_default();
+    let mut channel_multiplier = 1.0;
+    let mut role_multiplier: Option<f64> = None;
+    for multiplier in database::get_xp_multipliers(&data.database, guild_id).await? {
+        let applies = match multiplier.kind {
+            XpMultiplierKind::Channel => multiplier.target_id == message.channel_id.get(),
+            XpMultiplierKind::Role => roles.iter().any(|role| role.get() == multiplier.target_id),
+        };
+        if !applies {
+            continue;
+        }
+        if multiplier.multiplier == 0.0 {
+            return Ok(());
+        }
+
+        match multiplier.kind {
+            XpMultiplierKind::Channel => channel_multiplier = multiplier.multiplier,
+            XpMultiplierKind::Role => {
+                role_multiplier = Some(role_multiplier.map_or(multiplier.multiplier, |best| {
+                    best.max(multiplier.multiplier)
+                }))
+            }
+        }
+    }
+
+    let now = Instant::now();
+    {
+        let mut cooldowns = data.leveling.cooldowns.lock().await;
+        if let Some(last) = cooldowns.get(&(guild_id, message.author.id)) {
+            if now.duration_since(*last) < settings.cooldown {
+                return Ok(());
+            }
+        }
+        cooldowns.insert((guild_id, message.author.id), now);
+
+        // Nobody can be on cooldown for longer than this, so anything older is dead weight
+        if cooldowns.len() > 10_000 {
+            cooldowns.retain(|_, last| now.duration_since(*last) < MAX_COOLDOWN);
+        }
+    }
+
+    let base = rand::thread_rng().gen_range(settings.min_xp..=settings.max_xp);
+    let gain = (base as f64 * channel_multiplier * role_multiplier.unwrap_or(1.0)).round() as u64;
+    if gain == 0 {
+        return Ok(());
+    }
+
+    let xp = database::add_xp(&data.database, guild_id, message.author.id, gain).await?;
+
+    let curve = get_curve(data, guild_id, &settings).await?;
+    let level = curve.level_for_xp(xp);
+    // XP can be taken away or reset by hand at the same time, in which case there's no level up anyway
+    if level > curve.level_for_xp(xp.saturating_sub(gain)) {
+        rewards::level_up(
+            ctx,
+            &data.database,
+            &settings,
+            guild_id,
+            &message.author,
+            &roles,
+            level,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn role_delete(
+    data: &crate::Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<(), crate::error::Error> {
+    database::remove_level_reward(&data.database, guild_id, role_id).await?;
+
+    Ok(())
+}
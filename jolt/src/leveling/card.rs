@@ -0,0 +1,190 @@
+/*
+Rank cards
+
+Cards are drawn locally with the built-in bitmap font (see font.rs), in the guild's theme colors. The avatar is a
+placeholder circle with the member's initial in it, since downloading avatars would mean an outside request every time
+someone checks their rank.
+*/
+
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, Rgb, RgbImage};
+
+use crate::font;
+
+use super::types::RankCardTheme;
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 200;
+const PADDING: u32 = 24;
+
+const AVATAR_CENTER: (i64, i64) = (100, 100);
+const AVATAR_RADIUS: i64 = 70;
+const INITIAL_SCALE: u32 = 10;
+
+// Everything else lines up to the right of the avatar
+const CONTENT_X: u32 = 200;
+const CONTENT_WIDTH: u32 = WIDTH - CONTENT_X - PADDING;
+
+const NAME_Y: u32 = 36;
+const NAME_SCALE: u32 = 4;
+const STATS_Y: u32 = 84;
+const STATS_SCALE: u32 = 3;
+const XP_Y: u32 = 120;
+const XP_SCALE: u32 = 2;
+const BAR_Y: u32 = 144;
+const BAR_HEIGHT: u32 = 24;
+
+/// Everything shown on a rank card.
+pub struct RankCard<'a> {
+    pub username: &'a str,
+    pub level: u32,
+    pub rank: Option<u64>,
+    /// XP gained since the current level and XP needed for the next one, or `None` at the max level.
+    pub progress: Option<(u64, u64)>,
+}
+
+/// Draws a rank card as a PNG.
+pub fn render(card: &RankCard, theme: &RankCardTheme) -> Vec<u8> {
+    let background = rgb(theme.background);
+    let accent = rgb(theme.accent);
+    let text = rgb(theme.text);
+    // The empty part of the bar sits between the background and the text, so it works on light and dark themes
+    let track = blend(background, text, 0.15);
+
+    let mut image = RgbImage::from_pixel(WIDTH, HEIGHT, background);
+
+    draw_circle(&mut image, AVATAR_CENTER, AVATAR_RADIUS, accent);
+    let initial = card
+        .username
+        .chars()
+        .next()
+        .unwrap_or('?')
+        .to_ascii_uppercase()
+        .to_string();
+    font::draw_text(
+        &mut image,
+        &initial,
+        AVATAR_CENTER.0 as u32 - font::text_width(&initial, INITIAL_SCALE) / 2,
+        AVATAR_CENTER.1 as u32 - font::GLYPH_HEIGHT * INITIAL_SCALE / 2,
+        INITIAL_SCALE,
+        background,
+    );
+
+    font::draw_text(
+        &mut image,
+        &fit(card.username, NAME_SCALE, CONTENT_WIDTH),
+        CONTENT_X,
+        NAME_Y,
+        NAME_SCALE,
+        text,
+    );
+
+    let level_width = font::draw_text(
+        &mut image,
+        &format!("LEVEL {}", card.level),
+        CONTENT_X,
+        STATS_Y,
+        STATS_SCALE,
+        accent,
+    );
+    let rank = match card.rank {
+        Some(rank) => format!("RANK #{}", rank),
+        None => String::from("UNRANKED"),
+    };
+    font::draw_text(
+        &mut image,
+        &rank,
+        CONTENT_X + level_width + font::advance(STATS_SCALE) * 2,
+        STATS_Y,
+        STATS_SCALE,
+        text,
+    );
+
+    let (xp_text, filled) = match card.progress {
+        Some((gained, needed)) => (
+            format!("{} / {} XP", gained, needed),
+            gained as f64 / needed.max(1) as f64,
+        ),
+        None => (String::from("MAX LEVEL"), 1.0),
+    };
+    font::draw_text(
+        &mut image,
+        &xp_text,
+        (WIDTH - PADDING).saturating_sub(font::text_width(&xp_text, XP_SCALE)),
+        XP_Y,
+        XP_SCALE,
+        text,
+    );
+
+    let filled_width = (CONTENT_WIDTH as f64 * filled.clamp(0.0, 1.0)).round() as u32;
+    fill_rect(
+        &mut image,
+        CONTENT_X,
+        BAR_Y,
+        CONTENT_WIDTH,
+        BAR_HEIGHT,
+        track,
+    );
+    fill_rect(
+        &mut image,
+        CONTENT_X,
+        BAR_Y,
+        filled_width,
+        BAR_HEIGHT,
+        accent,
+    );
+
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+        .expect("Failed to encode rank card!");
+    bytes
+}
+
+/// Cuts text down to fit a width, ending it with `..` if anything had to go.
+fn fit(text: &str, scale: u32, width: u32) -> String {
+    let max_characters = ((width + scale) / font::advance(scale)) as usize;
+    if text.chars().count() <= max_characters {
+        return text.to_string();
+    }
+
+    let mut fitted: String = text
+        .chars()
+        .take(max_characters.saturating_sub(2))
+        .collect();
+    fitted.push_str("..");
+    fitted
+}
+
+fn draw_circle(image: &mut RgbImage, center: (i64, i64), radius: i64, color: Rgb<u8>) {
+    for y in (center.1 - radius).max(0)..=(center.1 + radius).min(image.height() as i64 - 1) {
+        for x in (center.0 - radius).max(0)..=(center.0 + radius).min(image.width() as i64 - 1) {
+            let (dx, dy) = (x - center.0, y - center.1);
+            if dx * dx + dy * dy <= radius * radius {
+                image.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+fn fill_rect(image: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+    for pixel_y in y..(y + height).min(image.height()) {
+        for pixel_x in x..(x + width).min(image.width()) {
+            image.put_pixel(pixel_x, pixel_y, color);
+        }
+    }
+}
+
+fn rgb(color: u32) -> Rgb<u8> {
+    Rgb([(color >> 16) as u8, (color >> 8) as u8, color as u8])
+}
+
+fn blend(from: Rgb<u8>, to: Rgb<u8>, amount: f64) -> Rgb<u8> {
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+    Rgb([
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ])
+}
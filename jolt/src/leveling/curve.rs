@@ -0,0 +1,211 @@
+/*
+Level curves
+
+A curve says how much XP in total it takes to reach each level, starting from 0 XP at level 0. Levels are found by
+walking up the curve until the next level is out of reach, which is cheap enough since levels are capped.
+*/
+
+use super::error::LevelingError;
+use super::expression::Expression;
+use super::types::{LevelCurve, LevelingSettings};
+
+/// The highest level anyone can reach.
+pub const MAX_LEVEL: u32 = 1000;
+
+/// A curve ready to be used, with custom expressions already parsed.
+pub enum Curve {
+    Preset(LevelCurve),
+    Custom(Expression),
+}
+
+impl Curve {
+    /// Builds a curve out of a guild's settings, checking that custom curves actually work as curves.
+    pub fn new(curve: LevelCurve, expression: Option<&str>) -> Result<Self, LevelingError> {
+        let curve = match curve {
+            LevelCurve::Custom => Curve::Custom(Expression::parse(
+                expression.ok_or(LevelingError::MissingExpression)?,
+            )?),
+            preset => Curve::Preset(preset),
+        };
+        curve.validate()?;
+
+        Ok(curve)
+    }
+
+    pub fn from_settings(settings: &LevelingSettings) -> Result<Self, LevelingError> {
+        Self::new(settings.curve, settings.expression.as_deref())
+    }
+
+    fn raw_xp(&self, level: u32) -> f64 {
+        let level = level as f64;
+        match self {
+            Curve::Preset(LevelCurve::Linear) => 100.0 * level,
+            Curve::Preset(LevelCurve::Quadratic) => 50.0 * level * level + 50.0 * level,
+            Curve::Preset(LevelCurve::Exponential) => 1000.0 * (1.1f64.powf(level) - 1.0),
+            // Unreachable, since custom curves are always parsed into `Curve::Custom`
+            Curve::Preset(LevelCurve::Custom) => f64::INFINITY,
+            Curve::Custom(expression) => expression.evaluate(level),
+        }
+    }
+
+    /// Makes sure every level takes more XP than the one before it.
+    fn validate(&self) -> Result<(), LevelingError> {
+        let mut previous = 0;
+        for level in 1..=MAX_LEVEL {
+            // Growing curves saturate below before they get anywhere near infinity, so this is only ever something like
+            // a division by zero
+            if !self.raw_xp(level).is_finite() {
+                return Err(LevelingError::InvalidCurve(format!(
+                    "level {} doesn't come out to a finite number",
+                    level
+                )));
+            }
+
+            let xp = self.xp_for_level(level);
+            if xp <= previous {
+                return Err(LevelingError::InvalidCurve(format!(
+                    "level {} needs {} XP, which isn't more than level {} ({} XP)",
+                    level,
+                    xp,
+                    level - 1,
+                    previous
+                )));
+            }
+            // Levels this far up can't be reached anyway
+            if xp == u64::MAX {
+                break;
+            }
+            previous = xp;
+        }
+
+        Ok(())
+    }
+
+    /// The total XP it takes to reach a level.
+    pub fn xp_for_level(&self, level: u32) -> u64 {
+        if level == 0 {
+            return 0;
+        }
+
+        // Float to integer casts saturate, so huge values just become unreachable levels
+        self.raw_xp(level.min(MAX_LEVEL)).floor() as u64
+    }
+
+    /// The level a member with this much XP is at.
+    pub fn level_for_xp(&self, xp: u64) -> u32 {
+        let mut level = 0;
+        while level < MAX_LEVEL && self.xp_for_level(level + 1) <= xp {
+            level += 1;
+        }
+
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(expression: &str) -> Result<Curve, LevelingError> {
+        Curve::new(LevelCurve::Custom, Some(expression))
+    }
+
+    #[test]
+    fn presets_are_valid_curves() {
+        for preset in [
+            LevelCurve::Linear,
+            LevelCurve::Quadratic,
+            LevelCurve::Exponential,
+        ] {
+            let curve = Curve::new(preset, None).expect("Preset curves should be valid");
+            assert_eq!(curve.xp_for_level(0), 0, "{:?}", preset);
+            assert!(curve.xp_for_level(1) > 0, "{:?}", preset);
+        }
+    }
+
+    #[test]
+    fn level_for_xp_matches_xp_for_level() {
+        let curve = Curve::new(LevelCurve::Linear, None).unwrap();
+        let cases = [(0, 0), (99, 0), (100, 1), (199, 1), (250, 2), (1_000, 10)];
+
+        for (xp, level) in cases {
+            assert_eq!(curve.level_for_xp(xp), level, "{} XP", xp);
+        }
+    }
+
+    #[test]
+    fn custom_curves_need_an_expression() {
+        assert!(matches!(
+            Curve::new(LevelCurve::Custom, None),
+            Err(LevelingError::MissingExpression)
+        ));
+    }
+
+    #[test]
+    fn rejects_curves_that_dont_keep_going_up() {
+        let cases = [
+            "100",
+            "100 - level",
+            "level ^ 2 - 10 * level",
+            "floor(level / 2) * 100",
+            "-100 * level",
+        ];
+
+        for expression in cases {
+            assert!(
+                matches!(custom(expression), Err(LevelingError::InvalidCurve(_))),
+                "{:?}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_division_by_zero_and_undefined_levels() {
+        let cases = [
+            "level / 0",
+            "100 / (level - 1)",
+            "100 * level + 1 / (level - 10)",
+            "sqrt(-level)",
+            "ln(level - 1)",
+        ];
+
+        for expression in cases {
+            assert!(
+                matches!(custom(expression), Err(LevelingError::InvalidCurve(_))),
+                "{:?}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_reasonable_custom_curves() {
+        let cases = [
+            ("100 * level ^ 1.5", 100),
+            ("50 * level ^ 2 + 50 * level", 100),
+            ("exp(level)", 2),
+            ("level", 1),
+        ];
+
+        for (expression, level_1) in cases {
+            let curve = custom(expression).expect(expression);
+            assert_eq!(curve.xp_for_level(1), level_1, "{:?}", expression);
+        }
+    }
+
+    #[test]
+    fn levels_are_capped() {
+        let curve = Curve::new(LevelCurve::Linear, None).unwrap();
+        assert_eq!(curve.level_for_xp(u64::MAX), MAX_LEVEL);
+        assert_eq!(
+            curve.xp_for_level(MAX_LEVEL + 1),
+            curve.xp_for_level(MAX_LEVEL)
+        );
+
+        // Curves that outgrow u64 partway through just stop there
+        let curve = custom("exp(level)").unwrap();
+        assert_eq!(curve.xp_for_level(MAX_LEVEL), u64::MAX);
+        assert!(curve.level_for_xp(u64::MAX - 1) < MAX_LEVEL);
+    }
+}
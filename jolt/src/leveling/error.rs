@@ -0,0 +1,56 @@
+/// Errors relating to the XP and leveling system.
+#[derive(thiserror::Error, Debug)]
+pub enum LevelingError {
+    /// An error representing a custom level curve that couldn't be read.
+    /// Contains one value, which is what went wrong.
+    #[error("Invalid curve expression: {0}")]
+    InvalidExpression(String),
+    /// An error representing a level curve that doesn't work as a curve.
+    /// Contains one value, which is what went wrong.
+    #[error("Invalid level curve: {0}")]
+    InvalidCurve(String),
+    /// An error representing a custom curve being picked without an expression.
+    #[error("A custom curve needs an expression, like `100 * level ^ 1.5`!")]
+    MissingExpression,
+    /// An error representing an XP gain range that is backwards or too large.
+    /// Contains one value, which is the most XP a message can give.
+    #[error("The minimum XP gain can't be above the maximum, and neither can be above {0}!")]
+    InvalidGain(u32),
+    /// An error representing a cooldown that is longer than allowed.
+    #[error("The cooldown can't be longer than a day!")]
+    CooldownTooLong,
+    /// An error representing a multiplier that is negative or too large.
+    /// Contains one value, which is the largest multiplier allowed.
+    #[error("Multipliers have to be between 0 and {0}!")]
+    InvalidMultiplier(f64),
+    /// An error representing a level that can't be used.
+    /// Contains two values, which are the lowest and highest levels allowed.
+    #[error("Levels have to be between {0} and {1}!")]
+    InvalidLevel(u32, u32),
+    /// An error representing a role that isn't a level reward.
+    #[error("That role is not a level reward!")]
+    RewardNotFound,
+    /// An error representing a level up message that is too long.
+    /// Contains one value, which is the longest a message can be.
+    #[error("Level up messages can't be longer than {0} characters!")]
+    MessageTooLong(usize),
+    /// An error representing an import file that couldn't be read.
+    /// Contains one value, which is what went wrong.
+    #[error("Couldn't import XP: {0}")]
+    InvalidImport(String),
+    /// An error representing an import file that is too large.
+    /// Contains one value, which is the largest file allowed in megabytes.
+    #[error("Import files can't be larger than {0} MB!")]
+    ImportTooLarge(usize),
+    /// An error representing a member being given more XP than allowed.
+    /// Contains one value, which is the most XP a member can have.
+    #[error("Members can't have more than {0} XP!")]
+    TooMuchXp(u64),
+    /// An error representing a theme color that isn't a hex color.
+    /// Contains one value, which is the color that was given.
+    #[error("{0} is not a valid color! Colors are written in hex, like #89B4FA.")]
+    InvalidColor(String),
+    /// An error representing leveling being used while it's turned off.
+    #[error("Leveling is not enabled in this server!")]
+    Disabled,
+}
@@ -0,0 +1,430 @@
+/*
+Custom level curve expressions
+
+A small arithmetic language for level curves, like `100 * level ^ 1.5 + 50 * level`. It only has numbers, the `level`
+variable, a couple of constants, the usual operators and a handful of functions, so there is nothing in it that can
+loop, allocate without bound or touch anything outside of the expression. Expressions are also capped in length and
+nesting depth so that parsing them can't blow the stack.
+*/
+
+use super::error::LevelingError;
+
+/// The longest expression that can be used as a curve.
+pub const MAX_EXPRESSION_LENGTH: usize = 200;
+/// How deeply parentheses, functions and operators can be nested.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Token {
+    Number(f64),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LeftParen,
+    RightParen,
+    Identifier(usize, usize),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Function {
+    Sqrt,
+    Ln,
+    Log,
+    Exp,
+    Abs,
+    Floor,
+    Ceil,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sqrt" => Some(Function::Sqrt),
+            "ln" => Some(Function::Ln),
+            "log" => Some(Function::Log),
+            "exp" => Some(Function::Exp),
+            "abs" => Some(Function::Abs),
+            "floor" => Some(Function::Floor),
+            "ceil" => Some(Function::Ceil),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, value: f64) -> f64 {
+        match self {
+            Function::Sqrt => value.sqrt(),
+            Function::Ln => value.ln(),
+            Function::Log => value.log10(),
+            Function::Exp => value.exp(),
+            Function::Abs => value.abs(),
+            Function::Floor => value.floor(),
+            Function::Ceil => value.ceil(),
+        }
+    }
+}
+
+/// A parsed expression, ready to be evaluated for any level.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expression {
+    Number(f64),
+    Level,
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Function(Function, Box<Expression>),
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Self, LevelingError> {
+        if text.chars().count() > MAX_EXPRESSION_LENGTH {
+            return Err(LevelingError::InvalidExpression(format!(
+                "expressions can be at most {} characters long",
+                MAX_EXPRESSION_LENGTH
+            )));
+        }
+
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            text,
+            tokens: &tokens,
+            position: 0,
+            depth: 0,
+        };
+
+        let expression = parser.expression()?;
+        if parser.position < tokens.len() {
+            return Err(LevelingError::InvalidExpression(String::from(
+                "unexpected input after the end of the expression",
+            )));
+        }
+
+        Ok(expression)
+    }
+
+    pub fn evaluate(&self, level: f64) -> f64 {
+        match self {
+            Expression::Number(value) => *value,
+            Expression::Level => level,
+            Expression::Negate(inner) => -inner.evaluate(level),
+            Expression::Binary(operator, left, right) => {
+                let left = left.evaluate(level);
+                let right = right.evaluate(level);
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                    Operator::Power => left.powf(right),
+                }
+            }
+            Expression::Function(function, inner) => function.apply(inner.evaluate(level)),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, LevelingError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '^' => Token::Caret,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, next)) = chars.peek() {
+                    if !(next.is_ascii_digit() || *next == '.') {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+
+                let number = &text[start..end];
+                Token::Number(number.parse().map_err(|_| {
+                    LevelingError::InvalidExpression(format!("`{}` is not a number", number))
+                })?)
+            }
+            c if c.is_ascii_alphabetic() => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, next)) = chars.peek() {
+                    if !next.is_ascii_alphanumeric() {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+
+                Token::Identifier(start, end)
+            }
+            c => {
+                return Err(LevelingError::InvalidExpression(format!(
+                    "unexpected character `{}`",
+                    c
+                )))
+            }
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser, lowest precedence first:
+/// addition and subtraction, then multiplication and division, then negation, then powers (right to left).
+struct Parser<'a> {
+    text: &'a str,
+    tokens: &'a [Token],
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn descend(&mut self) -> Result<(), LevelingError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(LevelingError::InvalidExpression(String::from(
+                "the expression is nested too deeply",
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn expression(&mut self) -> Result<Expression, LevelingError> {
+        self.descend()?;
+        let mut left = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Plus) => Operator::Add,
+                Some(Token::Minus) => Operator::Subtract,
+                _ => break,
+            };
+            self.next();
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.term()?));
+        }
+        self.depth -= 1;
+
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expression, LevelingError> {
+        let mut left = self.unary()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Star) => Operator::Multiply,
+                Some(Token::Slash) => Operator::Divide,
+                _ => break,
+            };
+            self.next();
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.unary()?));
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression, LevelingError> {
+        if self.peek() == Some(Token::Minus) {
+            self.next();
+            self.descend()?;
+            let inner = self.unary()?;
+            self.depth -= 1;
+            return Ok(Expression::Negate(Box::new(inner)));
+        }
+
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expression, LevelingError> {
+        let base = self.atom()?;
+        if self.peek() != Some(Token::Caret) {
+            return Ok(base);
+        }
+
+        self.next();
+        self.descend()?;
+        // Powers go right to left, so `2 ^ 3 ^ 2` is `2 ^ 9`
+        let exponent = self.unary()?;
+        self.depth -= 1;
+
+        Ok(Expression::Binary(
+            Operator::Power,
+            Box::new(base),
+            Box::new(exponent),
+        ))
+    }
+
+    fn atom(&mut self) -> Result<Expression, LevelingError> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::LeftParen) => {
+                let inner = self.expression()?;
+                self.expect_closing()?;
+                Ok(inner)
+            }
+            Some(Token::Identifier(start, end)) => {
+                let name = self.text[start..end].to_lowercase();
+                match name.as_str() {
+                    "level" | "x" | "n" => return Ok(Expression::Level),
+                    "e" => return Ok(Expression::Number(std::f64::consts::E)),
+                    "pi" => return Ok(Expression::Number(std::f64::consts::PI)),
+                    _ => (),
+                }
+
+                let function = Function::from_name(&name).ok_or_else(|| {
+                    LevelingError::InvalidExpression(format!("unknown name `{}`", name))
+                })?;
+                if self.next() != Some(Token::LeftParen) {
+                    return Err(LevelingError::InvalidExpression(format!(
+                        "`{}` needs parentheses, like `{}(level)`",
+                        name, name
+                    )));
+                }
+                let inner = self.expression()?;
+                self.expect_closing()?;
+
+                Ok(Expression::Function(function, Box::new(inner)))
+            }
+            Some(_) => Err(LevelingError::InvalidExpression(String::from(
+                "expected a number, `level`, a function or parentheses",
+            ))),
+            None => Err(LevelingError::InvalidExpression(String::from(
+                "the expression ends too early",
+            ))),
+        }
+    }
+
+    fn expect_closing(&mut self) -> Result<(), LevelingError> {
+        match self.next() {
+            Some(Token::RightParen) => Ok(()),
+            _ => Err(LevelingError::InvalidExpression(String::from(
+                "missing a closing parenthesis",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(text: &str, level: f64) -> f64 {
+        Expression::parse(text)
+            .unwrap_or_else(|error| panic!("{:?} failed to parse: {}", text, error))
+            .evaluate(level)
+    }
+
+    #[test]
+    fn follows_operator_precedence() {
+        let cases = [
+            ("1 + 2 * 3", 7.0),
+            ("(1 + 2) * 3", 9.0),
+            ("10 - 4 - 3", 3.0),
+            ("100 / 10 / 2", 5.0),
+            ("2 * 3 ^ 2", 18.0),
+            ("2 ^ 3 ^ 2", 512.0),
+            ("-2 ^ 2", -4.0),
+            ("(-2) ^ 2", 4.0),
+            ("2 ^ -1", 0.5),
+            ("2 * -3", -6.0),
+            ("--3", 3.0),
+            ("1 - -1", 2.0),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(evaluate(text, 0.0), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn evaluates_level_constants_and_functions() {
+        let cases = [
+            ("level", 5.0),
+            ("LEVEL * 2", 10.0),
+            ("x + n", 10.0),
+            ("100 * level ^ 2", 2500.0),
+            ("sqrt(16)", 4.0),
+            ("abs(-level)", 5.0),
+            ("floor(level / 2)", 2.0),
+            ("ceil(level / 2)", 3.0),
+            ("log(1000)", 3.0),
+            ("ln(e)", 1.0),
+            ("exp(0)", 1.0),
+            ("floor(pi * 100)", 314.0),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(evaluate(text, 5.0), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn division_by_zero_is_not_finite() {
+        assert_eq!(evaluate("1 / 0", 0.0), f64::INFINITY);
+        assert_eq!(evaluate("-1 / 0", 0.0), f64::NEG_INFINITY);
+        assert!(evaluate("0 / 0", 0.0).is_nan());
+        assert!(evaluate("100 / (level - 1)", 1.0).is_infinite());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        let cases = [
+            "", "1 +", "* 2", "(1 + 2", "1 + 2)", "1 2", "1..2", "1 $ 2", "foo", "sqrt 4",
+            "sqrt(4", "level(2)",
+        ];
+
+        for text in cases {
+            assert!(
+                matches!(
+                    Expression::parse(text),
+                    Err(LevelingError::InvalidExpression(_))
+                ),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_expressions_that_are_too_long_or_deep() {
+        let long = "1 + ".repeat(MAX_EXPRESSION_LENGTH / 4) + "1";
+        assert!(long.len() > MAX_EXPRESSION_LENGTH);
+        assert!(Expression::parse(&long).is_err());
+
+        let deep = "(".repeat(MAX_DEPTH + 1) + "1" + &")".repeat(MAX_DEPTH + 1);
+        assert!(deep.len() <= MAX_EXPRESSION_LENGTH);
+        assert!(Expression::parse(&deep).is_err());
+
+        let negated = "-".repeat(MAX_DEPTH + 1) + "1";
+        assert!(Expression::parse(&negated).is_err());
+
+        let shallow = "(".repeat(MAX_DEPTH - 1) + "1" + &")".repeat(MAX_DEPTH - 1);
+        assert_eq!(evaluate(&shallow, 0.0), 1.0);
+    }
+}
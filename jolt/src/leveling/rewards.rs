@@ -0,0 +1,223 @@
+/*
+Level rewards
+
+Rewards are roles given to members once they reach a level. In stack mode members keep every reward up to their level,
+in replace mode only the rewards of the highest level they've reached. Which rewards a member should have only ever
+depends on their level, so syncing just compares that against the roles they actually have.
+*/
+
+use poise::serenity_prelude::{self, CreateMessage, GuildId, RoleId, User, UserId};
+
+use crate::database;
+
+use super::curve::Curve;
+use super::types::{LevelReward, LevelingSettings, RewardMode};
+
+/// Used when announcements are turned on without a message of the server's own.
+pub const DEFAULT_LEVELUP_MESSAGE: &str = "Congratulations {user}, you reached level {level}!";
+/// The longest a level up message can be, leaving room for the placeholders to be filled in.
+pub const MAX_LEVELUP_MESSAGE_LENGTH: usize = 1000;
+
+const MEMBER_PAGE_SIZE: u64 = 1000;
+
+/// Works out which reward roles a member at a level should have.
+pub fn earned_rewards(rewards: &[LevelReward], mode: RewardMode, level: u32) -> Vec<RoleId> {
+    let reached = rewards.iter().filter(|reward| reward.level <= level);
+
+    match mode {
+        RewardMode::Stack => reached.map(|reward| reward.role_id).collect(),
+        RewardMode::Replace => {
+            let highest = reached.clone().map(|reward| reward.level).max();
+            reached
+                .filter(|reward| Some(reward.level) == highest)
+                .map(|reward| reward.role_id)
+                .collect()
+        }
+    }
+}
+
+/// Gives and takes away reward roles so a member has exactly the ones their level earns.
+/// Returns whether any roles were changed.
+pub async fn sync_member(
+    cache_http: impl serenity_prelude::CacheHttp,
+    guild_id: GuildId,
+    user_id: UserId,
+    roles: &[RoleId],
+    level: u32,
+    rewards: &[LevelReward],
+    mode: RewardMode,
+) -> Result<bool, serenity_prelude::Error> {
+    let earned = earned_rewards(rewards, mode, level);
+    let mut changed = false;
+
+    for reward in rewards {
+        let has_role = roles.contains(&reward.role_id);
+        let earned_role = earned.contains(&reward.role_id);
+
+        if earned_role && !has_role {
+            cache_http
+                .http()
+                .add_member_role(guild_id, user_id, reward.role_id, Some("Level reward"))
+                .await?;
+            changed = true;
+        } else if !earned_role && has_role {
+            cache_http
+                .http()
+                .remove_member_role(
+                    guild_id,
+                    user_id,
+                    reward.role_id,
+                    Some("Level reward no longer earned"),
+                )
+                .await?;
+            changed = true;
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Syncs the rewards of every member of a guild, for when the curve or the rewards change.
+/// Returns how many members were updated and how many couldn't be.
+pub async fn sync_guild(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+) -> Result<(usize, usize), crate::DynError> {
+    let rewards = database::get_level_rewards(database, guild_id).await?;
+    if rewards.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let settings = database::get_leveling_settings(database, guild_id).await?;
+    let curve = Curve::from_settings(&settings)?;
+    let xp = database::get_all_xp(database, guild_id).await?;
+
+    let mut updated = 0;
+    let mut failed = 0;
+    let mut after: Option<UserId> = None;
+    loop {
+        let members = guild_id
+            .members(cache_http.http(), Some(MEMBER_PAGE_SIZE), after)
+            .await?;
+        after = members.last().map(|member| member.user.id);
+
+        for member in &members {
+            if member.user.bot {
+                continue;
+            }
+
+            let level = curve.level_for_xp(xp.get(&member.user.id).copied().unwrap_or(0));
+            match sync_member(
+                &cache_http,
+                guild_id,
+                member.user.id,
+                &member.roles,
+                level,
+                &rewards,
+                settings.reward_mode,
+            )
+            .await
+            {
+                Ok(true) => updated += 1,
+                Ok(false) => (),
+                Err(_) => failed += 1,
+            }
+        }
+
+        if (members.len() as u64) < MEMBER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    Ok((updated, failed))
+}
+
+/// Syncs the rewards of one member, for when their XP was changed by hand.
+/// Members that aren't in the guild are skipped.
+pub async fn sync_user(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), crate::DynError> {
+    let rewards = database::get_level_rewards(database, guild_id).await?;
+    if rewards.is_empty() {
+        return Ok(());
+    }
+
+    let member = match guild_id.member(&cache_http, user_id).await {
+        Ok(member) => member,
+        Err(_) => return Ok(()),
+    };
+    let settings = database::get_leveling_settings(database, guild_id).await?;
+    let curve = Curve::from_settings(&settings)?;
+    let xp = database::get_xp(database, guild_id, user_id).await?;
+
+    sync_member(
+        &cache_http,
+        guild_id,
+        user_id,
+        &member.roles,
+        curve.level_for_xp(xp),
+        &rewards,
+        settings.reward_mode,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Fills in the placeholders of a level up message.
+pub fn format_levelup_message(template: &str, user: &User, level: u32) -> String {
+    template
+        .replace("{user}", &format!("<@{}>", user.id))
+        .replace("{username}", &user.name)
+        .replace("{level}", &level.to_string())
+}
+
+/// Gives a member the rewards of their new level and announces it, if the guild wants level ups announced.
+/// Failing to give rewards is only printed, so a role above Jolt's doesn't stop the announcement.
+pub async fn level_up(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    settings: &LevelingSettings,
+    guild_id: GuildId,
+    user: &User,
+    roles: &[RoleId],
+    level: u32,
+) -> Result<(), crate::DynError> {
+    let rewards = database::get_level_rewards(database, guild_id).await?;
+    if let Err(error) = sync_member(
+        &cache_http,
+        guild_id,
+        user.id,
+        roles,
+        level,
+        &rewards,
+        settings.reward_mode,
+    )
+    .await
+    {
+        println!(
+            "Failed to give level rewards to user {} in {}: {}",
+            user.id, guild_id, error
+        );
+    }
+
+    if let Some(channel_id) = settings.levelup_channel {
+        let template = settings
+            .levelup_message
+            .as_deref()
+            .unwrap_or(DEFAULT_LEVELUP_MESSAGE);
+
+        channel_id
+            .send_message(
+                &cache_http,
+                CreateMessage::default().content(format_levelup_message(template, user, level)),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
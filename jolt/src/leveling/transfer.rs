@@ -0,0 +1,367 @@
+/*
+XP import and export
+
+Exports are either CSV (`user_id,xp`) or JSON (`[{"user_id": "...", "xp": ...}]`), and both can be imported again.
+Imports also take leaderboard dumps from other bots, like MEE6's. Those all look about the same: a list of entries,
+sometimes inside an object or keyed by user ID, each with a user ID and either total XP or a level. Field names differ
+between bots, so a few common ones are tried. Entries with only a level are given the XP it takes to reach that level on
+the guild's own curve.
+*/
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude::UserId;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::curve::{Curve, MAX_LEVEL};
+use super::error::LevelingError;
+
+/// The largest file that can be imported, in bytes.
+pub const MAX_IMPORT_SIZE: usize = 8 * 1024 * 1024;
+/// The most XP a member can have, which keeps sums well within what the database can store.
+pub const MAX_XP: u64 = 1_000_000_000_000;
+
+const USER_ID_FIELDS: [&str; 4] = ["user_id", "userId", "id", "user"];
+const XP_FIELDS: [&str; 5] = ["xp", "exp", "experience", "total_xp", "totalXp"];
+const LEVEL_FIELDS: [&str; 2] = ["level", "lvl"];
+const LIST_FIELDS: [&str; 6] = [
+    "players",
+    "users",
+    "members",
+    "leaderboard",
+    "levels",
+    "data",
+];
+
+#[derive(Serialize)]
+struct ExportEntry {
+    // Strings, since plenty of JSON readers can't handle integers this large
+    user_id: String,
+    xp: u64,
+}
+
+pub fn export_csv(entries: &[(UserId, u64)]) -> String {
+    let mut csv = String::from("user_id,xp\n");
+    for (user_id, xp) in entries {
+        csv.push_str(&format!("{},{}\n", user_id, xp));
+    }
+
+    csv
+}
+
+pub fn export_json(entries: &[(UserId, u64)]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(
+        &entries
+            .iter()
+            .map(|(user_id, xp)| ExportEntry {
+                user_id: user_id.to_string(),
+                xp: *xp,
+            })
+            .collect::<Vec<ExportEntry>>(),
+    )
+}
+
+/// Reads an import file, working out whether it's CSV or JSON from what it starts with.
+/// If a user shows up more than once, the last entry wins.
+pub fn parse_import(bytes: &[u8], curve: &Curve) -> Result<Vec<(UserId, u64)>, LevelingError> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|_| LevelingError::InvalidImport(String::from("the file isn't text")))?;
+    let text = text.trim_start_matches('\u{feff}').trim();
+
+    let entries = if text.starts_with('[') || text.starts_with('{') {
+        parse_json(text, curve)?
+    } else {
+        parse_csv(text, curve)?
+    };
+
+    let mut seen = HashMap::new();
+    for (user_id, xp) in entries {
+        seen.insert(user_id, xp);
+    }
+
+    Ok(seen.into_iter().collect())
+}
+
+fn parse_json(text: &str, curve: &Curve) -> Result<Vec<(UserId, u64)>, LevelingError> {
+    let value: Value = serde_json::from_str(text).map_err(|error| {
+        LevelingError::InvalidImport(format!("the JSON is invalid ({})", error))
+    })?;
+
+    match value {
+        Value::Array(entries) => entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| parse_json_entry(entry, None, curve, i + 1))
+            .collect(),
+        Value::Object(object) => {
+            // Most dumps keep the leaderboard in a list inside the object
+            if let Some(entries) = LIST_FIELDS
+                .iter()
+                .find_map(|field| object.get(*field).and_then(Value::as_array))
+            {
+                return entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| parse_json_entry(entry, None, curve, i + 1))
+                    .collect();
+            }
+
+            // Otherwise it's keyed by user ID
+            object
+                .iter()
+                .enumerate()
+                .map(|(i, (key, entry))| parse_json_entry(entry, Some(key.as_str()), curve, i + 1))
+                .collect()
+        }
+        _ => Err(LevelingError::InvalidImport(String::from(
+            "the JSON isn't a list of members",
+        ))),
+    }
+}
+
+fn parse_json_entry(
+    entry: &Value,
+    key: Option<&str>,
+    curve: &Curve,
+    position: usize,
+) -> Result<(UserId, u64), LevelingError> {
+    let entry = entry.as_object().ok_or_else(|| {
+        LevelingError::InvalidImport(format!("entry {} isn't an object", position))
+    })?;
+
+    let user_id = USER_ID_FIELDS
+        .iter()
+        .find_map(|field| entry.get(*field).and_then(json_u64))
+        .or_else(|| key.and_then(|key| key.parse().ok()));
+    let user_id = parse_user_id(user_id, &format!("entry {}", position))?;
+
+    let xp = XP_FIELDS
+        .iter()
+        .find_map(|field| entry.get(*field).and_then(json_u64));
+    let level = LEVEL_FIELDS
+        .iter()
+        .find_map(|field| entry.get(*field).and_then(json_u64));
+    let xp = resolve_xp(xp, level, curve, &format!("entry {}", position))?;
+
+    Ok((user_id, xp))
+}
+
+/// Reads a number that may have been written as a string, as IDs often are.
+fn json_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number
+            .as_u64()
+            .or_else(|| number.as_f64().filter(|n| *n >= 0.0).map(|n| n as u64)),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_csv(text: &str, curve: &Curve) -> Result<Vec<(UserId, u64)>, LevelingError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let mut user_id_column = 0;
+    let mut xp_column = Some(1);
+    let mut level_column = None;
+    let mut rows = Vec::new();
+
+    // Files without a header are taken to be `user_id,xp`
+    if let Some((i, first)) = lines.next() {
+        let header = split_csv_line(first);
+        if header
+            .first()
+            .map_or(false, |cell| cell.parse::<u64>().is_err())
+        {
+            let find = |fields: &[&str]| {
+                header
+                    .iter()
+                    .position(|cell| fields.iter().any(|field| cell.eq_ignore_ascii_case(field)))
+            };
+
+            user_id_column = find(&USER_ID_FIELDS).ok_or_else(|| {
+                LevelingError::InvalidImport(String::from("the CSV has no user ID column"))
+            })?;
+            xp_column = find(&XP_FIELDS);
+            level_column = find(&LEVEL_FIELDS);
+        } else {
+            rows.push((i, header));
+        }
+    }
+    rows.extend(lines.map(|(i, line)| (i, split_csv_line(line))));
+
+    rows.into_iter()
+        .map(|(i, row)| {
+            let location = format!("line {}", i + 1);
+            let cell = |column: Option<usize>| {
+                column
+                    .and_then(|column| row.get(column))
+                    .and_then(|cell| cell.parse::<u64>().ok())
+            };
+
+            let user_id = parse_user_id(cell(Some(user_id_column)), &location)?;
+            let xp = resolve_xp(cell(xp_column), cell(level_column), curve, &location)?;
+
+            Ok((user_id, xp))
+        })
+        .collect()
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|cell| cell.trim().trim_matches('"').trim().to_string())
+        .collect()
+}
+
+fn parse_user_id(user_id: Option<u64>, location: &str) -> Result<UserId, LevelingError> {
+    match user_id {
+        Some(user_id) if user_id != 0 => Ok(UserId::new(user_id)),
+        _ => Err(LevelingError::InvalidImport(format!(
+            "{} has no valid user ID",
+            location
+        ))),
+    }
+}
+
+fn resolve_xp(
+    xp: Option<u64>,
+    level: Option<u64>,
+    curve: &Curve,
+    location: &str,
+) -> Result<u64, LevelingError> {
+    let xp = match (xp, level) {
+        (Some(xp), _) => xp,
+        (None, Some(level)) => curve.xp_for_level(level.min(MAX_LEVEL as u64) as u32),
+        (None, None) => {
+            return Err(LevelingError::InvalidImport(format!(
+                "{} has no XP or level",
+                location
+            )))
+        }
+    };
+
+    if xp > MAX_XP {
+        return Err(LevelingError::TooMuchXp(MAX_XP));
+    }
+
+    Ok(xp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leveling::types::LevelCurve;
+
+    fn linear() -> Curve {
+        Curve::new(LevelCurve::Linear, None).unwrap()
+    }
+
+    fn parse(text: &str) -> Result<Vec<(u64, u64)>, LevelingError> {
+        let mut entries: Vec<(u64, u64)> = parse_import(text.as_bytes(), &linear())?
+            .into_iter()
+            .map(|(user_id, xp)| (user_id.get(), xp))
+            .collect();
+        entries.sort_unstable();
+
+        Ok(entries)
+    }
+
+    #[test]
+    fn parses_csv() {
+        let cases = [
+            ("1,100\n2,250", vec![(1, 100), (2, 250)]),
+            ("user_id,xp\n1,100\n2,250\n", vec![(1, 100), (2, 250)]),
+            ("\"id\",\"XP\"\n\"1\",\"100\"", vec![(1, 100)]),
+            ("xp,user\n100,1\n\n250,2", vec![(1, 100), (2, 250)]),
+            ("user_id,level\n1,3", vec![(1, 300)]),
+            ("user_id,level,xp\n1,3,120", vec![(1, 120)]),
+            ("1,100\n1,50", vec![(1, 50)]),
+            ("\u{feff}user_id,xp\r\n1,100\r\n", vec![(1, 100)]),
+            ("", vec![]),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(parse(text).unwrap(), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn parses_json() {
+        let cases = [
+            (r#"[{"user_id": "1", "xp": 100}]"#, vec![(1, 100)]),
+            (
+                r#"[{"id": 1, "exp": 100.7}, {"userId": 2, "lvl": 2}]"#,
+                vec![(1, 100), (2, 200)],
+            ),
+            (
+                r#"{"page": 0, "players": [{"id": "1", "level": 5, "xp": 520}]}"#,
+                vec![(1, 520)],
+            ),
+            (
+                r#"{"1": {"xp": 100}, "2": {"level": 1}}"#,
+                vec![(1, 100), (2, 100)],
+            ),
+            (r#"{"1": {"user_id": "3", "xp": 100}}"#, vec![(3, 100)]),
+            (
+                r#"[{"id": 1, "xp": 100}, {"id": 1, "xp": 5}]"#,
+                vec![(1, 5)],
+            ),
+            (r#"[{"id": 1, "level": 5000}]"#, vec![(1, 100_000)]),
+            ("[]", vec![]),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(parse(text).unwrap(), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_imports() {
+        let cases = [
+            "user_id,xp\nabc,100",
+            "0,100",
+            "1",
+            "name,xp\nfoo,100",
+            "[{\"xp\": 100}]",
+            "[{\"id\": 1}]",
+            "[1, 2]",
+            "[{\"id\": 1, \"xp\": 100}",
+            "{\"1\": 100}",
+        ];
+
+        for text in cases {
+            assert!(
+                matches!(parse(text), Err(LevelingError::InvalidImport(_))),
+                "{:?}",
+                text
+            );
+        }
+
+        assert!(matches!(
+            parse_import(&[0xff, 0xfe, 0x00], &linear()),
+            Err(LevelingError::InvalidImport(_))
+        ));
+        assert!(matches!(
+            parse(&format!("1,{}", MAX_XP + 1)),
+            Err(LevelingError::TooMuchXp(_))
+        ));
+    }
+
+    #[test]
+    fn exports_can_be_imported_again() {
+        let entries = vec![
+            (UserId::new(1), 0),
+            (UserId::new(2), 250),
+            (UserId::new(u64::MAX), MAX_XP),
+        ];
+
+        for export in [export_csv(&entries), export_json(&entries).unwrap()] {
+            let mut imported = parse_import(export.as_bytes(), &linear()).unwrap();
+            imported.sort_unstable();
+            assert_eq!(imported, entries, "{}", export);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{ChannelId, RoleId};
+
+use crate::error::Error;
+
+/// How much XP each level takes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum LevelCurve {
+    /// Every level takes the same amount of XP.
+    Linear = 0,
+    /// Each level takes a bit more XP than the last.
+    Quadratic = 1,
+    /// Each level takes 10% more XP than the last.
+    Exponential = 2,
+    /// An expression of the server's own, see expression.rs.
+    Custom = 3,
+}
+
+impl TryFrom<u8> for LevelCurve {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(LevelCurve::Linear),
+            1 => Ok(LevelCurve::Quadratic),
+            2 => Ok(LevelCurve::Exponential),
+            3 => Ok(LevelCurve::Custom),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for LevelCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let curve_string = match self {
+            LevelCurve::Linear => "Linear",
+            LevelCurve::Quadratic => "Quadratic",
+            LevelCurve::Exponential => "Exponential",
+            LevelCurve::Custom => "Custom",
+        };
+        write!(f, "{}", curve_string)
+    }
+}
+
+/// What an XP multiplier applies to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum XpMultiplierKind {
+    Role = 0,
+    Channel = 1,
+}
+
+impl TryFrom<u8> for XpMultiplierKind {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(XpMultiplierKind::Role),
+            1 => Ok(XpMultiplierKind::Channel),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+/// How reward roles are given out as members level up.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum RewardMode {
+    /// Members keep the rewards of every level they've reached.
+    Stack = 0,
+    /// Members only keep the rewards of the highest level they've reached.
+    Replace = 1,
+}
+
+impl TryFrom<u8> for RewardMode {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RewardMode::Stack),
+            1 => Ok(RewardMode::Replace),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for RewardMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode_string = match self {
+            RewardMode::Stack => "Stack",
+            RewardMode::Replace => "Replace",
+        };
+        write!(f, "{}", mode_string)
+    }
+}
+
+/// What file type XP is exported as.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+}
+
+/// The colors of a guild's rank cards, as `0xRRGGBB`.
+#[derive(Copy, Clone, Debug)]
+pub struct RankCardTheme {
+    pub background: u32,
+    pub accent: u32,
+    pub text: u32,
+}
+
+impl Default for RankCardTheme {
+    fn default() -> Self {
+        Self {
+            background: 0x1e1e2e,
+            accent: crate::colors::BLUE,
+            text: 0xcdd6f4,
+        }
+    }
+}
+
+/// A guild's leveling settings.
+#[derive(Clone, Debug)]
+pub struct LevelingSettings {
+    pub enabled: bool,
+    /// The least XP a message can give.
+    pub min_xp: u32,
+    /// The most XP a message can give.
+    pub max_xp: u32,
+    /// How long members have to wait between messages that give XP.
+    pub cooldown: Duration,
+    pub curve: LevelCurve,
+    /// The expression of a custom curve, only used when `curve` is `Custom`.
+    pub expression: Option<String>,
+    pub reward_mode: RewardMode,
+    /// Where level ups are announced, if anywhere.
+    pub levelup_channel: Option<ChannelId>,
+    /// The template of level up announcements, see rewards.rs.
+    pub levelup_message: Option<String>,
+    pub card_theme: RankCardTheme,
+}
+
+impl Default for LevelingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_xp: 15,
+            max_xp: 25,
+            cooldown: Duration::from_secs(60),
+            curve: LevelCurve::Quadratic,
+            expression: None,
+            reward_mode: RewardMode::Stack,
+            levelup_channel: None,
+            levelup_message: None,
+            card_theme: RankCardTheme::default(),
+        }
+    }
+}
+
+/// A role or channel that changes how much XP messages give.
+/// A multiplier of 0 excludes the role or channel from gaining XP entirely.
+#[derive(Copy, Clone, Debug)]
+pub struct XpMultiplier {
+    pub target_id: u64,
+    pub kind: XpMultiplierKind,
+    pub multiplier: f64,
+}
+
+/// A role given to members once they reach a level.
+#[derive(Copy, Clone, Debug)]
+pub struct LevelReward {
+    pub level: u32,
+    pub role_id: RoleId,
+}
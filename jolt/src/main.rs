@@ -1,23 +1,53 @@
+mod antinuke;
+mod antiraid;
+mod automod;
+mod autoroles;
+mod backup;
+mod boosterroles;
 mod colors;
 mod commands;
 mod database;
+mod dehoist;
 mod error;
+mod events;
+mod font;
+mod leveling;
 mod messages;
+mod prune;
+mod reactionroles;
+mod rolemenus;
+mod serverlog;
+mod temproles;
+mod verification;
 
 use std::error::Error;
 use std::sync::Arc;
 use std::{fs, time::Instant};
 
 use commands::moderation::types::ModerationType;
+use commands::moderation::utilities::release_from_jail;
 // use commands::moderation::types::ModerationType;
 use poise::{serenity_prelude, PrefixFrameworkOptions};
 use serde::{Deserialize, Serialize};
 use serenity_prelude::GatewayIntents;
 use sqlx::sqlite;
 
+use commands::antinuke::*;
+use commands::antiraid::*;
+use commands::automod::*;
+use commands::autoroles::*;
+use commands::backup::*;
+use commands::boosterroles::*;
 use commands::configuration::*;
+use commands::dehoist::*;
+use commands::leveling::*;
 use commands::meta::*;
 use commands::moderation::*;
+use commands::prune::*;
+use commands::reactionroles::*;
+use commands::rolemenus::*;
+use commands::temproles::*;
+use commands::verification::*;
 
 // This gets the current git commit hash for development builds. See the build.rs file for more information on how this is obtained.
 const VERSION: &str = concat!("git-", env!("GIT_HASH"));
@@ -27,6 +57,8 @@ struct Config {
     token: String,
     prefix: String,
     database: String,
+    /// Path to a file of known phishing domains, one per line.
+    phishing_blocklist: Option<String>,
 }
 
 pub struct Handler;
@@ -56,6 +88,13 @@ pub struct Data {
     database: Arc<sqlx::SqlitePool>,
     config: Config,
     uptime: Instant,
+    server_log: serverlog::ServerLogState,
+    automod: automod::AutomodState,
+    antiraid: antiraid::AntiraidState,
+    antinuke: antinuke::AntinukeState,
+    verification: verification::VerificationState,
+    leveling: leveling::LevelingState,
+    prune: prune::PruneState,
 }
 
 // Some types that poise can use to make things a bit easier to use.
@@ -145,6 +184,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .await
         .expect("Couldn't run database migrations!");
 
+    // The phishing blocklist is optional, so the bot should still start up if it can't be read
+    let blocklist = match &config.phishing_blocklist {
+        Some(path) => automod::phishing::Blocklist::load(path).unwrap_or_else(|error| {
+            println!("{}", error);
+            Default::default()
+        }),
+        None => Default::default(),
+    };
+
     // Used in the info command to get the bot uptime. Declared here so that the timer starts ticking as the bot starts up
     let uptime = Instant::now();
     let framework = poise::Framework::builder()
@@ -165,12 +213,48 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 untimeout(),
                 mute(),
                 unmute(),
+                jail(),
+                unjail(),
+                nick(),
                 modlogs(),
 
+                // Automod
+                automod(),
+                filter(),
+                linkfilter(),
+                phishing(),
+                antiraid(),
+                agegate(),
+                antinuke(),
+                dehoist(),
+                verification(),
+
+                // Roles
+                reactionrole(),
+                rolemenu(),
+                autorole(),
+                temprole(),
+                temprole_list(),
+                boosterrole(),
+
+                // Leveling
+                rank(),
+                leaderboard(),
+                xp(),
+                levelreward(),
+
+                // Pruning
+                prune(),
+
+                // Backups
+                backup(),
+
                 // Configuration
                 test_command(),
                 mute_role(),
                 logs_channel(),
+                quarantine_role(),
+                jail_role(),
                 set_prefix(),
                 configure(),
                 // setup(),
@@ -180,10 +264,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 ..Default::default()
             },
             on_error: |error| Box::pin(on_error(error)),
+            event_handler: |ctx, event, framework, data| {
+                Box::pin(events::event_handler(ctx, event, framework, data))
+            },
             ..Default::default()
         })
         .token(&config.token)
-        .intents(GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT)
+        .intents(
+            GatewayIntents::non_privileged()
+                | GatewayIntents::MESSAGE_CONTENT
+                | GatewayIntents::GUILD_MEMBERS,
+        )
         .setup(
             move |ctx, _ready, framework| {
                 Box::pin(async move {
@@ -209,20 +300,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
                             // that has an expiry date less than the current Unix time (or earlier in time).
                             let current_time = serenity_prelude::Timestamp::now().unix_timestamp();
 
-                            let entries = sqlx::query!(
-                                "SELECT guild_id, user_id, moderation_type FROM moderations WHERE expiry_date < ? AND active = TRUE",
-                                current_time
-                            )
-                                .fetch_all(&*moderations_database)
+                            let entries = database::get_expired_moderations(&moderations_database, current_time)
                                 .await
                                 .expect("Failed to get current moderations!");
 
-                            for entry in entries {
-                                let guild_id = serenity_prelude::GuildId::new(entry.guild_id as u64);
-                                let user_id = serenity_prelude::UserId::new(entry.user_id as u64);
-                                let moderation_type: ModerationType = (entry.moderation_type as u8).try_into()
-                                    .expect("Failed to convert moderation_type into ModerationType enum!");
-
+                            for (guild_id, user_id, moderation_type) in entries {
                                 match moderation_type {
                                     ModerationType::Ban => guild_id.unban(&moderations_ctx.http, user_id).await
                                         .expect(format!("Failed to unban user {} from {}", user_id, guild_id).as_str()),
@@ -230,8 +312,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
                                         let role = database::get_mute_role(&moderations_database, guild_id)
                                             .await.expect("Failed to open database!");
                                         if let Some(role_id) = role {
-                                            let mut member = guild_id.member(&moderations_ctx, user_id).await.expect("Failed to get member!");
-                                            member.remove_role(&moderations_ctx.http, role_id).await.expect("Failed to remove role from member!");
+                                            temproles::take_away(&moderations_ctx, guild_id, user_id, role_id, "Mute expired").await;
+                                        }
+                                    },
+                                    ModerationType::Jail => {
+                                        if let Err(error) = release_from_jail(&moderations_ctx, &moderations_database, guild_id, user_id).await {
+                                            println!("Failed to unjail user {} in {}: {}", user_id, guild_id, error);
                                         }
                                     },
                                     _ => () // Either there is no timed event, or the event has a built-in expiry (timeout)
@@ -245,6 +331,43 @@ async fn main() -> Result<(), Box<dyn Error>> {
                                 .execute(&*moderations_database)
                                 .await
                                 .expect("Failed to write to database!");
+
+                            temproles::expire(&moderations_ctx, &moderations_database, current_time)
+                                .await
+                                .expect("Failed to get temporary roles!");
+
+                            verification::expire(&moderations_ctx, &moderations_database, current_time)
+                                .await
+                                .expect("Failed to get pending verifications!");
+
+                            let due_autoroles = database::take_due_autoroles(&moderations_database, current_time)
+                                .await
+                                .expect("Failed to get pending autoroles!");
+
+                            for (guild_id, user_id) in due_autoroles {
+                                if let Err(error) = autoroles::assign(&moderations_ctx, &moderations_database, guild_id, user_id, false).await {
+                                    println!("Failed to give autoroles to user {} in {}: {}", user_id, guild_id, error);
+                                }
+                            }
+
+                            let due_prunes = database::take_due_prune_guilds(
+                                &moderations_database,
+                                current_time - prune::PRUNE_INTERVAL.as_secs() as i64,
+                                current_time
+                            )
+                                .await
+                                .expect("Failed to get prune policies!");
+
+                            for guild_id in due_prunes {
+                                // Going through every member of a big guild takes a while, so it shouldn't hold up everything else
+                                let prune_ctx = moderations_ctx.clone();
+                                let prune_database = moderations_database.clone();
+                                tokio::spawn(async move {
+                                    if let Err(error) = prune::run(&prune_ctx, &prune_database, guild_id).await {
+                                        println!("Failed to prune inactive members in {}: {}", guild_id, error);
+                                    }
+                                });
+                            }
                         }
                     });
 
@@ -252,6 +375,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
                         database,
                         config: config.clone(),
                         uptime,
+                        server_log: Default::default(),
+                        automod: automod::AutomodState::new(blocklist),
+                        antiraid: Default::default(),
+                        antinuke: Default::default(),
+                        verification: Default::default(),
+                        leveling: Default::default(),
+                        prune: Default::default(),
                     }
                 )})
             }
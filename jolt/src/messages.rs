@@ -1,10 +1,14 @@
 use crate::colors;
+use crate::database;
 
 use poise::{
-    serenity_prelude::{self, CreateEmbed},
+    serenity_prelude::{self, CreateEmbed, CreateMessage},
     CreateReply,
 };
 
+// Discord caps embed fields at 1024 characters, anything past this gets cut off.
+const FIELD_LIMIT: usize = 1024;
+
 pub async fn send_error(
     ctx: &crate::Context<'_>,
     error: &str,
@@ -23,3 +27,47 @@ pub async fn send_error(
 
     Ok(())
 }
+
+/// Sends an embed to the logs channel of the guild.
+/// Nothing is sent if the guild has not set a logs channel.
+pub async fn send_log(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: serenity_prelude::GuildId,
+    embed: CreateEmbed,
+) -> Result<(), crate::error::Error> {
+    send_log_message(
+        cache_http,
+        database,
+        guild_id,
+        CreateMessage::default().embed(embed),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sends a full message to the logs channel of the guild, for logs that need more than an embed (like buttons).
+/// Returns `None` if the guild has not set a logs channel.
+pub async fn send_log_message(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: serenity_prelude::GuildId,
+    message: CreateMessage,
+) -> Result<Option<serenity_prelude::Message>, crate::error::Error> {
+    match database::get_logs_channel(database, guild_id).await? {
+        Some(channel) => Ok(Some(channel.send_message(cache_http, message).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Cuts text down to fit in an embed field, ending it with `...` if anything had to go.
+pub fn truncate_field(text: &str) -> String {
+    if text.chars().count() <= FIELD_LIMIT {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(FIELD_LIMIT - 3).collect();
+    truncated.push_str("...");
+    truncated
+}
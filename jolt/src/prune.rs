@@ -0,0 +1,310 @@
+/*
+Inactivity pruning
+
+Members count as active whenever they send a message, join a voice channel or react to something. That's remembered
+per guild, although only every few minutes per member so chatty members don't mean a database write per message.
+
+Guilds set up policies that add a role, remove a role or kick once a member has been inactive for long enough, which
+the scheduler in `main.rs` runs every hour. Nobody counts as inactive for longer than they've been in the guild, or for
+longer than the policy has existed, so members that were active before Jolt started keeping track aren't pruned straight
+away. Bots, the owner and anyone with an exempt role are never pruned.
+*/
+
+pub mod error;
+pub mod types;
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use poise::serenity_prelude::{self, CreateEmbed, GuildId, Member, RoleId, Timestamp, UserId};
+use tokio::sync::Mutex;
+
+use crate::colors;
+use crate::commands::moderation::types::ModerationType;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+use types::{PruneAction, PrunePolicy};
+
+/// How often each guild's policies are run.
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The shortest inactivity period a policy can have, so a typo can't kick half the server.
+pub const MIN_INACTIVITY: Duration = Duration::from_secs(24 * 60 * 60);
+/// The most policies a guild can have.
+pub const MAX_POLICIES: usize = 10;
+
+// Activity is only written down this often per member, which is plenty when periods are at least a day long
+const ACTIVITY_RESOLUTION: i64 = 5 * 60;
+const MEMBER_PAGE_SIZE: u64 = 1000;
+const PRUNE_REASON: &str = "Inactive";
+
+/// In-memory state that pruning needs to keep track of between events.
+#[derive(Default)]
+pub struct PruneState {
+    recorded: Mutex<HashMap<(GuildId, UserId), i64>>,
+}
+
+/// The members a policy applies to right now.
+pub struct PrunePlan {
+    pub policy: PrunePolicy,
+    /// Members that have been inactive for long enough and haven't been dealt with yet.
+    pub targets: Vec<UserId>,
+    /// Members that are active again and should have an inactivity role taken back.
+    pub returning: Vec<UserId>,
+}
+
+/// What a prune run ended up doing.
+#[derive(Default)]
+pub struct PruneReport {
+    pub roles_added: usize,
+    pub roles_removed: usize,
+    pub kicked: usize,
+    pub failed: usize,
+}
+
+/// Notes that a member was active. Called on messages, voice channel joins and reactions.
+pub async fn record_activity(
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    let current_time = Timestamp::now().unix_timestamp();
+
+    {
+        let mut recorded = data.prune.recorded.lock().await;
+        if let Some(last) = recorded.get(&(guild_id, user_id)) {
+            if current_time - last < ACTIVITY_RESOLUTION {
+                return Ok(());
+            }
+        }
+        recorded.insert((guild_id, user_id), current_time);
+
+        if recorded.len() > 10_000 {
+            recorded.retain(|_, last| current_time - *last < ACTIVITY_RESOLUTION);
+        }
+    }
+
+    database::set_member_activity(&data.database, guild_id, user_id, current_time).await?;
+
+    Ok(())
+}
+
+pub async fn member_remove(
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    data.prune
+        .recorded
+        .lock()
+        .await
+        .remove(&(guild_id, user_id));
+    database::remove_member_activity(&data.database, guild_id, user_id).await?;
+
+    Ok(())
+}
+
+pub async fn role_delete(
+    data: &crate::Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    database::remove_prune_policies_by_role(&data.database, guild_id, role_id).await?;
+    database::remove_prune_exempt_role(&data.database, guild_id, role_id).await?;
+
+    Ok(())
+}
+
+/// Works out who each of a guild's policies applies to, without changing anything.
+pub async fn plan(
+    ctx: &serenity_prelude::Context,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    policies: Vec<PrunePolicy>,
+) -> Result<Vec<PrunePlan>, Error> {
+    let mut plans: Vec<PrunePlan> = policies
+        .into_iter()
+        .map(|policy| PrunePlan {
+            policy,
+            targets: Vec::new(),
+            returning: Vec::new(),
+        })
+        .collect();
+    if plans.is_empty() {
+        return Ok(plans);
+    }
+
+    let current_time = Timestamp::now().unix_timestamp();
+    let owner_id = guild_id.to_partial_guild(ctx).await?.owner_id;
+    let exempt_roles = database::get_prune_exempt_roles(database, guild_id).await?;
+    let activity = database::get_member_activity(database, guild_id).await?;
+
+    let mut after: Option<UserId> = None;
+    loop {
+        let members = guild_id
+            .members(&ctx.http, Some(MEMBER_PAGE_SIZE), after)
+            .await?;
+        after = members.last().map(|member| member.user.id);
+
+        for member in &members {
+            if member.user.bot
+                || member.user.id == owner_id
+                || member.roles.iter().any(|role| exempt_roles.contains(role))
+            {
+                continue;
+            }
+
+            for plan in plans.iter_mut() {
+                check_member(plan, member, activity.get(&member.user.id), current_time);
+            }
+        }
+
+        if (members.len() as u64) < MEMBER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    Ok(plans)
+}
+
+fn check_member(
+    plan: &mut PrunePlan,
+    member: &Member,
+    last_active: Option<&i64>,
+    current_time: i64,
+) {
+    let policy = &plan.policy;
+
+    let last_seen = [
+        last_active.copied(),
+        member.joined_at.map(|joined_at| joined_at.unix_timestamp()),
+        Some(policy.created_at.unix_timestamp()),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .unwrap_or(current_time);
+    let inactive = current_time - last_seen >= policy.inactive_for.as_secs() as i64;
+    let has_role = policy
+        .role_id
+        .map_or(false, |role_id| member.roles.contains(&role_id));
+
+    match policy.action {
+        PruneAction::AddRole if inactive && !has_role => plan.targets.push(member.user.id),
+        PruneAction::AddRole if !inactive && has_role => plan.returning.push(member.user.id),
+        PruneAction::RemoveRole if inactive && has_role => plan.targets.push(member.user.id),
+        PruneAction::Kick if inactive => plan.targets.push(member.user.id),
+        _ => (),
+    }
+}
+
+/// Runs every policy of a guild, logging what happened if anything did.
+pub async fn run(
+    ctx: &serenity_prelude::Context,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+) -> Result<PruneReport, Error> {
+    let policies = database::get_prune_policies(database, guild_id).await?;
+    let plans = plan(ctx, database, guild_id, policies).await?;
+
+    let bot_id = ctx.cache.current_user().id;
+    let mut report = PruneReport::default();
+    let mut kicked = HashSet::new();
+
+    for plan in &plans {
+        let reason = format!(
+            "{} for {}",
+            PRUNE_REASON,
+            humantime::format_duration(plan.policy.inactive_for)
+        );
+
+        for user_id in &plan.targets {
+            if kicked.contains(user_id) {
+                continue;
+            }
+
+            let result = match (plan.policy.action, plan.policy.role_id) {
+                (PruneAction::AddRole, Some(role_id)) => ctx
+                    .http
+                    .add_member_role(guild_id, *user_id, role_id, Some(reason.as_str()))
+                    .await
+                    .map(|_| report.roles_added += 1),
+                (PruneAction::RemoveRole, Some(role_id)) => ctx
+                    .http
+                    .remove_member_role(guild_id, *user_id, role_id, Some(reason.as_str()))
+                    .await
+                    .map(|_| report.roles_removed += 1),
+                (PruneAction::Kick, _) => {
+                    match guild_id.kick_with_reason(ctx, *user_id, &reason).await {
+                        Ok(()) => {
+                            // The member is gone either way, so the rest of the run shouldn't stop over the modlogs
+                            if let Err(error) = database::add_moderation(
+                                database,
+                                guild_id,
+                                *user_id,
+                                bot_id,
+                                ModerationType::Kick,
+                                Timestamp::now(),
+                                None,
+                                Some(reason.as_str()),
+                            )
+                            .await
+                            {
+                                println!(
+                                    "Failed to add the prune kick of user {} in {} to the modlogs: {}",
+                                    user_id, guild_id, error
+                                );
+                            }
+                            kicked.insert(*user_id);
+                            report.kicked += 1;
+                            Ok(())
+                        }
+                        Err(error) => Err(error),
+                    }
+                }
+                // Role policies always have a role, see `commands::prune`
+                _ => Ok(()),
+            };
+
+            if result.is_err() {
+                report.failed += 1;
+            }
+        }
+
+        if let (PruneAction::AddRole, Some(role_id)) = (plan.policy.action, plan.policy.role_id) {
+            for user_id in &plan.returning {
+                match ctx
+                    .http
+                    .remove_member_role(guild_id, *user_id, role_id, Some("Active again"))
+                    .await
+                {
+                    Ok(()) => report.roles_removed += 1,
+                    Err(_) => report.failed += 1,
+                }
+            }
+        }
+    }
+
+    if report.roles_added + report.roles_removed + report.kicked + report.failed > 0 {
+        let mut embed = CreateEmbed::default()
+            .color(colors::BLUE)
+            .title("INACTIVITY PRUNE")
+            .field("Roles Added:", report.roles_added.to_string(), true)
+            .field("Roles Removed:", report.roles_removed.to_string(), true)
+            .field("Kicked:", report.kicked.to_string(), true);
+        if report.failed > 0 {
+            embed = embed.field(
+                "NOTE",
+                format!(
+                    "{} members could not be pruned, most likely because their highest role is above Jolt's.",
+                    report.failed
+                ),
+                false,
+            );
+        }
+        messages::send_log(ctx, database, guild_id, embed).await?;
+    }
+
+    Ok(report)
+}
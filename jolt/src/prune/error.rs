@@ -0,0 +1,23 @@
+/// Errors relating to inactivity pruning.
+#[derive(thiserror::Error, Debug)]
+pub enum PruneError {
+    /// An error representing a prune policy that doesn't exist.
+    #[error("There is no prune policy with that ID!")]
+    NotFound,
+    /// An error representing a role action without a role.
+    #[error("Adding or removing a role needs a role to add or remove!")]
+    MissingRole,
+    /// An error representing an inactivity period that is too short.
+    #[error("Members have to be inactive for at least a day before they can be pruned!")]
+    PeriodTooShort,
+    /// An error representing too many prune policies in one guild.
+    /// Contains one value, which is the most policies allowed.
+    #[error("There can be at most {0} prune policies!")]
+    TooManyPolicies(usize),
+    /// An error representing a role that isn't exempt from pruning.
+    #[error("That role is not exempt from pruning!")]
+    NotExempt,
+    /// An error representing someone without Kick Members setting up a policy that kicks.
+    #[error("You need the Kick Members permission to set up a policy that kicks!")]
+    MissingKickPermission,
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{RoleId, Timestamp};
+
+use crate::error::Error;
+
+/// What happens to members once they've been inactive for long enough.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum PruneAction {
+    /// Give them a role, which is taken away again once they're active.
+    #[name = "Add role"]
+    AddRole = 0,
+    /// Take a role away from them.
+    #[name = "Remove role"]
+    RemoveRole = 1,
+    /// Kick them from the server.
+    Kick = 2,
+}
+
+impl TryFrom<u8> for PruneAction {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PruneAction::AddRole),
+            1 => Ok(PruneAction::RemoveRole),
+            2 => Ok(PruneAction::Kick),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for PruneAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_string = match self {
+            PruneAction::AddRole => "Add role",
+            PruneAction::RemoveRole => "Remove role",
+            PruneAction::Kick => "Kick",
+        };
+        write!(f, "{}", action_string)
+    }
+}
+
+/// A rule for what to do with members that have been inactive for a while.
+#[derive(Copy, Clone, Debug)]
+pub struct PrunePolicy {
+    pub id: u64,
+    pub inactive_for: Duration,
+    pub action: PruneAction,
+    /// The role to add or remove. Always set unless the action is a kick.
+    pub role_id: Option<RoleId>,
+    /// Nobody counts as inactive for longer than the policy has existed.
+    pub created_at: Timestamp,
+}
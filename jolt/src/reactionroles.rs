@@ -0,0 +1,176 @@
+/*
+Reaction roles
+
+Binds emojis on any message to roles. What happens when a member reacts or unreacts depends on the mode of the
+binding (see types.rs). Bindings are stored per message, so unique reaction roles only exclude each other when they
+are on the same message.
+
+Temporary reaction roles go through temproles.rs, which takes them away again once they expire.
+Bindings are cleaned up when their message, channel or role is deleted.
+*/
+
+pub mod error;
+pub mod types;
+
+use poise::serenity_prelude::{self, ChannelId, GuildId, MessageId, Reaction, RoleId, UserId};
+
+use crate::database;
+use crate::error::Error;
+use crate::temproles;
+
+use types::{ReactionRole, ReactionRoleMode};
+
+const REACTION_ROLE_REASON: &str = "Reaction role";
+
+/// Finds the reaction role a reaction is for, along with who reacted and every other binding on the message.
+async fn find_binding(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    reaction: &Reaction,
+) -> Result<Option<(GuildId, UserId, ReactionRole, Vec<ReactionRole>)>, Error> {
+    let (guild_id, user_id) = match reaction.guild_id.zip(reaction.user_id) {
+        Some(ids) => ids,
+        None => return Ok(None),
+    };
+    if user_id == ctx.cache.current_user().id {
+        return Ok(None);
+    }
+
+    let bindings =
+        database::get_message_reaction_roles(&data.database, reaction.message_id).await?;
+    let binding = match bindings
+        .iter()
+        .find(|binding| binding.matches(&reaction.emoji))
+    {
+        Some(binding) => binding.clone(),
+        None => return Ok(None),
+    };
+
+    Ok(Some((guild_id, user_id, binding, bindings)))
+}
+
+pub async fn reaction_add(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    reaction: &Reaction,
+) -> Result<(), crate::DynError> {
+    let (guild_id, user_id, binding, bindings) = match find_binding(ctx, data, reaction).await? {
+        Some(found) => found,
+        None => return Ok(()),
+    };
+
+    let member = guild_id.member(ctx, user_id).await?;
+    if member.user.bot {
+        return Ok(());
+    }
+
+    match binding.mode {
+        ReactionRoleMode::Normal | ReactionRoleMode::Verify => {
+            add_role(ctx, guild_id, user_id, binding.role_id).await?
+        }
+        ReactionRoleMode::Drop => remove_role(ctx, guild_id, user_id, binding.role_id).await?,
+        ReactionRoleMode::Temporary => {
+            temproles::grant(
+                ctx,
+                &data.database,
+                guild_id,
+                user_id,
+                binding.role_id,
+                binding.duration.unwrap_or_default(),
+                REACTION_ROLE_REASON,
+            )
+            .await?;
+        }
+        ReactionRoleMode::Unique => {
+            // Swap out any other unique role from the same message, along with the reaction for it
+            for other in bindings.iter().filter(|other| {
+                other.mode == ReactionRoleMode::Unique && other.emoji != binding.emoji
+            }) {
+                if member.roles.contains(&other.role_id) {
+                    remove_role(ctx, guild_id, user_id, other.role_id).await?;
+                }
+
+                if let Some(reaction_type) = other.reaction_type() {
+                    // The member may not have reacted with it in the first place
+                    let _ = reaction
+                        .channel_id
+                        .delete_reaction(ctx, reaction.message_id, Some(user_id), reaction_type)
+                        .await;
+                }
+            }
+
+            add_role(ctx, guild_id, user_id, binding.role_id).await?
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn reaction_remove(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    reaction: &Reaction,
+) -> Result<(), crate::DynError> {
+    let (guild_id, user_id, binding, _) = match find_binding(ctx, data, reaction).await? {
+        Some(found) => found,
+        None => return Ok(()),
+    };
+
+    match binding.mode {
+        ReactionRoleMode::Normal | ReactionRoleMode::Unique => {
+            remove_role(ctx, guild_id, user_id, binding.role_id).await?
+        }
+        ReactionRoleMode::Temporary => {
+            remove_role(ctx, guild_id, user_id, binding.role_id).await?;
+            database::remove_temporary_role(&data.database, guild_id, user_id, binding.role_id)
+                .await?;
+        }
+        ReactionRoleMode::Verify | ReactionRoleMode::Drop => (),
+    }
+
+    Ok(())
+}
+
+pub async fn message_delete(data: &crate::Data, message_id: MessageId) -> Result<(), Error> {
+    database::remove_message_reaction_roles(&data.database, message_id).await?;
+
+    Ok(())
+}
+
+pub async fn channel_delete(data: &crate::Data, channel_id: ChannelId) -> Result<(), Error> {
+    database::remove_channel_reaction_roles(&data.database, channel_id).await?;
+
+    Ok(())
+}
+
+pub async fn role_delete(data: &crate::Data, role_id: RoleId) -> Result<(), Error> {
+    database::remove_role_reaction_roles(&data.database, role_id).await?;
+
+    Ok(())
+}
+
+async fn add_role(
+    ctx: &serenity_prelude::Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    ctx.http
+        .add_member_role(guild_id, user_id, role_id, Some(REACTION_ROLE_REASON))
+        .await?;
+
+    Ok(())
+}
+
+async fn remove_role(
+    ctx: &serenity_prelude::Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    ctx.http
+        .remove_member_role(guild_id, user_id, role_id, Some(REACTION_ROLE_REASON))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+/// Errors relating to reaction roles.
+#[derive(thiserror::Error, Debug)]
+pub enum ReactionRoleError {
+    /// An error representing an emoji that can't be reacted with.
+    /// Contains one value, which is the emoji that was given.
+    #[error("`{0}` is not an emoji Jolt can react with!")]
+    InvalidEmoji(String),
+    /// An error representing a temporary reaction role that wasn't given a length.
+    #[error("Temporary reaction roles need a length!")]
+    MissingDuration,
+    /// An error representing an emoji on a message that isn't bound to a role.
+    /// Contains one value, which is the emoji.
+    #[error("{0} is not a reaction role on that message!")]
+    NotFound(String),
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{ChannelId, MessageId, ReactionType, RoleId};
+
+use crate::error::Error;
+
+/// How a reaction role behaves when members react and unreact.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum ReactionRoleMode {
+    /// Reacting gives the role, unreacting takes it away.
+    Normal = 0,
+    /// Like normal, but members can only have one of the unique roles on a message at a time.
+    Unique = 1,
+    /// Reacting gives the role, unreacting does nothing.
+    Verify = 2,
+    /// Reacting takes the role away, unreacting does nothing.
+    Drop = 3,
+    /// Like normal, but the role is taken away again after a while.
+    Temporary = 4,
+}
+
+impl TryFrom<u8> for ReactionRoleMode {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ReactionRoleMode::Normal),
+            1 => Ok(ReactionRoleMode::Unique),
+            2 => Ok(ReactionRoleMode::Verify),
+            3 => Ok(ReactionRoleMode::Drop),
+            4 => Ok(ReactionRoleMode::Temporary),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for ReactionRoleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode_string = match self {
+            ReactionRoleMode::Normal => "Normal",
+            ReactionRoleMode::Unique => "Unique",
+            ReactionRoleMode::Verify => "Verify",
+            ReactionRoleMode::Drop => "Drop",
+            ReactionRoleMode::Temporary => "Temporary",
+        };
+        write!(f, "{}", mode_string)
+    }
+}
+
+/// An emoji on a message that is bound to a role.
+#[derive(Clone, Debug)]
+pub struct ReactionRole {
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    /// The emoji as it would be typed in a message, like `<:name:id>` for custom emojis.
+    pub emoji: String,
+    pub role_id: RoleId,
+    pub mode: ReactionRoleMode,
+    /// How long the role is kept for, only used by temporary reaction roles.
+    pub duration: Option<Duration>,
+}
+
+impl ReactionRole {
+    pub fn reaction_type(&self) -> Option<ReactionType> {
+        ReactionType::try_from(self.emoji.as_str()).ok()
+    }
+
+    /// Whether or not a reaction is for this reaction role's emoji.
+    pub fn matches(&self, emoji: &ReactionType) -> bool {
+        self.reaction_type().map_or(false, |reaction_type| {
+            emoji_key(&reaction_type) == emoji_key(emoji)
+        })
+    }
+}
+
+/// Gets what identifies an emoji. Custom emojis are compared by ID, since they can be renamed.
+pub fn emoji_key(emoji: &ReactionType) -> String {
+    match emoji {
+        ReactionType::Custom { id, .. } => id.to_string(),
+        // Some clients send the variation selector and some don't
+        ReactionType::Unicode(emoji) => emoji.trim_end_matches('\u{FE0F}').to_string(),
+        _ => emoji.to_string(),
+    }
+}
@@ -0,0 +1,366 @@
+/*
+Role menus
+
+Messages with buttons or a select menu that members use to pick their own roles. Menus are configured first and then
+posted, and posting again after changing a menu edits the message that's already up.
+
+Nothing is kept in memory: every component carries the ID of its menu (and role, for buttons) in its custom ID, so
+menus keep working across restarts and are handled here no matter which message they're on.
+*/
+
+pub mod error;
+pub mod types;
+
+use poise::serenity_prelude::{
+    self, ButtonStyle, ChannelId, ComponentInteraction, ComponentInteractionDataKind,
+    CreateActionRow, CreateButton, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CreateSelectMenu, CreateSelectMenuKind,
+    CreateSelectMenuOption, EditMessage, GuildId, Interaction, MessageId, RoleId,
+};
+
+use crate::colors;
+use crate::database;
+use crate::error::Error;
+
+use types::{RoleMenu, RoleMenuStyle};
+
+const CUSTOM_ID_PREFIX: &str = "rolemenu";
+const ROLE_MENU_REASON: &str = "Role menu";
+
+/// The most roles a menu can hold, which is as many buttons or select menu options as Discord allows on a message.
+pub const MAX_OPTIONS: usize = 25;
+const BUTTONS_PER_ROW: usize = 5;
+
+fn embed(menu: &RoleMenu) -> CreateEmbed {
+    let mut description = menu
+        .options
+        .iter()
+        .map(|option| match &option.emoji {
+            Some(emoji) => format!("{} <@&{}>", emoji, option.role_id),
+            None => format!("<@&{}>", option.role_id),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut rules = Vec::new();
+    if let Some(required_role) = menu.required_role {
+        rules.push(format!("Requires <@&{}>", required_role));
+    }
+    if menu.min_roles > 0 {
+        rules.push(format!("Pick at least {}", menu.min_roles));
+    }
+    if let Some(max_roles) = menu.max_roles {
+        rules.push(format!("Pick at most {}", max_roles));
+    }
+    if !rules.is_empty() {
+        description.push_str(&format!("\n\n*{}*", rules.join(" • ")));
+    }
+
+    CreateEmbed::default()
+        .color(colors::BLUE)
+        .title(&menu.title)
+        .description(description)
+}
+
+fn components(menu: &RoleMenu) -> Vec<CreateActionRow> {
+    match menu.style {
+        RoleMenuStyle::Buttons => menu
+            .options
+            .chunks(BUTTONS_PER_ROW)
+            .map(|row| {
+                CreateActionRow::Buttons(
+                    row.iter()
+                        .map(|option| {
+                            let mut button = CreateButton::new(format!(
+                                "{}:{}:{}",
+                                CUSTOM_ID_PREFIX, menu.id, option.role_id
+                            ))
+                            .label(&option.label)
+                            .style(ButtonStyle::Secondary);
+                            if let Some(emoji) = option.reaction_type() {
+                                button = button.emoji(emoji);
+                            }
+                            button
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+        RoleMenuStyle::SelectMenu => {
+            let options = menu
+                .options
+                .iter()
+                .map(|option| {
+                    let mut menu_option =
+                        CreateSelectMenuOption::new(&option.label, option.role_id.to_string());
+                    if let Some(emoji) = option.reaction_type() {
+                        menu_option = menu_option.emoji(emoji);
+                    }
+                    menu_option
+                })
+                .collect();
+            let max_values = menu
+                .max_roles
+                .map_or(menu.options.len(), |max_roles| max_roles as usize)
+                .min(menu.options.len());
+
+            vec![CreateActionRow::SelectMenu(
+                CreateSelectMenu::new(
+                    format!("{}:{}", CUSTOM_ID_PREFIX, menu.id),
+                    CreateSelectMenuKind::String { options },
+                )
+                .placeholder("Pick your roles")
+                .min_values(menu.min_roles.min(max_values as u32) as u8)
+                .max_values(max_values as u8),
+            )]
+        }
+    }
+}
+
+/// Posts a menu in a channel, returning the message it was posted as.
+pub async fn post(
+    cache_http: impl serenity_prelude::CacheHttp,
+    menu: &RoleMenu,
+    channel_id: ChannelId,
+) -> Result<MessageId, Error> {
+    let message = channel_id
+        .send_message(
+            cache_http,
+            CreateMessage::default()
+                .embed(embed(menu))
+                .components(components(menu)),
+        )
+        .await?;
+
+    Ok(message.id)
+}
+
+/// Updates an already posted menu to match its current settings.
+/// Returns whether or not the menu was still up to be updated.
+pub async fn refresh(
+    cache_http: impl serenity_prelude::CacheHttp,
+    menu: &RoleMenu,
+) -> Result<bool, Error> {
+    let (channel_id, message_id) = match menu.message {
+        Some(message) => message,
+        None => return Ok(false),
+    };
+
+    let result = channel_id
+        .edit_message(
+            cache_http,
+            message_id,
+            EditMessage::default()
+                .embed(embed(menu))
+                .components(components(menu)),
+        )
+        .await;
+
+    Ok(result.is_ok())
+}
+
+pub async fn handle_interaction(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    let component = match interaction {
+        Interaction::Component(component) => component,
+        _ => return Ok(()),
+    };
+
+    // Custom IDs look like `rolemenu:<menu>` for select menus and `rolemenu:<menu>:<role>` for buttons
+    let mut parts = component.data.custom_id.split(':');
+    if parts.next() != Some(CUSTOM_ID_PREFIX) {
+        return Ok(());
+    }
+    let menu_id = match parts.next().and_then(|id| id.parse::<u64>().ok()) {
+        Some(menu_id) => menu_id,
+        None => return Ok(()),
+    };
+    let button_role = parts
+        .next()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(RoleId::new);
+
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+
+    let menu = match database::get_role_menu(&data.database, guild_id, menu_id).await? {
+        Some(menu) => menu,
+        None => return respond_error(ctx, component, "This role menu no longer exists.").await,
+    };
+
+    let member = guild_id.member(ctx, component.user.id).await?;
+    if let Some(required_role) = menu.required_role {
+        if !member.roles.contains(&required_role) {
+            return respond_error(
+                ctx,
+                component,
+                &format!("You need <@&{}> to use this role menu.", required_role),
+            )
+            .await;
+        }
+    }
+
+    let current: Vec<RoleId> = menu
+        .options
+        .iter()
+        .map(|option| option.role_id)
+        .filter(|role_id| member.roles.contains(role_id))
+        .collect();
+
+    let wanted: Vec<RoleId> = match (&component.data.kind, button_role) {
+        (ComponentInteractionDataKind::Button, Some(role_id)) => {
+            if current.contains(&role_id) {
+                current
+                    .iter()
+                    .copied()
+                    .filter(|id| *id != role_id)
+                    .collect()
+            } else {
+                current.iter().copied().chain([role_id]).collect()
+            }
+        }
+        (ComponentInteractionDataKind::StringSelect { values }, None) => values
+            .iter()
+            .filter_map(|value| value.parse::<u64>().ok())
+            .map(RoleId::new)
+            .collect(),
+        _ => return Ok(()),
+    };
+    // Anything that isn't part of the menu (like a role that has since been taken out of it) is ignored
+    let wanted: Vec<RoleId> = wanted
+        .into_iter()
+        .filter(|role_id| menu.options.iter().any(|option| option.role_id == *role_id))
+        .collect();
+
+    // Buttons only change one role at a time, so members have to be able to work their way up to the minimum
+    if (wanted.len() as u32) < menu.min_roles && wanted.len() < current.len() {
+        return respond_error(
+            ctx,
+            component,
+            &format!(
+                "You need to keep at least {} roles from this menu.",
+                menu.min_roles
+            ),
+        )
+        .await;
+    }
+    if let Some(max_roles) = menu.max_roles {
+        if wanted.len() as u32 > max_roles {
+            return respond_error(
+                ctx,
+                component,
+                &format!("You can only have {} roles from this menu.", max_roles),
+            )
+            .await;
+        }
+    }
+
+    let added: Vec<RoleId> = wanted
+        .iter()
+        .copied()
+        .filter(|role_id| !current.contains(role_id))
+        .collect();
+    let removed: Vec<RoleId> = current
+        .iter()
+        .copied()
+        .filter(|role_id| !wanted.contains(role_id))
+        .collect();
+
+    for role_id in &added {
+        ctx.http
+            .add_member_role(
+                guild_id,
+                component.user.id,
+                *role_id,
+                Some(ROLE_MENU_REASON),
+            )
+            .await?;
+    }
+    for role_id in &removed {
+        ctx.http
+            .remove_member_role(
+                guild_id,
+                component.user.id,
+                *role_id,
+                Some(ROLE_MENU_REASON),
+            )
+            .await?;
+    }
+
+    let mut embed = CreateEmbed::default().color(colors::GREEN);
+    if added.is_empty() && removed.is_empty() {
+        embed = embed.description("Your roles are already up to date.");
+    }
+    if !added.is_empty() {
+        embed = embed.field("Added", mention_roles(&added), true);
+    }
+    if !removed.is_empty() {
+        embed = embed.field("Removed", mention_roles(&removed), true);
+    }
+
+    respond(ctx, component, embed).await
+}
+
+fn mention_roles(role_ids: &[RoleId]) -> String {
+    role_ids
+        .iter()
+        .map(|role_id| format!("<@&{}>", role_id))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+async fn respond_error(
+    ctx: &serenity_prelude::Context,
+    component: &ComponentInteraction,
+    description: &str,
+) -> Result<(), Error> {
+    respond(
+        ctx,
+        component,
+        CreateEmbed::default()
+            .color(colors::RED)
+            .title("Error!")
+            .description(description),
+    )
+    .await
+}
+
+async fn respond(
+    ctx: &serenity_prelude::Context,
+    component: &ComponentInteraction,
+    embed: CreateEmbed,
+) -> Result<(), Error> {
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::default()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn message_delete(data: &crate::Data, message_id: MessageId) -> Result<(), Error> {
+    database::clear_role_menu_message(&data.database, message_id).await?;
+
+    Ok(())
+}
+
+pub async fn role_delete(
+    data: &crate::Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    database::remove_role_menu_role(&data.database, guild_id, role_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+/// Errors relating to role menus.
+#[derive(thiserror::Error, Debug)]
+pub enum RoleMenuError {
+    /// An error representing a role menu that doesn't exist in the guild.
+    /// Contains one value, which is the ID of the menu.
+    #[error("Role menu {0} does not exist!")]
+    NotFound(u64),
+    /// An error representing a role that isn't part of a role menu.
+    #[error("That role is not in the role menu!")]
+    RoleNotInMenu,
+    /// An error representing a role menu that can't fit any more roles.
+    /// Contains one value, which is the most roles a menu can hold.
+    #[error("Role menus can hold at most {0} roles!")]
+    TooManyRoles(usize),
+    /// An error representing a role menu being posted without any roles in it.
+    #[error("The role menu has no roles in it! Use `rolemenu add` first.")]
+    Empty,
+    /// An error representing a minimum that is above the maximum.
+    #[error("The minimum amount of roles can't be above the maximum!")]
+    InvalidLimits,
+    /// An error representing an emoji that can't be put on a button or menu option.
+    /// Contains one value, which is the emoji that was given.
+    #[error("`{0}` is not an emoji Jolt can use!")]
+    InvalidEmoji(String),
+}
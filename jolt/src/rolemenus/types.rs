@@ -0,0 +1,69 @@
+use poise::serenity_prelude::{ChannelId, MessageId, ReactionType, RoleId};
+
+use crate::error::Error;
+
+/// What members pick their roles with.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum RoleMenuStyle {
+    /// One button per role, which toggles it.
+    Buttons = 0,
+    /// A single dropdown, where the selected roles are the ones the member ends up with.
+    #[name = "Select Menu"]
+    SelectMenu = 1,
+}
+
+impl TryFrom<u8> for RoleMenuStyle {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RoleMenuStyle::Buttons),
+            1 => Ok(RoleMenuStyle::SelectMenu),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for RoleMenuStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let style_string = match self {
+            RoleMenuStyle::Buttons => "Buttons",
+            RoleMenuStyle::SelectMenu => "Select Menu",
+        };
+        write!(f, "{}", style_string)
+    }
+}
+
+/// A single role that can be picked from a role menu.
+#[derive(Clone, Debug)]
+pub struct RoleMenuOption {
+    pub role_id: RoleId,
+    pub label: String,
+    pub emoji: Option<String>,
+}
+
+impl RoleMenuOption {
+    pub fn reaction_type(&self) -> Option<ReactionType> {
+        self.emoji
+            .as_deref()
+            .and_then(|emoji| ReactionType::try_from(emoji).ok())
+    }
+}
+
+/// A menu of roles that members can pick from.
+#[derive(Clone, Debug)]
+pub struct RoleMenu {
+    pub id: u64,
+    pub title: String,
+    pub style: RoleMenuStyle,
+    /// The fewest roles from the menu a member can be left with.
+    pub min_roles: u32,
+    /// The most roles from the menu a member can have, if there is a limit.
+    pub max_roles: Option<u32>,
+    /// A role members need before they can use the menu at all.
+    pub required_role: Option<RoleId>,
+    /// Where the menu has been posted, if it has been.
+    pub message: Option<(ChannelId, MessageId)>,
+    pub options: Vec<RoleMenuOption>,
+}
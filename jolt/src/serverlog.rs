@@ -0,0 +1,526 @@
+/*
+Server configuration logging
+
+Records changes to the structure of a server (channels, roles, permission overwrites, emojis and the guild settings
+themselves) to the logs channel. Each log shows what the value was before and after, along with whoever made the
+change according to the audit log, so admins can figure out who changed what after the fact.
+*/
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use poise::serenity_prelude::{
+    self, Action, ChannelAction, ChannelOverwriteAction, CreateEmbed, Emoji, EmojiAction, EmojiId,
+    Guild, GuildChannel, GuildId, PartialGuild, PermissionOverwrite, PermissionOverwriteType,
+    Permissions, Role, RoleAction, RoleId, Timestamp, UserId,
+};
+use tokio::sync::Mutex;
+
+use crate::colors;
+use crate::error::Error;
+use crate::messages;
+
+// Audit log entries older than this are about some earlier change to the same target
+const MAX_ENTRY_AGE: Duration = Duration::from_secs(60);
+
+/// Emojis are the one thing the cache can't give us an old copy of, so we keep our own.
+#[derive(Default)]
+pub struct ServerLogState {
+    emojis: Mutex<HashMap<GuildId, HashMap<EmojiId, Emoji>>>,
+}
+
+pub async fn snapshot_emojis(
+    data: &crate::Data,
+    guild_id: GuildId,
+    emojis: &HashMap<EmojiId, Emoji>,
+) {
+    data.server_log
+        .emojis
+        .lock()
+        .await
+        .insert(guild_id, emojis.clone());
+}
+
+/// Looks through the most recent audit log entries to find who is responsible for a change to `target_id`.
+/// Only recent entries for one of `actions` count, so an older or unrelated change to the same target isn't credited.
+/// The audit log entry isn't guaranteed to exist by the time the gateway event arrives, so this can come up empty.
+async fn responsible_user(
+    ctx: &serenity_prelude::Context,
+    guild_id: GuildId,
+    target_id: u64,
+    actions: &[Action],
+) -> Option<UserId> {
+    let audit_logs = guild_id
+        .audit_logs(&ctx.http, None, None, None, Some(10))
+        .await
+        .ok()?;
+
+    audit_logs
+        .entries
+        .into_iter()
+        .filter(|entry| {
+            Timestamp::now().unix_timestamp() - entry.id.created_at().unix_timestamp()
+                <= MAX_ENTRY_AGE.as_secs() as i64
+        })
+        .find(|entry| {
+            entry.target_id.map(|id| id.get()) == Some(target_id)
+                && actions
+                    .iter()
+                    .any(|action| action.num() == entry.action.num())
+        })
+        .map(|entry| entry.user_id)
+}
+
+/// A change to log, along with what to look for in the audit log to find who made it.
+struct Change<'a> {
+    target_id: u64,
+    actions: &'a [Action],
+    title: &'a str,
+    description: String,
+    changes: Vec<String>,
+    color: u32,
+}
+
+async fn send_change_log(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    change: Change<'_>,
+) -> Result<(), Error> {
+    let responsible = responsible_user(ctx, guild_id, change.target_id, change.actions).await;
+
+    let mut embed = CreateEmbed::default()
+        .color(change.color)
+        .title(change.title)
+        .description(change.description)
+        .field(
+            "Responsible User:",
+            match responsible {
+                Some(user_id) => format!("<@{}>", user_id),
+                None => String::from("Unknown"),
+            },
+            true,
+        );
+
+    if !change.changes.is_empty() {
+        embed = embed.field(
+            "Changes:",
+            messages::truncate_field(&change.changes.join("\n")),
+            false,
+        );
+    }
+
+    messages::send_log(ctx, &data.database, guild_id, embed).await
+}
+
+/// Formats a single before/after line, or nothing at all if the value didn't change.
+fn diff_line<T: PartialEq + std::fmt::Debug>(name: &str, old: &T, new: &T) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    Some(format!("**{}:** `{:?}` → `{:?}`", name, old, new))
+}
+
+fn permission_diff(old: Permissions, new: Permissions) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let granted = (new & !old).get_permission_names();
+    let revoked = (old & !new).get_permission_names();
+
+    let mut lines = Vec::new();
+    if !granted.is_empty() {
+        lines.push(format!("+ {}", granted.join(", ")));
+    }
+    if !revoked.is_empty() {
+        lines.push(format!("- {}", revoked.join(", ")));
+    }
+
+    Some(lines.join("\n"))
+}
+
+fn overwrite_target(kind: &PermissionOverwriteType) -> String {
+    match kind {
+        PermissionOverwriteType::Member(user_id) => format!("<@{}>", user_id),
+        PermissionOverwriteType::Role(role_id) => format!("<@&{}>", role_id),
+        _ => String::from("Unknown"),
+    }
+}
+
+fn overwrite_diff(old: &[PermissionOverwrite], new: &[PermissionOverwrite]) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    for overwrite in new {
+        let target = overwrite_target(&overwrite.kind);
+        match old.iter().find(|old| old.kind == overwrite.kind) {
+            Some(previous) => {
+                if let Some(diff) = permission_diff(previous.allow, overwrite.allow) {
+                    changes.push(format!("**Allowed for {}:**\n{}", target, diff));
+                }
+                if let Some(diff) = permission_diff(previous.deny, overwrite.deny) {
+                    changes.push(format!("**Denied for {}:**\n{}", target, diff));
+                }
+            }
+            None => changes.push(format!("**Added overwrite for {}**", target)),
+        }
+    }
+
+    for overwrite in old {
+        if !new.iter().any(|new| new.kind == overwrite.kind) {
+            changes.push(format!(
+                "**Removed overwrite for {}**",
+                overwrite_target(&overwrite.kind)
+            ));
+        }
+    }
+
+    changes
+}
+
+pub async fn channel_create(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    channel: &GuildChannel,
+) -> Result<(), Error> {
+    send_change_log(
+        ctx,
+        data,
+        channel.guild_id,
+        Change {
+            target_id: channel.id.get(),
+            actions: &[Action::Channel(ChannelAction::Create)],
+            title: "Channel Created",
+            description: format!("<#{}> (`{}`, {:?})", channel.id, channel.name, channel.kind),
+            changes: overwrite_diff(&[], &channel.permission_overwrites),
+            color: colors::GREEN,
+        },
+    )
+    .await
+}
+
+pub async fn channel_delete(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    channel: &GuildChannel,
+) -> Result<(), Error> {
+    send_change_log(
+        ctx,
+        data,
+        channel.guild_id,
+        Change {
+            target_id: channel.id.get(),
+            actions: &[Action::Channel(ChannelAction::Delete)],
+            title: "Channel Deleted",
+            description: format!("`{}` ({:?})", channel.name, channel.kind),
+            changes: Vec::new(),
+            color: colors::RED,
+        },
+    )
+    .await
+}
+
+pub async fn channel_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    old: Option<&GuildChannel>,
+    new: &GuildChannel,
+) -> Result<(), Error> {
+    // Without the old channel there's nothing to compare against.
+    let old = match old {
+        Some(old) => old,
+        None => return Ok(()),
+    };
+
+    let mut changes: Vec<String> = [
+        diff_line("Name", &old.name, &new.name),
+        diff_line("Topic", &old.topic, &new.topic),
+        diff_line("Category", &old.parent_id, &new.parent_id),
+        diff_line("NSFW", &old.nsfw, &new.nsfw),
+        diff_line(
+            "Slowmode",
+            &old.rate_limit_per_user,
+            &new.rate_limit_per_user,
+        ),
+        diff_line("Bitrate", &old.bitrate, &new.bitrate),
+        diff_line("User Limit", &old.user_limit, &new.user_limit),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    changes.extend(overwrite_diff(
+        &old.permission_overwrites,
+        &new.permission_overwrites,
+    ));
+
+    // Position changes get sent for every channel that gets shifted around, which is mostly noise
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    send_change_log(
+        ctx,
+        data,
+        new.guild_id,
+        Change {
+            target_id: new.id.get(),
+            actions: &[
+                Action::Channel(ChannelAction::Update),
+                Action::ChannelOverwrite(ChannelOverwriteAction::Create),
+                Action::ChannelOverwrite(ChannelOverwriteAction::Update),
+                Action::ChannelOverwrite(ChannelOverwriteAction::Delete),
+            ],
+            title: "Channel Updated",
+            description: format!("<#{}>", new.id),
+            changes,
+            color: colors::BLUE,
+        },
+    )
+    .await
+}
+
+pub async fn role_create(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    role: &Role,
+) -> Result<(), Error> {
+    send_change_log(
+        ctx,
+        data,
+        role.guild_id,
+        Change {
+            target_id: role.id.get(),
+            actions: &[Action::Role(RoleAction::Create)],
+            title: "Role Created",
+            description: format!("<@&{}> (`{}`)", role.id, role.name),
+            changes: permission_diff(Permissions::empty(), role.permissions)
+                .into_iter()
+                .map(|diff| format!("**Permissions:**\n{}", diff))
+                .collect(),
+            color: colors::GREEN,
+        },
+    )
+    .await
+}
+
+pub async fn role_delete(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    role_id: RoleId,
+    role: Option<&Role>,
+) -> Result<(), Error> {
+    send_change_log(
+        ctx,
+        data,
+        guild_id,
+        Change {
+            target_id: role_id.get(),
+            actions: &[Action::Role(RoleAction::Delete)],
+            title: "Role Deleted",
+            description: match role {
+                Some(role) => format!("`{}` ({})", role.name, role_id),
+                None => format!("{}", role_id),
+            },
+            changes: Vec::new(),
+            color: colors::RED,
+        },
+    )
+    .await
+}
+
+pub async fn role_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    old: Option<&Role>,
+    new: &Role,
+) -> Result<(), Error> {
+    let old = match old {
+        Some(old) => old,
+        None => return Ok(()),
+    };
+
+    let mut changes: Vec<String> = [
+        diff_line("Name", &old.name, &new.name),
+        diff_line("Color", &old.colour.hex(), &new.colour.hex()),
+        diff_line("Hoisted", &old.hoist, &new.hoist),
+        diff_line("Mentionable", &old.mentionable, &new.mentionable),
+        diff_line("Icon", &old.icon, &new.icon),
+        diff_line("Emoji", &old.unicode_emoji, &new.unicode_emoji),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if let Some(diff) = permission_diff(old.permissions, new.permissions) {
+        changes.push(format!("**Permissions:**\n{}", diff));
+    }
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    send_change_log(
+        ctx,
+        data,
+        new.guild_id,
+        Change {
+            target_id: new.id.get(),
+            actions: &[Action::Role(RoleAction::Update)],
+            title: "Role Updated",
+            description: format!("<@&{}>", new.id),
+            changes,
+            color: colors::BLUE,
+        },
+    )
+    .await
+}
+
+pub async fn emojis_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    guild_id: GuildId,
+    current_state: &HashMap<EmojiId, Emoji>,
+) -> Result<(), Error> {
+    let old_state = data
+        .server_log
+        .emojis
+        .lock()
+        .await
+        .insert(guild_id, current_state.clone())
+        .unwrap_or_default();
+
+    for (emoji_id, emoji) in current_state {
+        match old_state.get(emoji_id) {
+            None => {
+                send_change_log(
+                    ctx,
+                    data,
+                    guild_id,
+                    Change {
+                        target_id: emoji_id.get(),
+                        actions: &[Action::Emoji(EmojiAction::Create)],
+                        title: "Emoji Created",
+                        description: format!("{} (`:{}:`)", emoji, emoji.name),
+                        changes: Vec::new(),
+                        color: colors::GREEN,
+                    },
+                )
+                .await?
+            }
+            Some(old) if old.name != emoji.name || old.roles != emoji.roles => {
+                send_change_log(
+                    ctx,
+                    data,
+                    guild_id,
+                    Change {
+                        target_id: emoji_id.get(),
+                        actions: &[Action::Emoji(EmojiAction::Update)],
+                        title: "Emoji Updated",
+                        description: format!("{}", emoji),
+                        changes: [
+                            diff_line("Name", &old.name, &emoji.name),
+                            diff_line("Roles", &old.roles, &emoji.roles),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect(),
+                        color: colors::BLUE,
+                    },
+                )
+                .await?
+            }
+            Some(_) => (),
+        }
+    }
+
+    for (emoji_id, emoji) in &old_state {
+        if !current_state.contains_key(emoji_id) {
+            send_change_log(
+                ctx,
+                data,
+                guild_id,
+                Change {
+                    target_id: emoji_id.get(),
+                    actions: &[Action::Emoji(EmojiAction::Delete)],
+                    title: "Emoji Deleted",
+                    description: format!("`:{}:` ({})", emoji.name, emoji_id),
+                    changes: Vec::new(),
+                    color: colors::RED,
+                },
+            )
+            .await?
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn guild_update(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    old: Option<&Guild>,
+    new: &PartialGuild,
+) -> Result<(), Error> {
+    let old = match old {
+        Some(old) => old,
+        None => return Ok(()),
+    };
+
+    let changes: Vec<String> = [
+        diff_line("Name", &old.name, &new.name),
+        diff_line("Description", &old.description, &new.description),
+        diff_line("Icon", &old.icon, &new.icon),
+        diff_line("Banner", &old.banner, &new.banner),
+        diff_line("Owner", &old.owner_id, &new.owner_id),
+        diff_line(
+            "Verification Level",
+            &old.verification_level,
+            &new.verification_level,
+        ),
+        diff_line(
+            "Explicit Content Filter",
+            &old.explicit_content_filter,
+            &new.explicit_content_filter,
+        ),
+        diff_line(
+            "Default Notifications",
+            &old.default_message_notifications,
+            &new.default_message_notifications,
+        ),
+        diff_line("2FA Requirement", &old.mfa_level, &new.mfa_level),
+        diff_line(
+            "System Channel",
+            &old.system_channel_id,
+            &new.system_channel_id,
+        ),
+        diff_line(
+            "Rules Channel",
+            &old.rules_channel_id,
+            &new.rules_channel_id,
+        ),
+        diff_line("Vanity URL", &old.vanity_url_code, &new.vanity_url_code),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    send_change_log(
+        ctx,
+        data,
+        new.id,
+        Change {
+            target_id: new.id.get(),
+            actions: &[Action::GuildUpdate],
+            title: "Server Settings Updated",
+            description: format!("**{}**", new.name),
+            changes,
+            color: colors::BLUE,
+        },
+    )
+    .await
+}
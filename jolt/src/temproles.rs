@@ -0,0 +1,82 @@
+/*
+Temporary roles
+
+Roles that are taken away again after a while. They're kept in the database with their expiry date, and the scheduler in
+`main.rs` takes them away once it passes, so they survive restarts. The same goes for timed mutes, which use the mute
+role instead of a row in here since they're tracked as moderations.
+*/
+
+pub mod error;
+
+use std::time::Duration;
+
+use poise::serenity_prelude::{self, GuildId, RoleId, Timestamp, UserId};
+
+use crate::database;
+
+/// Gives a member a role and schedules it to be taken away again, returning when that will happen.
+/// Giving a role the member already has temporarily pushes its expiry date back.
+pub async fn grant(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    user_id: UserId,
+    role_id: RoleId,
+    duration: Duration,
+    reason: &str,
+) -> Result<Timestamp, crate::DynError> {
+    let expiry_date = Timestamp::from_unix_timestamp(
+        Timestamp::now().unix_timestamp() + duration.as_secs() as i64,
+    )?;
+
+    cache_http
+        .http()
+        .add_member_role(guild_id, user_id, role_id, Some(reason))
+        .await?;
+    database::add_temporary_role(database, guild_id, user_id, role_id, expiry_date).await?;
+
+    Ok(expiry_date)
+}
+
+/// Takes a role away from a member once its time is up.
+/// Failures are only printed, since the member may have left or the role may be gone, neither of which matter anymore.
+pub async fn take_away(
+    cache_http: impl serenity_prelude::CacheHttp,
+    guild_id: GuildId,
+    user_id: UserId,
+    role_id: RoleId,
+    reason: &str,
+) {
+    if let Err(error) = cache_http
+        .http()
+        .remove_member_role(guild_id, user_id, role_id, Some(reason))
+        .await
+    {
+        println!(
+            "Failed to remove timed role {} from user {} in {}: {}",
+            role_id, user_id, guild_id, error
+        );
+    }
+}
+
+/// Takes away every temporary role that has expired. Called by the scheduler.
+pub async fn expire(
+    cache_http: impl serenity_prelude::CacheHttp,
+    database: &sqlx::SqlitePool,
+    current_time: i64,
+) -> sqlx::Result<()> {
+    for (guild_id, user_id, role_id) in
+        database::take_expired_temporary_roles(database, current_time).await?
+    {
+        take_away(
+            &cache_http,
+            guild_id,
+            user_id,
+            role_id,
+            "Temporary role expired",
+        )
+        .await;
+    }
+
+    Ok(())
+}
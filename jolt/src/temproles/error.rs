@@ -0,0 +1,8 @@
+/// Errors relating to temporary roles.
+#[derive(thiserror::Error, Debug)]
+pub enum TempRoleError {
+    /// An error representing a member that already has a role for good.
+    /// Giving it temporarily would take it away from them once it expires.
+    #[error("That member already has the role!")]
+    AlreadyHasRole,
+}
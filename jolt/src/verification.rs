@@ -0,0 +1,523 @@
+/*
+Member verification
+
+New members get the unverified role and a message in the verification channel with a button to start their
+challenge. Depending on the guild, that's either an image of letters to type or a sum to solve (see render.rs), or a
+question the guild wrote itself. Getting it right swaps the unverified role for the verified one. Getting it wrong too
+many times, or not finishing within the time limit, gets the member kicked.
+
+Deadlines are kept in the database and enforced by the scheduler in `main.rs`, so restarts don't let anyone off the
+hook. The challenge a member was last shown is only kept in memory though, so after a restart they start over.
+*/
+
+pub mod error;
+pub mod render;
+pub mod types;
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude::{
+    self, ActionRowComponent, ComponentInteraction, CreateActionRow, CreateAttachment,
+    CreateButton, CreateEmbed, CreateInputText, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CreateModal, GuildId, InputTextStyle,
+    Interaction, Member, ModalInteraction, Timestamp, UserId,
+};
+use tokio::sync::Mutex;
+
+use crate::autoroles;
+use crate::colors;
+use crate::commands::moderation::types::ModerationType;
+use crate::database;
+use crate::error::Error;
+use crate::messages;
+
+use types::{VerificationChallenge, VerificationSettings};
+
+const START_BUTTON: &str = "verification_start";
+const ANSWER_BUTTON: &str = "verification_answer_open";
+const ANSWER_MODAL: &str = "verification_answer";
+const ANSWER_INPUT: &str = "verification_answer_input";
+
+const CAPTCHA_FILE: &str = "captcha.png";
+
+// How many wrong answers a member gets before they're kicked
+const MAX_ATTEMPTS: u32 = 3;
+
+struct Pending {
+    /// The answer to the captcha the member was last shown, if any.
+    answer: Option<String>,
+    attempts: u32,
+}
+
+#[derive(Default)]
+pub struct VerificationState {
+    pending: Mutex<HashMap<(GuildId, UserId), Pending>>,
+}
+
+/// Starts verification for a new member, if the guild has it enabled.
+pub async fn member_join(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    member: &Member,
+) -> Result<(), Error> {
+    let guild_id = member.guild_id;
+    let user_id = member.user.id;
+    if member.user.bot {
+        return Ok(());
+    }
+
+    let settings = match database::get_verification_settings(&data.database, guild_id).await? {
+        Some(settings) => settings,
+        None => return Ok(()),
+    };
+
+    // Someone kicked or banned by anti-raid or the age gate in the meantime doesn't need to verify
+    let member = match guild_id.member(ctx, user_id).await {
+        Ok(member) => member,
+        Err(_) => return Ok(()),
+    };
+    member.add_role(ctx, settings.unverified_role).await?;
+
+    // Rejoining replaces the old deadline, so a timer from an earlier join can't kick them early
+    let deadline = Timestamp::from_unix_timestamp(
+        Timestamp::now().unix_timestamp() + settings.time_limit.as_secs() as i64,
+    )?;
+    database::add_pending_verification(
+        &data.database,
+        guild_id,
+        user_id,
+        deadline.unix_timestamp(),
+    )
+    .await?;
+    data.verification.pending.lock().await.insert(
+        (guild_id, user_id),
+        Pending {
+            answer: None,
+            attempts: 0,
+        },
+    );
+    settings
+        .channel
+        .send_message(
+            ctx,
+            CreateMessage::default()
+                .content(format!("<@{}>", user_id))
+                .embed(
+                    CreateEmbed::default()
+                        .color(colors::BLUE)
+                        .title("Verification")
+                        .description(format!(
+                            "Welcome! Press the button below to verify yourself and get access to the rest of the \
+                            server. You have until <t:{}:t> (<t:{}:R>).",
+                            deadline.unix_timestamp(),
+                            deadline.unix_timestamp()
+                        )),
+                )
+                .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                    START_BUTTON,
+                )
+                .label("Verify")])]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Forgets a member that left before verifying.
+pub async fn member_remove(
+    data: &crate::Data,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    database::remove_pending_verification(&data.database, guild_id, user_id).await?;
+    data.verification
+        .pending
+        .lock()
+        .await
+        .remove(&(guild_id, user_id));
+
+    Ok(())
+}
+
+/// Kicks every member that didn't verify before their deadline. Called by the scheduler.
+pub async fn expire(
+    ctx: &serenity_prelude::Context,
+    database: &sqlx::SqlitePool,
+    current_time: i64,
+) -> Result<(), Error> {
+    for (guild_id, user_id) in database::take_due_verifications(database, current_time).await? {
+        let settings = match database::get_verification_settings(database, guild_id).await? {
+            Some(settings) => settings,
+            None => continue,
+        };
+
+        // Members that left in the meantime, or that a moderator let in by hand, are left alone
+        let member = match guild_id.member(ctx, user_id).await {
+            Ok(member) => member,
+            Err(_) => continue,
+        };
+        if !member.roles.contains(&settings.unverified_role) {
+            continue;
+        }
+
+        if let Err(error) = fail(ctx, database, guild_id, user_id, "Did not verify in time").await {
+            println!(
+                "Failed to kick user {} for not verifying: {}",
+                user_id, error
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles the buttons and modal used to verify.
+pub async fn handle_interaction(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    match interaction {
+        Interaction::Component(component) => match component.data.custom_id.as_str() {
+            START_BUTTON | ANSWER_BUTTON => start_challenge(ctx, data, component).await,
+            _ => Ok(()),
+        },
+        Interaction::Modal(modal) if modal.data.custom_id == ANSWER_MODAL => {
+            check_answer(ctx, data, modal).await
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn start_challenge(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    component: &ComponentInteraction,
+) -> Result<(), Error> {
+    let guild_id = match component.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+    let user_id = component.user.id;
+
+    let settings = database::get_verification_settings(&data.database, guild_id).await?;
+    let is_pending = database::is_verification_pending(&data.database, guild_id, user_id).await?;
+    let mut pending = data.verification.pending.lock().await;
+    let entry = if is_pending {
+        // The challenge is only kept in memory, so anyone still verifying from before a restart starts over
+        Some(pending.entry((guild_id, user_id)).or_insert(Pending {
+            answer: None,
+            attempts: 0,
+        }))
+    } else {
+        None
+    };
+    let (settings, entry) = match settings.zip(entry) {
+        Some(found) => found,
+        None => {
+            drop(pending);
+            return respond(
+                ctx,
+                component,
+                CreateEmbed::default()
+                    .color(colors::RED)
+                    .title("Error!")
+                    .description("You don't need to verify!"),
+            )
+            .await;
+        }
+    };
+
+    // The image has to be shown in a message, and the answer typed in a modal afterwards
+    if component.data.custom_id == START_BUTTON {
+        if let Some(captcha) = render::generate(settings.challenge) {
+            entry.answer = Some(captcha.answer);
+            drop(pending);
+
+            component
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::default()
+                            .embed(
+                                CreateEmbed::default()
+                                    .color(colors::BLUE)
+                                    .description(match settings.challenge {
+                                        VerificationChallenge::Arithmetic => {
+                                            "Solve the sum in the image, then press the button to answer."
+                                        }
+                                        _ => {
+                                            "Type the letters and numbers in the image, then press the button to \
+                                            answer."
+                                        }
+                                    })
+                                    .image(format!("attachment://{}", CAPTCHA_FILE)),
+                            )
+                            .add_file(CreateAttachment::bytes(captcha.image, CAPTCHA_FILE))
+                            .components(vec![CreateActionRow::Buttons(vec![
+                                CreateButton::new(ANSWER_BUTTON).label("Answer"),
+                            ])])
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+    }
+    drop(pending);
+
+    let label = match (&settings.challenge, &settings.question) {
+        (VerificationChallenge::Question, Some((question, _))) => question.clone(),
+        _ => String::from("Answer"),
+    };
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Modal(
+                CreateModal::new(ANSWER_MODAL, "Verification").components(vec![
+                    CreateActionRow::InputText(CreateInputText::new(
+                        InputTextStyle::Short,
+                        label,
+                        ANSWER_INPUT,
+                    )),
+                ]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn check_answer(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    modal: &ModalInteraction,
+) -> Result<(), Error> {
+    let guild_id = match modal.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(()),
+    };
+    let user_id = modal.user.id;
+
+    let given = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == ANSWER_INPUT => {
+                input.value.clone()
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let settings = match database::get_verification_settings(&data.database, guild_id).await? {
+        Some(settings) => settings,
+        None => return Ok(()),
+    };
+    if !database::is_verification_pending(&data.database, guild_id, user_id).await? {
+        return Ok(());
+    }
+
+    let mut pending = data.verification.pending.lock().await;
+    let entry = match pending.get_mut(&(guild_id, user_id)) {
+        Some(entry) => entry,
+        None => return Ok(()),
+    };
+
+    let expected = match settings.challenge {
+        VerificationChallenge::Question => {
+            settings.question.as_ref().map(|(_, answer)| answer.clone())
+        }
+        _ => entry.answer.take(),
+    };
+    let correct = expected
+        .map(|expected| matches(&given, &expected))
+        .unwrap_or(false);
+
+    if correct {
+        pending.remove(&(guild_id, user_id));
+        drop(pending);
+        database::remove_pending_verification(&data.database, guild_id, user_id).await?;
+        return pass(ctx, data, &settings, modal, guild_id, user_id).await;
+    }
+
+    entry.attempts += 1;
+    let attempts = entry.attempts;
+    if attempts < MAX_ATTEMPTS {
+        drop(pending);
+        let remaining = MAX_ATTEMPTS - attempts;
+        modal
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::default()
+                        .embed(
+                            CreateEmbed::default()
+                                .color(colors::RED)
+                                .title("Wrong answer!")
+                                .description(format!(
+                                    "Press the Verify button to try again. You have {} {} left.",
+                                    remaining,
+                                    if remaining == 1 { "try" } else { "tries" }
+                                )),
+                        )
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    pending.remove(&(guild_id, user_id));
+    drop(pending);
+    database::remove_pending_verification(&data.database, guild_id, user_id).await?;
+    modal
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+    fail(
+        ctx,
+        &data.database,
+        guild_id,
+        user_id,
+        "Failed the verification challenge",
+    )
+    .await
+}
+
+async fn pass(
+    ctx: &serenity_prelude::Context,
+    data: &crate::Data,
+    settings: &VerificationSettings,
+    modal: &ModalInteraction,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    let member = guild_id.member(ctx, user_id).await?;
+    member.remove_role(ctx, settings.unverified_role).await?;
+    member.add_role(ctx, settings.verified_role).await?;
+    autoroles::member_verified(ctx, data, &member).await?;
+
+    modal
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::default()
+                    .embed(
+                        CreateEmbed::default()
+                            .color(colors::GREEN)
+                            .description("You have been verified, welcome!"),
+                    )
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    messages::send_log(
+        ctx,
+        &data.database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::GREEN)
+            .title("VERIFICATION")
+            .description(format!("User <@{}> has been verified", user_id)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn fail(
+    ctx: &serenity_prelude::Context,
+    database: &sqlx::SqlitePool,
+    guild_id: GuildId,
+    user_id: UserId,
+    reason: &str,
+) -> Result<(), Error> {
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| String::from("the server"));
+    let dm = match user_id.create_dm_channel(ctx).await {
+        Ok(channel) => channel
+            .send_message(
+                ctx,
+                CreateMessage::default().embed(
+                    CreateEmbed::default()
+                        .color(colors::RED)
+                        .field(
+                            "Zap!",
+                            format!("You have been kicked from **{}**", guild_name),
+                            true,
+                        )
+                        .field("Reason:", reason, false),
+                ),
+            )
+            .await
+            .map(|_| ()),
+        Err(error) => Err(error),
+    };
+
+    if dm.is_err() {
+        println!("Failed to DM user {} about verification", user_id);
+    }
+
+    guild_id.kick_with_reason(ctx, user_id, reason).await?;
+
+    database::add_moderation(
+        database,
+        guild_id,
+        user_id,
+        ctx.cache.current_user().id,
+        ModerationType::Kick,
+        Timestamp::now(),
+        None,
+        Some(reason),
+    )
+    .await?;
+
+    messages::send_log(
+        ctx,
+        database,
+        guild_id,
+        CreateEmbed::default()
+            .color(colors::RED)
+            .title("VERIFICATION")
+            .description(format!("User <@{}> has been kicked", user_id))
+            .field("Reason:", reason, false),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Compares answers loosely, so that capitalization and extra spaces don't count as mistakes.
+fn matches(given: &str, expected: &str) -> bool {
+    let normalize = |text: &str| {
+        text.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .to_lowercase()
+    };
+
+    normalize(given) == normalize(expected)
+}
+
+async fn respond(
+    ctx: &serenity_prelude::Context,
+    component: &ComponentInteraction,
+    embed: CreateEmbed,
+) -> Result<(), Error> {
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::default()
+                    .embed(embed)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+/// Errors relating to member verification.
+#[derive(thiserror::Error, Debug)]
+pub enum VerificationError {
+    /// An error representing a command that needs verification, but it isn't enabled in the guild.
+    #[error("Verification is not enabled! Use `verification enable` first.")]
+    NotEnabled,
+    /// An error representing the question challenge being picked before a question was set.
+    #[error("There is no question set! Use `verification question` first.")]
+    QuestionNotSet,
+    /// An error representing the same role being used as both the unverified and verified role.
+    #[error("The unverified and verified roles have to be different!")]
+    SameRoles,
+    /// An error representing a time limit that is too short to complete a challenge in, or too long to wait for.
+    /// Contains two values, the shortest and longest time limits that are allowed.
+    #[error(
+        "Time limits have to be between {} and {}",
+        humantime::format_duration(*.0),
+        humantime::format_duration(*.1)
+    )]
+    InvalidTimeLimit(std::time::Duration, std::time::Duration),
+}
@@ -0,0 +1,152 @@
+/*
+Captcha rendering
+
+Challenges are drawn locally with a tiny built-in bitmap font (see font.rs), so no font files or outside services are
+needed. Every character gets its own color and offset, and the image is covered in noise lines and speckles, which is
+enough to stop the simple bots that raids are usually made of.
+*/
+
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, Rgb, RgbImage};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+use super::types::VerificationChallenge;
+
+// Letters and numbers that are easy to mix up (0 and O, 1 and I, 5 and S...) are left out
+const TEXT_ALPHABET: &[u8] = b"ACDEFGHJKLMNPQRTUVWXY34679";
+const TEXT_LENGTH: usize = 6;
+
+const SCALE: u32 = 6;
+const SPACING: u32 = 10;
+const PADDING: u32 = 20;
+// Room for characters to be pushed up and down
+const JITTER: u32 = 16;
+
+const NOISE_LINES: usize = 6;
+const NOISE_SPECKLES: usize = 400;
+
+/// A generated challenge, along with the answer to it.
+pub struct Captcha {
+    pub image: Vec<u8>,
+    pub answer: String,
+}
+
+/// Generates a new captcha for the given challenge.
+/// Returns `None` for challenges that don't use an image.
+pub fn generate(challenge: VerificationChallenge) -> Option<Captcha> {
+    let mut rng = rand::thread_rng();
+
+    let (text, answer) = match challenge {
+        VerificationChallenge::Text => {
+            let text: String = (0..TEXT_LENGTH)
+                .map(|_| *TEXT_ALPHABET.choose(&mut rng).unwrap() as char)
+                .collect();
+            (text.clone(), text)
+        }
+        VerificationChallenge::Arithmetic => {
+            let a: u32 = rng.gen_range(2..=20);
+            let b: u32 = rng.gen_range(2..=20);
+            // Subtraction always has the bigger number first so that the answer is never negative
+            if rng.gen_bool(0.5) {
+                (format!("{} + {} = ?", a, b), (a + b).to_string())
+            } else {
+                let (a, b) = (a.max(b), a.min(b));
+                (format!("{} - {} = ?", a, b), (a - b).to_string())
+            }
+        }
+        VerificationChallenge::Question => return None,
+    };
+
+    Some(Captcha {
+        image: render(&text, &mut rng),
+        answer,
+    })
+}
+
+fn render(text: &str, rng: &mut impl Rng) -> Vec<u8> {
+    let characters = text.chars().count() as u32;
+    let glyph_width = GLYPH_WIDTH * SCALE;
+    let glyph_height = GLYPH_HEIGHT * SCALE;
+    let width = PADDING * 2 + characters * glyph_width + characters.saturating_sub(1) * SPACING;
+    let height = PADDING * 2 + glyph_height + JITTER;
+
+    let mut image = RgbImage::from_pixel(width, height, Rgb([235, 235, 240]));
+
+    for (index, character) in text.chars().enumerate() {
+        let color = Rgb([
+            rng.gen_range(0..120),
+            rng.gen_range(0..120),
+            rng.gen_range(0..120),
+        ]);
+        let x = PADDING + index as u32 * (glyph_width + SPACING) + rng.gen_range(0..4);
+        let y = PADDING + rng.gen_range(0..=JITTER);
+        font::draw_glyph(&mut image, character, x, y, SCALE, color);
+    }
+
+    for _ in 0..NOISE_LINES {
+        let color = Rgb([
+            rng.gen_range(60..200),
+            rng.gen_range(60..200),
+            rng.gen_range(60..200),
+        ]);
+        let start = (
+            rng.gen_range(0..width) as i64,
+            rng.gen_range(0..height) as i64,
+        );
+        let end = (
+            rng.gen_range(0..width) as i64,
+            rng.gen_range(0..height) as i64,
+        );
+        draw_line(&mut image, start, end, color);
+    }
+
+    for _ in 0..NOISE_SPECKLES {
+        let shade = rng.gen_range(0..200);
+        image.put_pixel(
+            rng.gen_range(0..width),
+            rng.gen_range(0..height),
+            Rgb([shade, shade, shade]),
+        );
+    }
+
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+        .expect("Failed to encode captcha!");
+    bytes
+}
+
+// Bresenham's line algorithm, drawn two pixels thick so it actually gets in the way
+fn draw_line(image: &mut RgbImage, start: (i64, i64), end: (i64, i64), color: Rgb<u8>) {
+    let (mut x, mut y) = start;
+    let dx = (end.0 - x).abs();
+    let dy = -(end.1 - y).abs();
+    let step_x = if x < end.0 { 1 } else { -1 };
+    let step_y = if y < end.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        for (px, py) in [(x, y), (x + 1, y), (x, y + 1)] {
+            if px >= 0 && py >= 0 && (px as u32) < image.width() && (py as u32) < image.height() {
+                image.put_pixel(px as u32, py as u32, color);
+            }
+        }
+
+        if x == end.0 && y == end.1 {
+            break;
+        }
+        let doubled = error * 2;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
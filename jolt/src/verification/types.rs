@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use poise::serenity_prelude::{ChannelId, RoleId};
+
+use crate::error::Error;
+
+/// The kind of challenge new members have to complete.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum VerificationChallenge {
+    #[name = "Text (type the letters in an image)"]
+    Text = 0,
+    #[name = "Arithmetic (solve the sum in an image)"]
+    Arithmetic = 1,
+    #[name = "Question (answer the server's question)"]
+    Question = 2,
+}
+
+impl TryFrom<u8> for VerificationChallenge {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VerificationChallenge::Text),
+            1 => Ok(VerificationChallenge::Arithmetic),
+            2 => Ok(VerificationChallenge::Question),
+            _ => Err(Error::IntEnumError),
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationChallenge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let challenge_string = match self {
+            VerificationChallenge::Text => "Text",
+            VerificationChallenge::Arithmetic => "Arithmetic",
+            VerificationChallenge::Question => "Question",
+        };
+        write!(f, "{}", challenge_string)
+    }
+}
+
+/// The verification settings of a guild.
+/// New members get `unverified_role` and have `time_limit` to complete the challenge in `channel`.
+#[derive(Clone, Debug)]
+pub struct VerificationSettings {
+    pub channel: ChannelId,
+    pub unverified_role: RoleId,
+    pub verified_role: RoleId,
+    pub challenge: VerificationChallenge,
+    pub time_limit: Duration,
+    /// The question and its answer, only used by `VerificationChallenge::Question`.
+    pub question: Option<(String, String)>,
+}
@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE automod_rules (
+    guild_id BIGINT NOT NULL,
+    rule TINYINT NOT NULL,
+    threshold INTEGER NOT NULL,
+    interval_seconds BIGINT NOT NULL,
+    action TINYINT NOT NULL,
+    action_duration BIGINT,
+
+    PRIMARY KEY (guild_id, rule)
+)
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS filter_exemptions;
+DROP TABLE IF EXISTS filter_rules
\ No newline at end of file
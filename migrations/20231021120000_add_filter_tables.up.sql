@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE filter_rules (
+    id INTEGER NOT NULL,
+    guild_id BIGINT NOT NULL,
+    kind TINYINT NOT NULL,
+    pattern TEXT NOT NULL,
+    action TINYINT NOT NULL,
+    action_duration BIGINT,
+
+    PRIMARY KEY (id, guild_id)
+);
+
+CREATE TABLE filter_exemptions (
+    guild_id BIGINT NOT NULL,
+    target_id BIGINT NOT NULL,
+    kind TINYINT NOT NULL,
+
+    PRIMARY KEY (guild_id, target_id)
+)
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS invite_whitelist;
+DROP TABLE IF EXISTS link_filter_domains;
+DROP TABLE IF EXISTS link_filter_settings
\ No newline at end of file
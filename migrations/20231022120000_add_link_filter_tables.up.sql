@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TABLE link_filter_settings (
+    guild_id BIGINT NOT NULL UNIQUE,
+    filter_invites BOOLEAN NOT NULL,
+    filter_urls BOOLEAN NOT NULL,
+    action TINYINT NOT NULL,
+    action_duration BIGINT
+);
+
+CREATE TABLE link_filter_domains (
+    guild_id BIGINT NOT NULL,
+    domain TEXT NOT NULL,
+    allowed BOOLEAN NOT NULL,
+
+    PRIMARY KEY (guild_id, domain)
+);
+
+CREATE TABLE invite_whitelist (
+    guild_id BIGINT NOT NULL,
+    whitelisted_guild_id BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, whitelisted_guild_id)
+)
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE guild_settings DROP COLUMN phishing_timeout;
+ALTER TABLE guild_settings DROP COLUMN phishing_protection
\ No newline at end of file
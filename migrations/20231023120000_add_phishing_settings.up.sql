@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE guild_settings ADD COLUMN phishing_protection BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE guild_settings ADD COLUMN phishing_timeout BIGINT
\ No newline at end of file
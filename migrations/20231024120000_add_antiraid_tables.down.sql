@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE guild_settings DROP COLUMN quarantine_role_id;
+DROP TABLE IF EXISTS antiraid_lock_channels;
+DROP TABLE IF EXISTS antiraid_settings
@@ -0,0 +1,17 @@
+-- Add up migration script here
+CREATE TABLE antiraid_settings (
+    guild_id BIGINT NOT NULL UNIQUE,
+    join_threshold INTEGER NOT NULL,
+    interval_seconds BIGINT NOT NULL,
+    action TINYINT NOT NULL,
+    verification_level TINYINT NOT NULL
+);
+
+CREATE TABLE antiraid_lock_channels (
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, channel_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN quarantine_role_id BIGINT
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE guild_settings DROP COLUMN account_age_action;
+ALTER TABLE guild_settings DROP COLUMN min_account_age
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE guild_settings ADD COLUMN min_account_age BIGINT;
+ALTER TABLE guild_settings ADD COLUMN account_age_action TINYINT
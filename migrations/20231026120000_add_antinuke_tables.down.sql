@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS antinuke_trusted_users;
+DROP TABLE IF EXISTS antinuke_settings
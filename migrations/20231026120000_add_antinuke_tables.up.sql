@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE antinuke_settings (
+    guild_id BIGINT NOT NULL UNIQUE,
+    threshold INTEGER NOT NULL,
+    interval_seconds BIGINT NOT NULL
+);
+
+CREATE TABLE antinuke_trusted_users (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, user_id)
+)
@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE snapshots (
+    id INTEGER NOT NULL,
+    guild_id BIGINT NOT NULL,
+    created_by BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    version INTEGER NOT NULL,
+    data TEXT NOT NULL,
+
+    PRIMARY KEY (id, guild_id)
+)
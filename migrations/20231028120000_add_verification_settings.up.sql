@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE verification_settings (
+    guild_id BIGINT NOT NULL UNIQUE,
+    channel_id BIGINT NOT NULL,
+    unverified_role_id BIGINT NOT NULL,
+    verified_role_id BIGINT NOT NULL,
+    challenge TINYINT NOT NULL,
+    time_limit_seconds BIGINT NOT NULL,
+    question TEXT,
+    answer TEXT
+)
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS jailed_roles;
+ALTER TABLE guild_settings DROP COLUMN jail_channel_id;
+ALTER TABLE guild_settings DROP COLUMN jail_role_id
@@ -0,0 +1,11 @@
+-- Add up migration script here
+ALTER TABLE guild_settings ADD COLUMN jail_role_id BIGINT;
+ALTER TABLE guild_settings ADD COLUMN jail_channel_id BIGINT;
+
+CREATE TABLE jailed_roles (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, user_id, role_id)
+)
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE guild_settings ADD COLUMN dehoist BOOLEAN NOT NULL DEFAULT FALSE
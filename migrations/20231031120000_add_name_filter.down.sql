@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE guild_settings DROP COLUMN name_filter_action
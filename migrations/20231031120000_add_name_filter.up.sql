@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE guild_settings ADD COLUMN name_filter_action TINYINT
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE guild_settings ADD COLUMN ghost_ping_detection BOOLEAN NOT NULL DEFAULT FALSE
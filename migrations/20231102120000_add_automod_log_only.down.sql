@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE link_filter_settings DROP COLUMN log_only;
+ALTER TABLE filter_rules DROP COLUMN log_only;
+ALTER TABLE automod_rules DROP COLUMN log_only
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE automod_rules ADD COLUMN log_only BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE filter_rules ADD COLUMN log_only BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE link_filter_settings ADD COLUMN log_only BOOLEAN NOT NULL DEFAULT FALSE
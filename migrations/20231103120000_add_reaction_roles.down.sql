@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS temporary_roles;
+DROP TABLE IF EXISTS reaction_roles
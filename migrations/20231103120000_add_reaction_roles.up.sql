@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE reaction_roles (
+    guild_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    message_id BIGINT NOT NULL,
+    emoji TEXT NOT NULL,
+    role_id BIGINT NOT NULL,
+    mode TINYINT NOT NULL,
+    duration BIGINT,
+
+    PRIMARY KEY (message_id, emoji)
+);
+
+CREATE TABLE temporary_roles (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+    expiry_date BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, user_id, role_id)
+)
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS role_menu_options;
+DROP TABLE IF EXISTS role_menus
@@ -0,0 +1,25 @@
+-- Add up migration script here
+CREATE TABLE role_menus (
+    id INTEGER NOT NULL,
+    guild_id BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    style TINYINT NOT NULL,
+    min_roles INTEGER NOT NULL,
+    max_roles INTEGER,
+    required_role_id BIGINT,
+    channel_id BIGINT,
+    message_id BIGINT,
+
+    PRIMARY KEY (id, guild_id)
+);
+
+CREATE TABLE role_menu_options (
+    guild_id BIGINT NOT NULL,
+    menu_id INTEGER NOT NULL,
+    role_id BIGINT NOT NULL,
+    label TEXT NOT NULL,
+    emoji TEXT,
+    position INTEGER NOT NULL,
+
+    PRIMARY KEY (guild_id, menu_id, role_id)
+)
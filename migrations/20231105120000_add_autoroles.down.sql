@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE guild_settings DROP COLUMN autorole_after_screening;
+ALTER TABLE guild_settings DROP COLUMN autorole_delay;
+DROP TABLE IF EXISTS pending_autoroles;
+DROP TABLE IF EXISTS autoroles
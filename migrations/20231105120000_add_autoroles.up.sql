@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE autoroles (
+    guild_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+    bots BOOLEAN NOT NULL,
+
+    PRIMARY KEY (guild_id, role_id, bots)
+);
+
+CREATE TABLE pending_autoroles (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    assign_date BIGINT,
+
+    PRIMARY KEY (guild_id, user_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN autorole_delay BIGINT;
+ALTER TABLE guild_settings ADD COLUMN autorole_after_screening BOOLEAN NOT NULL DEFAULT FALSE
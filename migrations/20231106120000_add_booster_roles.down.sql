@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE guild_settings DROP COLUMN booster_role_anchor_id;
+DROP TABLE IF EXISTS booster_roles
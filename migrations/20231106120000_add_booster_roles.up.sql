@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE booster_roles (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, user_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN booster_role_anchor_id BIGINT
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS member_xp_leaderboard;
+DROP TABLE IF EXISTS member_xp;
+DROP TABLE IF EXISTS xp_multipliers;
+DROP TABLE IF EXISTS leveling_settings
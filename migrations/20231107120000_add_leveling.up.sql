@@ -0,0 +1,29 @@
+-- Add up migration script here
+CREATE TABLE leveling_settings (
+    guild_id BIGINT NOT NULL UNIQUE,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    min_xp INTEGER NOT NULL DEFAULT 15,
+    max_xp INTEGER NOT NULL DEFAULT 25,
+    cooldown_seconds INTEGER NOT NULL DEFAULT 60,
+    curve TINYINT NOT NULL DEFAULT 1,
+    curve_expression TEXT
+);
+
+CREATE TABLE xp_multipliers (
+    guild_id BIGINT NOT NULL,
+    target_id BIGINT NOT NULL,
+    kind TINYINT NOT NULL,
+    multiplier REAL NOT NULL,
+
+    PRIMARY KEY (guild_id, target_id)
+);
+
+CREATE TABLE member_xp (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    xp BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE INDEX member_xp_leaderboard ON member_xp (guild_id, xp DESC)
@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE leveling_settings DROP COLUMN levelup_message;
+ALTER TABLE leveling_settings DROP COLUMN levelup_channel_id;
+ALTER TABLE leveling_settings DROP COLUMN reward_mode;
+DROP TABLE IF EXISTS level_rewards
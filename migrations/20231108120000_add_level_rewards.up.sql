@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE level_rewards (
+    guild_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+    level INTEGER NOT NULL,
+
+    PRIMARY KEY (guild_id, role_id)
+);
+
+ALTER TABLE leveling_settings ADD COLUMN reward_mode TINYINT NOT NULL DEFAULT 0;
+ALTER TABLE leveling_settings ADD COLUMN levelup_channel_id BIGINT;
+ALTER TABLE leveling_settings ADD COLUMN levelup_message TEXT
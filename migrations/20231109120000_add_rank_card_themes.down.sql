@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE leveling_settings DROP COLUMN card_text;
+ALTER TABLE leveling_settings DROP COLUMN card_accent;
+ALTER TABLE leveling_settings DROP COLUMN card_background
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE leveling_settings ADD COLUMN card_background INTEGER NOT NULL DEFAULT 1973806;
+ALTER TABLE leveling_settings ADD COLUMN card_accent INTEGER NOT NULL DEFAULT 9024762;
+ALTER TABLE leveling_settings ADD COLUMN card_text INTEGER NOT NULL DEFAULT 13489908
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS prune_exempt_roles;
+DROP TABLE IF EXISTS prune_policies;
+DROP TABLE IF EXISTS member_activity
@@ -0,0 +1,27 @@
+-- Add up migration script here
+CREATE TABLE member_activity (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    last_active BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE prune_policies (
+    guild_id BIGINT NOT NULL,
+    id INTEGER NOT NULL,
+    inactive_seconds BIGINT NOT NULL,
+    action TINYINT NOT NULL,
+    role_id BIGINT,
+    created_at BIGINT NOT NULL,
+    last_run BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, id)
+);
+
+CREATE TABLE prune_exempt_roles (
+    guild_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, role_id)
+)
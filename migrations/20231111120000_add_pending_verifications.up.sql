@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE TABLE pending_verifications (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    deadline BIGINT NOT NULL,
+
+    PRIMARY KEY (guild_id, user_id)
+)